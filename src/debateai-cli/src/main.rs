@@ -2,33 +2,68 @@
 //!
 //! A command-line tool for running AI debates between multiple LLM participants.
 
-use clap::{ArgAction, Parser};
+mod profile;
+mod util;
+
+use clap::{ArgAction, Parser, Subcommand};
 use colored::Colorize;
 use debateai_core::{
-    AIParticipant, Config, DebateConfig, DebateEvent, DebateOrchestrator, DebateTts,
-    ParticipantRole, VoicesConfig, adjust_audio_speed, combine_audio_segments, debate_format,
-    generate_output_filename,
+    AIParticipant, AudioBudget, Config, DebateConfig, DebateError, DebateEvent, DebateFormat,
+    DebateManifest, DebateMessage, DebateOrchestrator, DebateSection, DebateTts, FilteredFormat,
+    FrontMatter, JudgeConfig, JudgeVerdict, MAX_TOPIC_CHARS, MarkdownStripping, ModelAliases,
+    OpenAIBackend, ParticipantRole, PresidentialDebateFormat, Pronunciations, RedactionMode,
+    SPEECH_WORDS_PER_MINUTE, SanitizeConfig, SectionTokens, SegmentGaps, TtsTiming, VoicesConfig,
+    WavStreamWriter, adjust_audio_speed, build_chapters, check_voices, debate_format,
+    estimate_speech_samples, estimate_speech_seconds, generate_output_filename, merge_consecutive,
+    mix_background, normalize_topic, parse_json, read_wav_mono, redact, redact_transcript,
+    render_csv, render_html, render_json, render_markdown, replay_transcript, resample_linear,
+    today_iso_date, transcript_schema,
 };
+use profile::{AudioTimings, RunProfile};
 use std::env;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use util::textwrap;
 
 #[derive(Parser)]
 #[command(
     name = "debateai",
     version,
     about = "AI Debate Tool - Watch AIs debate topics",
-    long_about = "A CLI tool for running debates between AI participants using OpenAI-compatible APIs."
+    long_about = "A CLI tool for running debates between AI participants using OpenAI-compatible APIs.",
+    subcommand_negates_reqs = true,
+    after_help = "Exit codes:\n  \
+        0  Success\n  \
+        1  Other error\n  \
+        2  Configuration error\n  \
+        3  API error\n  \
+        4  A participant kept returning empty responses\n  \
+        5  Text-to-speech error\n  \
+        6  A participant refused to take a stance (--fail-fast-on-refusal)"
 )]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// The topic to debate
-    #[arg(value_name = "TOPIC")]
-    topic: String,
+    #[arg(
+        value_name = "TOPIC",
+        required_unless_present_any = ["dump_config", "transcript_in"]
+    )]
+    topic: Option<String>,
 
     /// Model names for participants (specify once per participant)
     /// For presidential format, specify exactly 2 models: -m model1 -m model2
     #[arg(short, long, action = ArgAction::Append, value_name = "MODEL")]
     model: Vec<String>,
 
+    /// TOML or JSON file mapping short model aliases to full model IDs
+    #[arg(long, value_name = "FILE")]
+    aliases: Option<PathBuf>,
+
     /// Debate format to use
     #[arg(long, default_value = "presidential", value_name = "FORMAT")]
     debate_format: String,
@@ -37,16 +72,84 @@ struct Cli {
     #[arg(long, action = ArgAction::Append, value_name = "NAME")]
     name: Vec<String>,
 
-    /// Number of debate rounds (minimum 4)
-    #[arg(short, long, default_value = "6", value_name = "ROUNDS")]
-    rounds: u32,
+    /// Personas for the participants (optional, specify in same order as models)
+    /// Example: --persona "a fiscal conservative economist"
+    #[arg(long, action = ArgAction::Append, value_name = "PERSONA")]
+    persona: Vec<String>,
+
+    /// API base URL for the participants (optional, specify in same order as models)
+    /// Pass "" to keep the default (OPENAI_API_BASE) for a participant
+    #[arg(long, action = ArgAction::Append, value_name = "URL")]
+    endpoint: Vec<String>,
+
+    /// API key for the participants (optional, specify in same order as models)
+    /// Pass "" to keep the default (OPENAI_API_KEY) for a participant
+    #[arg(long, action = ArgAction::Append, value_name = "KEY")]
+    api_key: Vec<String>,
+
+    /// Context document for the participants (optional, specify in same order as models)
+    /// The file's text is given to that participant as reference material
+    #[arg(long, action = ArgAction::Append, value_name = "FILE")]
+    context_file: Vec<PathBuf>,
+
+    /// Context document given to every participant (repeatable)
+    #[arg(long, action = ArgAction::Append, value_name = "FILE")]
+    shared_context_file: Vec<PathBuf>,
+
+    /// Maximum characters of context documents given to each participant
+    #[arg(long, default_value_t = DebateConfig::DEFAULT_CONTEXT_CHAR_BUDGET, value_name = "CHARS")]
+    context_char_budget: usize,
+
+    /// Number of debate rounds [default: 6, minimum 4 for presidential].
+    /// Formats with a fixed number of rounds ignore this
+    #[arg(short, long, value_name = "ROUNDS")]
+    rounds: Option<u32>,
+
+    /// Most rounds a presidential debate may run; larger --rounds values are
+    /// reduced to this with a warning
+    #[arg(long, default_value_t = PresidentialDebateFormat::DEFAULT_MAX_ROUNDS, value_name = "ROUNDS")]
+    max_rounds: u32,
+
+    /// Token budget for opening statements (presidential format)
+    #[arg(long, default_value_t = PresidentialDebateFormat::DEFAULT_OPENING_TOKENS, value_name = "TOKENS")]
+    opening_tokens: u32,
+
+    /// Token budget for each main argument round (presidential format)
+    #[arg(long, default_value_t = PresidentialDebateFormat::DEFAULT_MAIN_TOKENS, value_name = "TOKENS")]
+    main_tokens: u32,
+
+    /// Token budget for rebuttals (presidential format)
+    #[arg(long, default_value_t = PresidentialDebateFormat::DEFAULT_REBUTTAL_TOKENS, value_name = "TOKENS")]
+    rebuttal_tokens: u32,
+
+    /// Token budget for closing statements (presidential format)
+    #[arg(long, default_value_t = PresidentialDebateFormat::DEFAULT_CLOSING_TOKENS, value_name = "TOKENS")]
+    closing_tokens: u32,
+
+    /// Run only these sections of the format (comma-separated, case-insensitive)
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    only_sections: Vec<String>,
+
+    /// Skip these sections of the format (comma-separated, case-insensitive)
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    skip_sections: Vec<String>,
+
+    /// Make this participant (0-based index) speak first in every section
+    #[arg(long, value_name = "INDEX")]
+    first_speaker: Option<usize>,
+
+    /// Model for the closing section, resolved through --aliases. Given once,
+    /// every participant switches to it, so it must be served at each
+    /// participant's endpoint; repeat it to give one per --model instead
+    #[arg(long, value_name = "MODEL")]
+    closing_model: Vec<String>,
 
     /// Output directory for audio files (default: current directory)
     #[arg(short, long, default_value = ".", value_name = "DIR")]
     output_dir: PathBuf,
 
-    /// Disable audio output (text-only mode)
-    #[arg(long)]
+    /// Disable audio output (text-only mode, the TTS engine is never loaded)
+    #[arg(long, visible_alias = "transcript-only")]
     disable_audio: bool,
 
     /// Maximum reasoning tokens for models (0 = model default, -1 = unlimited)
@@ -70,469 +173,2113 @@ struct Cli {
     /// Lower values sound more measured/deliberate for debates
     #[arg(long, default_value = "0.75", value_name = "RATE")]
     speech_rate: f32,
+
+    /// Resample the saved audio to this rate in Hz (the TTS engine produces 24000)
+    #[arg(long, value_name = "HZ", value_parser = clap::value_parser!(u32).range(1..))]
+    audio_sample_rate: Option<u32>,
+
+    /// Mix this WAV file under the voices, looped or cut to the audio's length
+    #[arg(long, value_name = "WAV")]
+    background: Option<PathBuf>,
+
+    /// Gain applied to the --background track (1.0 = unchanged)
+    #[arg(long, default_value = "0.1", value_name = "GAIN", value_parser = parse_background_gain)]
+    background_gain: f32,
+
+    /// Stop the audio at a segment boundary once it would exceed this many minutes
+    #[arg(long, value_name = "MINUTES", value_parser = parse_max_audio_minutes)]
+    max_audio_minutes: Option<f32>,
+
+    /// Keep only minimal silence between chunks and messages in the audio
+    #[arg(long)]
+    tight_audio: bool,
+
+    /// Seconds of silence before a speaker who also gave the previous turn
+    #[arg(long, default_value_t = SegmentGaps::default().same_speaker_secs, value_name = "SECONDS")]
+    same_speaker_gap: f32,
+
+    /// Seconds of silence before a turn by a different speaker
+    #[arg(long, default_value_t = SegmentGaps::default().speaker_change_secs, value_name = "SECONDS")]
+    speaker_change_gap: f32,
+
+    /// Also write a `.chapters.txt` file with a chapter marker per section
+    #[arg(long)]
+    chapters: bool,
+
+    /// Print a breakdown of where the run's time went (API requests per
+    /// section, TTS synthesis, saving)
+    #[arg(long)]
+    profile: bool,
+
+    /// Speaking rate used to estimate the audio length before synthesis
+    #[arg(long, default_value_t = SPEECH_WORDS_PER_MINUTE, value_name = "WPM")]
+    speech_wpm: f32,
+
+    /// TOML or JSON file mapping words to phonetic respellings for TTS
+    /// (e.g. `LLM = "el el em"`); words match case-insensitively and whole
+    #[arg(long, value_name = "FILE")]
+    pronunciation: Option<PathBuf>,
+
+    /// Silence in place of a message that fails to synthesize: a number of
+    /// seconds, or "auto" to match the time the text would take to speak
+    #[arg(long, value_name = "SECONDS|auto", default_value = "1", value_parser = parse_failure_silence)]
+    failure_silence: FailureSilence,
+
+    /// Switch to a matching voice for messages not written in English
+    #[arg(long)]
+    auto_language_voice: bool,
+
+    /// When a response is cut off by the token limit, request a short continuation
+    /// so it ends on a complete sentence
+    #[arg(long)]
+    finish_truncated: bool,
+
+    /// Continue a response cut off by the token limit with up to N further
+    /// requests, joining the parts into one turn
+    #[arg(long, default_value_t = 0, value_name = "N")]
+    max_continuations: u32,
+
+    /// Keep only the last N messages of each participant's history, plus the
+    /// system prompt, context documents and opening statements, which are pinned
+    #[arg(long, value_name = "N")]
+    history_window: Option<usize>,
+
+    /// Re-prompt a speaker once when a response is at least this similar (0.0-1.0)
+    /// to one of their recent responses
+    #[arg(long, value_name = "THRESHOLD")]
+    repetition_threshold: Option<f32>,
+
+    /// Abort the debate when a speaker refuses to take a stance, instead of
+    /// re-prompting them
+    #[arg(long)]
+    fail_fast_on_refusal: bool,
+
+    /// How each statement is shown to the other participants; `{name}` and
+    /// `{content}` are replaced with the speaker and their statement
+    #[arg(long, value_name = "TEMPLATE", default_value = DebateConfig::DEFAULT_OPPONENT_MESSAGE_TEMPLATE)]
+    opponent_template: String,
+
+    /// Strip emoji and uncommon symbols from text before TTS synthesis
+    #[arg(long)]
+    clean_tts_text: bool,
+
+    /// Expand abbreviations, numbers and currency to words before TTS synthesis
+    #[arg(long)]
+    tts_normalize: bool,
+
+    /// Save the transcript to a file; the format follows the extension (.md, .html, .json, .csv)
+    #[arg(long, value_name = "PATH")]
+    save_transcript: Option<PathBuf>,
+
+    /// Omit the YAML front-matter from Markdown transcripts
+    #[arg(long)]
+    no_front_matter: bool,
+
+    /// Merge a participant's back-to-back turns into one block in the saved
+    /// transcript
+    #[arg(long)]
+    merge_turns: bool,
+
+    /// Save the debate setup to a JSON manifest for use with `debateai rematch`
+    #[arg(long, value_name = "PATH")]
+    save_manifest: Option<PathBuf>,
+
+    /// Replace model names in the saved manifest with "Model 1", "Model 2", ...
+    /// so results can be shared without revealing which model is which
+    #[arg(long, requires = "save_manifest")]
+    anonymize_models: bool,
+
+    /// Allow running without OPENAI_API_KEY against a non-local endpoint
+    #[arg(long)]
+    allow_no_key: bool,
+
+    /// Pause this many milliseconds before each speaker, to pace live output
+    /// (ignored when output is not a terminal)
+    #[arg(long, value_name = "MS")]
+    turn_delay: Option<u64>,
+
+    /// Redact this term from the saved transcript (repeatable); matches
+    /// whole words, case-insensitively
+    #[arg(long, value_name = "TERM")]
+    redact: Vec<String>,
+
+    /// Text that replaces redacted terms
+    #[arg(long, value_name = "TEXT", default_value = "[REDACTED]")]
+    redact_with: String,
+
+    /// Redact terms even inside other words
+    #[arg(long)]
+    redact_substrings: bool,
+
+    /// Skip the confirmation prompt before long debates
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Run the debate a second time with the models' sides and speaking
+    /// order swapped, then compare the runs. The second run's files get a
+    /// "-swapped" suffix
+    #[arg(long)]
+    compare: bool,
+
+    /// Treat warnings (missing API key, adjusted rounds, failed audio segments) as errors
+    #[arg(long)]
+    strict: bool,
+
+    /// How aggressively responses are cleaned up: "strict" also strips
+    /// meta-commentary tags, every asterisk and AI disclaimers; "none" keeps
+    /// the raw output
+    #[arg(
+        long,
+        default_value = "lenient",
+        value_name = "PRESET",
+        value_parser = clap::builder::PossibleValuesParser::new(SanitizeConfig::PRESETS)
+    )]
+    sanitize_preset: String,
+
+    /// Remove every asterisk from responses, not just paired *emphasis* markers
+    #[arg(long)]
+    strip_all_asterisks: bool,
+
+    /// Keep ``` code fence lines in responses instead of unwrapping the code blocks
+    #[arg(long)]
+    keep_code_fences: bool,
+
+    /// Retries after a failed API request (at most 10)
+    #[arg(
+        long,
+        default_value_t = OpenAIBackend::DEFAULT_RETRIES,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(0..=OpenAIBackend::MAX_RETRIES as i64)
+    )]
+    api_retries: u32,
+
+    /// Retries after an empty response (each retry gets its own API retries)
+    #[arg(long, default_value_t = DebateConfig::DEFAULT_EMPTY_RETRIES, value_name = "N")]
+    empty_retries: u32,
+
+    /// File of house rules added to every participant's system prompt
+    #[arg(long, value_name = "FILE")]
+    rules_file: Option<PathBuf>,
+
+    /// Date the debate takes place on, given to the models so they argue
+    /// about events as of that day; replaces {date} in system prompts
+    /// [default: today]
+    #[arg(long, value_name = "DATE")]
+    debate_date: Option<String>,
+
+    /// Send each model a throwaway request before the debate to warm up the backend
+    #[arg(long)]
+    warmup: bool,
+
+    /// Keep each response as the model sent it, before sanitizing, in the
+    /// JSON transcript (as raw_content)
+    #[arg(long)]
+    keep_raw: bool,
+
+    /// Have the announcer recap the debate at its halfway point (written by
+    /// the first model)
+    #[arg(long)]
+    halftime_recap: bool,
+
+    /// Open each section with a moderator question, written by the first
+    /// model from the config's moderator_prompt
+    #[arg(long)]
+    moderator: bool,
+
+    /// Have a model score the debate after it ends, on the criteria in the
+    /// config's [judge] section
+    #[arg(long)]
+    judge: bool,
+
+    /// Model that judges the debate, resolved through --aliases
+    /// [default: the first participant's model]
+    #[arg(long, value_name = "MODEL", requires = "judge")]
+    judge_model: Option<String>,
+
+    /// API base URL to ask the judge at, with the main API key
+    /// [default: the first participant's endpoint]
+    #[arg(long, value_name = "URL", requires = "judge")]
+    judge_endpoint: Option<String>,
+
+    /// Weight of a judging criterion, added if not already listed (repeatable),
+    /// e.g. --judge-weight evidence=2
+    #[arg(long, value_name = "CRITERION=WEIGHT", value_parser = parse_judge_weight, requires = "judge")]
+    judge_weight: Vec<(String, f32)>,
+
+    /// Ask the judge for strict JSON (OpenAI JSON mode); endpoints that don't
+    /// support it are asked again without it
+    #[arg(long, requires = "judge")]
+    json_mode: bool,
+
+    /// Print the exact messages sent to the model before each turn
+    #[arg(long)]
+    debug_prompts: bool,
+
+    /// Write each participant's system prompt to a file in this directory
+    #[arg(long, value_name = "DIR")]
+    dump_prompts: Option<PathBuf>,
+
+    /// Exit after writing the prompts, without running the debate
+    #[arg(long, requires = "dump_prompts")]
+    dump_only: bool,
+
+    /// Print full API error details (provider message, response body) on failure
+    #[arg(long)]
+    verbose_errors: bool,
+
+    /// Only print the debate itself, results, warnings and errors: no banners,
+    /// audio progress, turn pacing or closing summary
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Print the effective configuration (config file, overrides, API settings) and exit
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        default_missing_value = "toml",
+        value_parser = ["toml", "json"]
+    )]
+    dump_config: Option<String>,
+
+    /// Replay a saved JSON transcript through the console output instead of
+    /// running a debate
+    #[arg(long, value_name = "FILE")]
+    transcript_in: Option<PathBuf>,
+
+    /// Disable colored output (also honored via the NO_COLOR environment variable)
+    #[arg(long)]
+    no_color: bool,
+}
+
+impl Cli {
+    /// Whether the run should synthesize audio after the debate.
+    fn audio_enabled(&self) -> bool {
+        !self.disable_audio
+    }
+
+    /// Fill in a subcommand's options from the same options given before the
+    /// subcommand name, so `debateai --config x.toml check` checks x.toml.
+    /// Options given after the subcommand name win.
+    fn inherit_main_options(mut self) -> Self {
+        match &mut self.command {
+            Some(Command::Ping { model }) => {
+                if model.is_none() {
+                    *model = self.model.first().cloned();
+                }
+            }
+            #[cfg(feature = "server")]
+            Some(Command::Serve {
+                rounds,
+                allow_no_key,
+                strict,
+                ..
+            }) => {
+                *rounds = rounds.or(self.rounds);
+                *allow_no_key |= self.allow_no_key;
+                *strict |= self.strict;
+            }
+            Some(Command::Check {
+                config,
+                model,
+                debate_format,
+                ..
+            }) => {
+                if config.is_none() {
+                    config.clone_from(&self.config);
+                }
+                if model.is_empty() {
+                    model.clone_from(&self.model);
+                }
+                if debate_format.is_none() {
+                    *debate_format = Some(self.debate_format.clone());
+                }
+            }
+            Some(Command::Schema { .. } | Command::Rematch { .. }) | None => {}
+        }
+        self
+    }
+}
+
+/// Model the `ping` subcommand uses when none is given.
+const DEFAULT_PING_MODEL: &str = "gpt-4o-mini";
+
+/// Subcommands. Options of the main command go before the subcommand name
+/// (`debateai --disable-audio rematch ...`) and apply where the subcommand
+/// runs a debate. Where a subcommand has an option of its own by the same
+/// name, a value given before the subcommand is used if it has none.
+#[derive(Subcommand)]
+enum Command {
+    /// Check that the configured endpoint is reachable and the API key works
+    Ping {
+        /// Model to send the test request to [default: gpt-4o-mini]
+        #[arg(short, long, value_name = "MODEL")]
+        model: Option<String>,
+    },
+    /// Serve debates over HTTP, streaming events as Server-Sent Events
+    #[cfg(feature = "server")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080", value_name = "ADDR")]
+        listen: String,
+        /// Number of rounds for each debate [default: 6]
+        #[arg(short, long, value_name = "ROUNDS")]
+        rounds: Option<u32>,
+        /// Allow serving without OPENAI_API_KEY against a non-local endpoint
+        #[arg(long)]
+        allow_no_key: bool,
+        /// Treat a missing API key as an error even with --allow-no-key
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Print the JSON Schema of a saved transcript or manifest
+    Schema {
+        /// Which file to describe
+        #[arg(value_parser = ["transcript", "manifest"], default_value = "transcript")]
+        kind: String,
+    },
+    /// Re-run a saved debate (participants, format and voices) on a new topic.
+    /// Run options go before `rematch`, e.g. `debateai --disable-audio rematch`
+    Rematch {
+        /// Manifest written by a previous run with --save-manifest
+        #[arg(value_name = "MANIFEST")]
+        manifest: PathBuf,
+        /// The new topic to debate
+        #[arg(value_name = "TOPIC")]
+        topic: String,
+    },
+    /// Validate the config, format, participants and voices without running a debate
+    Check {
+        /// Configuration file (defaults to config.toml if present)
+        #[arg(short, long, value_name = "FILE")]
+        config: Option<PathBuf>,
+        /// Model names for participants (specify once per participant);
+        /// the participant count is checked when given
+        #[arg(short, long, action = ArgAction::Append, value_name = "MODEL")]
+        model: Vec<String>,
+        /// Debate format to check the participants against [default: presidential]
+        #[arg(long, value_name = "FORMAT")]
+        debate_format: Option<String>,
+        /// Also send a test request to each model
+        #[arg(long)]
+        ping: bool,
+    },
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> ExitCode {
     // Load environment variables from .env file if present
     dotenvy::dotenv().ok();
 
-    let cli = Cli::parse();
+    let cli = Cli::parse().inherit_main_options();
 
-    // Load configuration
-    let mut config = if let Some(config_path) = &cli.config {
-        Config::load(config_path)?
-    } else if PathBuf::from("config.toml").exists() {
-        Config::load("config.toml")?
-    } else {
-        debateai_core::config::default_config()
-    };
+    // Plain output when requested or when NO_COLOR is set
+    if !color_enabled(cli.no_color, env::var_os("NO_COLOR")) {
+        colored::control::set_override(false);
+    }
 
-    // Override voices from CLI if provided
-    if let Some(for_voice) = cli.voice.first() {
-        config.voices.for_voice = for_voice.clone();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            ExitCode::from(exit_code(e.as_ref()))
+        }
     }
-    if let Some(against_voice) = cli.voice.get(1) {
-        config.voices.against_voice = against_voice.clone();
+}
+
+/// Exit code for I/O and other unexpected errors.
+const EXIT_OTHER: u8 = 1;
+/// Exit code for invalid configuration or arguments.
+const EXIT_CONFIG: u8 = 2;
+/// Exit code for failed API requests.
+const EXIT_API: u8 = 3;
+/// Exit code for a participant that kept returning empty responses.
+const EXIT_EMPTY_RESPONSE: u8 = 4;
+/// Exit code for text-to-speech failures.
+const EXIT_TTS: u8 = 5;
+/// Exit code for a participant that refused to take a stance.
+const EXIT_REFUSAL: u8 = 6;
+
+/// Process exit code for an error returned by [`run`], as listed in `--help`.
+///
+/// The CLI reports its own validation failures (unknown format, missing API
+/// key, bad topic, ...) as plain messages, so those count as configuration
+/// errors.
+fn exit_code(err: &(dyn std::error::Error + 'static)) -> u8 {
+    if let Some(err) = err.downcast_ref::<DebateError>() {
+        exit_code_for(err)
+    } else if err.is::<std::io::Error>() {
+        EXIT_OTHER
+    } else {
+        EXIT_CONFIG
     }
-    if let Some(announcer) = &cli.announcer_voice {
-        config.voices.announcer_voice = announcer.clone();
+}
+
+/// Process exit code for a debate error.
+fn exit_code_for(err: &DebateError) -> u8 {
+    match err {
+        DebateError::ConfigError(_)
+        | DebateError::UnknownFormat(_)
+        | DebateError::InvalidParticipantCount { .. }
+        | DebateError::DuplicateParticipantName(_) => EXIT_CONFIG,
+        DebateError::InvalidVerdict(_) => EXIT_OTHER,
+        DebateError::OpenAIError(_) => EXIT_API,
+        DebateError::EmptyResponse(_) => EXIT_EMPTY_RESPONSE,
+        DebateError::TtsError(_) => EXIT_TTS,
+        DebateError::Refusal { .. } => EXIT_REFUSAL,
+        DebateError::IoError(_) => EXIT_OTHER,
     }
+}
 
-    // Get API configuration from environment
-    let api_base = env::var("OPENAI_API_BASE")
-        .or_else(|_| env::var("OPENAI_BASE_URL"))
-        .unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
+/// Run the command given on the command line.
+async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    match &cli.command {
+        Some(Command::Ping { model }) => {
+            return run_ping(model.as_deref().unwrap_or(DEFAULT_PING_MODEL)).await;
+        }
+        #[cfg(feature = "server")]
+        Some(Command::Serve {
+            listen,
+            rounds,
+            allow_no_key,
+            strict,
+        }) => {
+            let (api_base, api_key) = resolve_api_settings(*allow_no_key, *strict)?;
+            let listener = tokio::net::TcpListener::bind(listen).await?;
+            println!(
+                "{} http://{}/debate?topic=...&model=...&model=...",
+                "Serving debates at".bold(),
+                listener.local_addr()?
+            );
+            debateai_core::serve(
+                listener,
+                debateai_core::ServerConfig::new(api_base, api_key)
+                    .with_rounds(rounds.unwrap_or(debate_format::DEFAULT_ROUNDS)),
+            )
+            .await?;
+            return Ok(());
+        }
+        Some(Command::Schema { kind }) => {
+            match kind.as_str() {
+                "manifest" => println!("{}", DebateManifest::json_schema()),
+                _ => println!("{}", transcript_schema()),
+            }
+            return Ok(());
+        }
+        Some(Command::Rematch { manifest, topic }) => {
+            let manifest = DebateManifest::load(manifest)?;
+            let mut config = load_config(&cli)?;
+            config.voices = manifest.voices.clone();
+            let format = manifest.build_format()?;
+            let topic = prepare_topic(topic, cli.strict)?;
+            let outputs = RunOutputs::new(&cli, &topic);
+            return run_debate(
+                &cli,
+                &config,
+                &topic,
+                format,
+                manifest.participants,
+                &outputs,
+            )
+            .await
+            .map(|_| ());
+        }
+        Some(Command::Check {
+            config,
+            model,
+            debate_format,
+            ping,
+        }) => {
+            let format_name = debate_format.as_deref().unwrap_or(&cli.debate_format);
+            return run_check(config.as_deref(), model, format_name, *ping).await;
+        }
+        None => {}
+    }
 
-    let api_key = env::var("OPENAI_API_KEY").unwrap_or_else(|_| {
-        eprintln!(
-            "{}",
-            "Warning: OPENAI_API_KEY not set. API calls may fail.".yellow()
-        );
-        String::new()
-    });
+    if let Some(dump_format) = &cli.dump_config {
+        println!("{}", dump_config(&cli, dump_format == "json")?);
+        return Ok(());
+    }
 
-    // Validate rounds
-    let rounds = cli.rounds.max(4);
-    if cli.rounds < 4 {
-        eprintln!(
-            "{}",
-            format!(
-                "Warning: Rounds increased to minimum of 4 (was {}).",
-                cli.rounds
-            )
-            .yellow()
-        );
+    if let Some(path) = &cli.transcript_in {
+        return replay_saved_transcript(path, cli.quiet);
     }
 
-    // Get the debate format
-    let format = debate_format::get_format(&cli.debate_format, rounds).ok_or_else(|| {
-        format!(
-            "Unknown debate format: '{}'. Available formats: {}",
-            cli.debate_format,
-            debate_format::available_formats().join(", ")
-        )
-    })?;
+    let topic = prepare_topic(
+        cli.topic
+            .as_deref()
+            .expect("clap requires TOPIC when no subcommand is given"),
+        cli.strict,
+    )?;
+    let topic = topic.as_str();
+
+    let config = load_config(&cli)?;
+
+    // Get the debate format, and say if it could not use the requested rounds
+    let format = cli_format(&cli)?;
+    let rounds = format.rounds().unwrap_or_default();
+    if let Some(requested) = cli.rounds
+        && format.rounds() != Some(requested)
+    {
+        warn(
+            cli.strict,
+            match format.rounds() {
+                Some(rounds) => format!(
+                    "The {} format runs {} rounds (requested {}).",
+                    format.name(),
+                    rounds,
+                    requested
+                ),
+                None => format!(
+                    "The {} format has no rounds; --rounds is ignored.",
+                    format.name()
+                ),
+            },
+        )?;
+    }
 
     // Validate model count
     let min_participants = format.min_participants();
     let max_participants = format.max_participants();
 
-    if cli.model.len() < min_participants || cli.model.len() > max_participants {
-        eprintln!(
-            "{} The '{}' format requires {} to {} models, but {} were provided.",
-            "Error:".red().bold(),
-            cli.debate_format,
-            min_participants,
-            max_participants,
-            cli.model.len()
-        );
-        eprintln!(
-            "Usage: debateai \"{}\" {}",
-            cli.topic,
-            (0..min_participants)
-                .map(|i| format!("-m model{}", i + 1))
-                .collect::<Vec<_>>()
-                .join(" ")
-        );
-        std::process::exit(1);
-    }
-
-    // Create participants with voices from config
-    let default_names = vec![
-        "Candidate A".to_string(),
-        "Candidate B".to_string(),
-        "Candidate C".to_string(),
-        "Candidate D".to_string(),
-    ];
-    let roles = [
-        ParticipantRole::For,
-        ParticipantRole::Against,
-        ParticipantRole::For,
-        ParticipantRole::Against,
-    ];
+    if let Err(message) = check_participant_count(
+        &cli.debate_format,
+        cli.model.len(),
+        min_participants,
+        max_participants,
+    ) {
+        eprintln!("{} {}", "Error:".red().bold(), message);
+        if cli.model.len() < min_participants {
+            eprintln!(
+                "Usage: debateai \"{}\" {}",
+                topic,
+                (0..min_participants)
+                    .map(|i| format!("-m model{}", i + 1))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+        }
+        std::process::exit(EXIT_CONFIG.into());
+    }
+    check_per_participant_args(&cli)?;
+
+    let context_docs = read_context_files(&cli.context_file)?;
+    let aliases = load_aliases(&cli)?;
 
     let participants: Vec<AIParticipant> = cli
         .model
         .iter()
         .enumerate()
         .map(|(i, model)| {
-            let name = cli
-                .name
-                .get(i)
-                .cloned()
-                .unwrap_or_else(|| default_names[i % default_names.len()].clone());
-            let role = roles[i % roles.len()].clone();
-            let voice = config.get_voice(role == ParticipantRole::For).to_string();
-            AIParticipant::new(name, model.clone(), role).with_voice(voice)
+            let (default_name, role) = default_participant(i);
+            let name = cli.name.get(i).cloned().unwrap_or(default_name);
+            let voice = config.voices.voice_for_role(&role).to_string();
+            let model = aliases.resolve(model);
+            let mut participant = AIParticipant::new(name, model, role).with_voice(voice);
+            if let Some(api_base) = cli.endpoint.get(i).filter(|s| !s.is_empty()) {
+                participant = participant.with_endpoint(api_base);
+            }
+            if let Some(api_key) = cli.api_key.get(i).filter(|s| !s.is_empty()) {
+                participant = participant.with_api_key(api_key);
+            }
+            if let Some(doc) = context_docs.get(i) {
+                participant = participant.with_context_docs(vec![doc.clone()]);
+            }
+            match cli.persona.get(i) {
+                Some(persona) => participant.with_persona(persona),
+                None => participant,
+            }
         })
         .collect();
 
-    // Print header
-    println!();
-    println!("{}", "═".repeat(70).bright_blue());
-    println!(
-        "{}",
-        format!("  {} - {}", "DebateAI".bold(), format.display_name())
-            .bright_blue()
-            .bold()
-    );
-    println!("{}", "═".repeat(70).bright_blue());
-    println!();
-    println!("{} {}", "Topic:".bold(), cli.topic.bright_white());
-    println!();
-    println!("{}", "Participants:".bold());
-    for (i, p) in participants.iter().enumerate() {
-        println!(
-            "  {}. {} ({}) - using {}",
-            i + 1,
-            p.name.bright_cyan(),
-            p.role.display_name().yellow(),
-            p.model.dimmed()
+    if let Some(path) = &cli.save_manifest {
+        let manifest = DebateManifest::new(
+            topic,
+            format.as_ref(),
+            rounds,
+            participants.clone(),
+            config.voices.clone(),
+        )
+        .with_max_rounds(cli.max_rounds)
+        .with_section_tokens(SectionTokens {
+            opening: cli.opening_tokens,
+            main: cli.main_tokens,
+            rebuttal: cli.rebuttal_tokens,
+            closing: cli.closing_tokens,
+        })
+        .with_closing_models(
+            cli.closing_model
+                .iter()
+                .map(|model| aliases.resolve(model).to_string())
+                .collect(),
         );
+        let manifest = match cli.first_speaker {
+            Some(first) => manifest.with_first_speaker(first),
+            None => manifest,
+        };
+        if cli.anonymize_models {
+            manifest.anonymize_models().save(path)?;
+        } else {
+            manifest.save(path)?;
+        }
     }
 
-    if !cli.disable_audio {
-        println!();
+    let outputs = RunOutputs::new(&cli, topic);
+    if !cli.compare {
+        return run_debate(&cli, &config, topic, format, participants, &outputs)
+            .await
+            .map(|_| ());
+    }
+
+    // Run again with sides swapped, to control for side and speaking-order bias
+    let swapped = swap_sides(&participants, &config.voices);
+    let first = run_debate(&cli, &config, topic, format, participants.clone(), &outputs).await?;
+    let Some(first) = first else {
+        return Ok(());
+    };
+    let second = run_debate(
+        &cli,
+        &config,
+        topic,
+        cli_format(&cli)?,
+        swapped.clone(),
+        &outputs.with_suffix("swapped"),
+    )
+    .await?;
+    if let Some(second) = second {
         println!(
-            "{} {}",
-            "Audio Output:".bold(),
-            cli.output_dir.display().to_string().bright_green()
+            "{}",
+            comparison_report(&[(&participants, &first), (&swapped, &second)])
         );
     }
+    Ok(())
+}
 
-    println!();
-    println!("{}", "─".repeat(70).dimmed());
-
-    // Create debate configuration
-    let debate_config = DebateConfig::new(&cli.topic, api_base, api_key);
-
-    // Create orchestrator with event callback
-    let transcript_clone = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
-    let transcript_for_callback = transcript_clone.clone();
-
-    let callback = create_console_callback(transcript_for_callback);
-    let mut orchestrator = DebateOrchestrator::new(debate_config, participants.clone(), format)?
-        .with_callback(callback);
-
-    // Run the debate
-    let transcript = orchestrator.run().await?;
-
-    println!();
-    println!("{}", "═".repeat(70).bright_blue());
-    println!("{}", "  Debate concluded.".bright_green().bold());
-    println!("{}", "═".repeat(70).bright_blue());
+/// Build the debate format selected on the command line.
+///
+/// Without `--rounds`, each format runs its default number of rounds.
+fn cli_format(cli: &Cli) -> Result<Box<dyn DebateFormat>, String> {
+    let mut format: Box<dyn DebateFormat> =
+        if cli.debate_format.eq_ignore_ascii_case("presidential") {
+            Box::new(
+                PresidentialDebateFormat::default()
+                    .with_section_tokens(
+                        cli.opening_tokens,
+                        cli.main_tokens,
+                        cli.rebuttal_tokens,
+                        cli.closing_tokens,
+                    )
+                    .with_max_rounds(cli.max_rounds),
+            )
+        } else {
+            debate_format::get_format(&cli.debate_format, debate_format::DEFAULT_ROUNDS)
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown debate format: '{}'. Available formats: {}",
+                        cli.debate_format,
+                        debate_format::available_formats().join(", ")
+                    )
+                })?
+        };
+    if let Some(rounds) = cli.rounds {
+        format.set_rounds(rounds);
+    }
+    Ok(format)
+}
 
-    // Generate TTS output unless disabled
-    if !cli.disable_audio {
-        println!();
-        println!("{}", "Generating audio output...".bright_yellow());
+/// The participants with FOR and AGAINST swapped, in reverse speaking order,
+/// each with the voice for their new role.
+fn swap_sides(participants: &[AIParticipant], voices: &VoicesConfig) -> Vec<AIParticipant> {
+    participants
+        .iter()
+        .rev()
+        .map(|p| {
+            let role = match &p.role {
+                ParticipantRole::For => ParticipantRole::Against,
+                ParticipantRole::Against => ParticipantRole::For,
+                other => other.clone(),
+            };
+            let mut swapped = p.clone().with_voice(voices.voice_for_role(&role));
+            swapped.role = role;
+            swapped
+        })
+        .collect()
+}
 
-        // Create output directory if needed
-        std::fs::create_dir_all(&cli.output_dir)?;
-
-        // Initialize TTS engine
-        match DebateTts::new(config.voices.clone()).await {
-            Ok(mut tts) => {
-                // Synthesize each message with graceful degradation
-                let mut audio_segments: Vec<Vec<f32>> = Vec::new();
-                let mut failed_segments = 0;
-
-                // Sample rate for silence calculation
-                let sample_rate = 24000;
-                let section_pause_seconds = 2.0; // Pause between sections
-                let speaker_pause_seconds = 1.0; // Pause between speakers
-
-                // Macro to synthesize announcer text (avoids closure borrow issues)
-                macro_rules! synth_announcer {
-                    ($tts:expr, $text:expr, $label:expr, $segments:expr, $failed:expr) => {{
-                        print!("  Synthesizing {}...", $label);
-                        let _ = std::io::Write::flush(&mut std::io::stdout());
-                        match $tts.synthesize_announcer($text) {
-                            Ok(audio) => {
-                                println!(" {}", "✓".bright_green());
-                                $segments.push(audio);
-                                true
-                            }
-                            Err(e) => {
-                                println!(" {} ({})", "✗".bright_red(), e);
-                                $failed += 1;
-                                false
-                            }
-                        }
-                    }};
-                }
+/// Summarize each participant's side and word count across `--compare` runs.
+fn comparison_report(runs: &[(&[AIParticipant], &[DebateMessage])]) -> String {
+    let mut report = String::from("Side comparison:");
+    let Some((first_participants, _)) = runs.first() else {
+        return report;
+    };
 
-                // Get participant info for announcements
-                let for_participant = participants.iter().find(|p| p.role == ParticipantRole::For);
-                let against_participant = participants
+    for participant in first_participants.iter() {
+        let sides: Vec<String> = runs
+            .iter()
+            .enumerate()
+            .filter_map(|(run, (participants, transcript))| {
+                let index = participants
                     .iter()
-                    .find(|p| p.role == ParticipantRole::Against);
-
-                // === INTRO SECTION ===
-                // Welcome
-                synth_announcer!(
-                    tts,
-                    "Welcome to DebateAI.",
-                    "welcome",
-                    audio_segments,
-                    failed_segments
-                );
-                audio_segments.push(vec![0.0; (0.5 * sample_rate as f32) as usize]);
-
-                // Topic announcement
-                let topic_text = format!("Today's debate topic is: {}", cli.topic);
-                synth_announcer!(tts, &topic_text, "topic", audio_segments, failed_segments);
-                audio_segments.push(vec![
-                    0.0;
-                    (section_pause_seconds * sample_rate as f32) as usize
-                ]);
-
-                // Introduce FOR participant
-                if let Some(p) = for_participant {
-                    let intro_for = format!(
-                        "Arguing in favor, we have {}, powered by {}.",
-                        p.name, p.model
-                    );
-                    synth_announcer!(
-                        tts,
-                        &intro_for,
-                        &format!("{} intro", p.name),
-                        audio_segments,
-                        failed_segments
-                    );
-                    audio_segments.push(vec![
-                        0.0;
-                        (speaker_pause_seconds * sample_rate as f32) as usize
-                    ]);
-                }
+                    .position(|p| p.name == participant.name)?;
+                let words: usize = transcript
+                    .iter()
+                    .filter(|m| m.speaker_index == Some(index))
+                    .map(|m| m.content.split_whitespace().count())
+                    .sum();
+                Some(format!(
+                    "{} in run {} ({} words)",
+                    participants[index].role.display_name(),
+                    run + 1,
+                    words
+                ))
+            })
+            .collect();
+        report.push_str(&format!(
+            "\n  {} ({}): {}",
+            participant.name,
+            participant.model,
+            sides.join(", ")
+        ));
+    }
+    report
+}
 
-                // Introduce AGAINST participant
-                if let Some(p) = against_participant {
-                    let intro_against = format!(
-                        "Arguing against, we have {}, powered by {}.",
-                        p.name, p.model
-                    );
-                    synth_announcer!(
-                        tts,
-                        &intro_against,
-                        &format!("{} intro", p.name),
-                        audio_segments,
-                        failed_segments
-                    );
-                    audio_segments.push(vec![
-                        0.0;
-                        (section_pause_seconds * sample_rate as f32) as usize
-                    ]);
-                }
+/// Files written by a single debate run.
+struct RunOutputs {
+    /// Transcript file from `--save-transcript`.
+    transcript: Option<PathBuf>,
+    /// WAV file for the audio.
+    audio: PathBuf,
+}
 
-                // Let the debate begin
-                synth_announcer!(
-                    tts,
-                    "Let the debate begin.",
-                    "start",
-                    audio_segments,
-                    failed_segments
-                );
-                audio_segments.push(vec![
-                    0.0;
-                    (section_pause_seconds * sample_rate as f32) as usize
-                ]);
-
-                let mut current_section: Option<String> = None;
-
-                for message in &transcript {
-                    // Check if we're in a new section - add section announcement
-                    if current_section.as_ref() != Some(&message.section) {
-                        // Add pause before new section (except first)
-                        if current_section.is_some() {
-                            audio_segments.push(vec![
-                                0.0;
-                                (section_pause_seconds * sample_rate as f32)
-                                    as usize
-                            ]);
-                        }
-
-                        // Announce the new section with context
-                        let section_text = match message.section.as_str() {
-                            "Opening Statements" => "Opening Statements.".to_string(),
-                            "Rebuttals" => "Now, the rebuttals.".to_string(),
-                            "Closing Statements" => "And now, closing statements.".to_string(),
-                            s if s.starts_with("Main Arguments") => format!("{}.", s),
-                            s => format!("{}.", s),
-                        };
-
-                        synth_announcer!(
-                            tts,
-                            &section_text,
-                            &format!("section: {}", message.section),
-                            audio_segments,
-                            failed_segments
-                        );
-                        audio_segments.push(vec![
-                            0.0;
-                            (speaker_pause_seconds * sample_rate as f32)
-                                as usize
-                        ]);
-
-                        current_section = Some(message.section.clone());
-                    } else {
-                        // Add pause between speakers in same section
-                        audio_segments.push(vec![
-                            0.0;
-                            (speaker_pause_seconds * sample_rate as f32)
-                                as usize
-                        ]);
-                    }
+impl RunOutputs {
+    fn new(cli: &Cli, topic: &str) -> Self {
+        Self {
+            transcript: cli.save_transcript.clone(),
+            audio: cli.output_dir.join(generate_output_filename(topic)),
+        }
+    }
 
-                    // Announce the speaker before their turn
-                    let speaker_role = &participants[message.speaker_index].role;
-                    let speaker_intro = format!(
-                        "{}, speaking {}.",
-                        message.speaker_name,
-                        speaker_role.display_name().to_lowercase()
-                    );
-                    synth_announcer!(
-                        tts,
-                        &speaker_intro,
-                        &format!("{} turn", message.speaker_name),
-                        audio_segments,
-                        failed_segments
-                    );
-                    audio_segments.push(vec![0.0; (0.5 * sample_rate as f32) as usize]);
-
-                    let role = &participants[message.speaker_index].role;
-                    print!(
-                        "  Synthesizing {} ({})...",
-                        message.speaker_name.bright_cyan(),
-                        message.section
-                    );
-                    std::io::Write::flush(&mut std::io::stdout())?;
-
-                    match tts.synthesize_message(message, role) {
-                        Ok(audio) => {
-                            audio_segments.push(audio);
-                            println!(" {}", "✓".bright_green());
-                        }
-                        Err(e) => {
-                            failed_segments += 1;
-                            println!(" {} ({})", "✗".bright_red(), e);
-                            // Add silence instead of failing completely
-                            audio_segments.push(vec![0.0; sample_rate]); // 1 second of silence
-                        }
-                    }
-                }
+    /// The same outputs with `-{suffix}` added to each file name.
+    fn with_suffix(&self, suffix: &str) -> Self {
+        Self {
+            transcript: self
+                .transcript
+                .as_deref()
+                .map(|p| path_with_suffix(p, suffix)),
+            audio: path_with_suffix(&self.audio, suffix),
+        }
+    }
+}
 
-                // Add closing announcement
-                let outro_text = "This concludes today's debate. Thank you for listening.";
-                audio_segments.push(vec![
-                    0.0;
-                    (section_pause_seconds * sample_rate as f32) as usize
-                ]);
-                print!("  Synthesizing outro announcement...");
-                std::io::Write::flush(&mut std::io::stdout())?;
-                match tts.synthesize_announcer(outro_text) {
-                    Ok(audio) => {
-                        audio_segments.push(audio);
-                        println!(" {}", "✓".bright_green());
-                    }
-                    Err(e) => {
-                        failed_segments += 1;
-                        println!(" {} ({})", "✗".bright_red(), e);
-                    }
-                }
+/// Insert `-{suffix}` between a file's stem and its extension.
+fn path_with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, suffix, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, suffix),
+    };
+    path.with_file_name(name)
+}
 
-                if failed_segments > 0 {
-                    println!(
-                        "{}",
-                        format!(
-                            "  Warning: {} segment(s) failed to synthesize",
-                            failed_segments
-                        )
-                        .yellow()
-                    );
-                }
+/// Check the number of models against the format's participant limits.
+fn check_participant_count(
+    format: &str,
+    count: usize,
+    min: usize,
+    max: usize,
+) -> Result<(), String> {
+    if count > max {
+        Err(format!(
+            "The '{}' format allows at most {} models, but {} were provided.",
+            format, max, count
+        ))
+    } else if count < min {
+        Err(format!(
+            "The '{}' format requires at least {} models, but {} were provided.",
+            format, min, count
+        ))
+    } else {
+        Ok(())
+    }
+}
 
-                if !audio_segments.is_empty() {
-                    // Combine segments (pauses are already added inline)
-                    println!("  Combining audio segments...");
-                    let combined = combine_audio_segments(audio_segments, 0.0, 24000);
-
-                    // Apply speech rate adjustment
-                    let adjusted = if cli.speech_rate != 1.0 {
-                        println!("  Adjusting speech rate to {}x...", cli.speech_rate);
-                        adjust_audio_speed(combined, cli.speech_rate)
-                    } else {
-                        combined
-                    };
-
-                    // Save to file
-                    let filename = generate_output_filename(&cli.topic);
-                    let output_path = cli.output_dir.join(&filename);
-
-                    match tts.save_wav(&output_path, &adjusted) {
-                        Ok(_) => {
-                            println!();
-                            println!(
-                                "{} {}",
-                                "Audio saved:".bright_green().bold(),
-                                output_path.display().to_string().bright_white()
-                            );
-                        }
-                        Err(e) => {
-                            println!();
-                            println!("{} {}", "Failed to save audio:".red().bold(), e);
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                println!("{} {}", "TTS initialization failed:".red().bold(), e);
-                println!(
-                    "{}",
-                    "Skipping audio generation. Debate transcript completed successfully.".yellow()
-                );
-            }
+/// Check that no per-participant option is given more often than `--model`,
+/// as the extra values would otherwise be ignored.
+fn check_per_participant_args(cli: &Cli) -> Result<(), String> {
+    let counts = [
+        ("--name", cli.name.len()),
+        ("--persona", cli.persona.len()),
+        ("--endpoint", cli.endpoint.len()),
+        ("--api-key", cli.api_key.len()),
+        ("--context-file", cli.context_file.len()),
+        ("--voice", cli.voice.len()),
+        ("--closing-model", cli.closing_model.len()),
+    ];
+    let models = cli.model.len();
+    for (option, count) in counts {
+        if count > models {
+            return Err(format!(
+                "{} was given {} times but there {} only {} --model{}; give at most one per model",
+                option,
+                count,
+                if models == 1 { "is" } else { "are" },
+                models,
+                if models == 1 { "" } else { "s" }
+            ));
         }
     }
+    Ok(())
+}
 
-    println!();
+/// Default name and role for the participant at `index`.
+///
+/// Roles alternate FOR, AGAINST, FOR, ... and names run Candidate A, B, C, ...
+fn default_participant(index: usize) -> (String, ParticipantRole) {
+    let role = if index.is_multiple_of(2) {
+        ParticipantRole::For
+    } else {
+        ParticipantRole::Against
+    };
+    let letter = char::from(b'A' + (index % 26) as u8);
+    (format!("Candidate {}", letter), role)
+}
 
-    Ok(())
+/// The merged configuration and API settings, serialized for `--dump-config`.
+fn dump_config(cli: &Cli, json: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let config = load_config(cli)?;
+    let api_key = env::var("OPENAI_API_KEY").unwrap_or_default();
+    Ok(config.dump(&api_base_from_env(), &api_key, json)?)
 }
 
-/// Create a callback that prints debate events to the console.
-fn create_console_callback(
-    _transcript: std::sync::Arc<std::sync::Mutex<Vec<debateai_core::DebateMessage>>>,
-) -> Box<dyn Fn(DebateEvent) + Send + Sync> {
-    Box::new(move |event| match event {
-        DebateEvent::SectionStart { name, description } => {
-            println!();
-            println!("{}", "═".repeat(70).bright_magenta());
-            println!(
-                "{}",
-                format!("  📢 ANNOUNCER: {}", name).bright_magenta().bold()
-            );
+/// Load the config file (or the built-in default) and apply voice overrides.
+fn load_config(cli: &Cli) -> Result<Config, Box<dyn std::error::Error>> {
+    let mut config = read_config(cli.config.as_deref())?;
+
+    // Override voices from CLI if provided
+    if let Some(for_voice) = cli.voice.first() {
+        config.voices.for_voice = for_voice.clone();
+    }
+    if let Some(against_voice) = cli.voice.get(1) {
+        config.voices.against_voice = against_voice.clone();
+    }
+    if let Some(announcer) = &cli.announcer_voice {
+        config.voices.announcer_voice = announcer.clone();
+    }
+    if let Some(path) = &cli.pronunciation {
+        config
+            .audio
+            .pronunciations
+            .extend(Pronunciations::load(path)?);
+    }
+
+    Ok(config)
+}
+
+/// Load `path`, or config.toml if it exists, or the built-in default.
+fn read_config(path: Option<&Path>) -> Result<Config, DebateError> {
+    match path {
+        Some(path) => Config::load(path),
+        None if Path::new("config.toml").exists() => Config::load("config.toml"),
+        None => Ok(debateai_core::config::default_config()),
+    }
+}
+
+/// Run a debate, print it to the console and optionally synthesize audio.
+async fn run_debate(
+    cli: &Cli,
+    config: &Config,
+    topic: &str,
+    format: Box<dyn DebateFormat>,
+    participants: Vec<AIParticipant>,
+    outputs: &RunOutputs,
+) -> Result<Option<Vec<DebateMessage>>, Box<dyn std::error::Error>> {
+    // Check the transcript format before spending any tokens
+    let transcript_format = outputs
+        .transcript
+        .as_deref()
+        .map(TranscriptFormat::from_path)
+        .transpose()?;
+    let judge = cli.judge.then(|| judge_config(cli, config)).transpose()?;
+
+    let format: Box<dyn DebateFormat> = if cli.only_sections.is_empty()
+        && cli.skip_sections.is_empty()
+        && cli.first_speaker.is_none()
+        && cli.closing_model.is_empty()
+    {
+        format
+    } else {
+        let mut filtered = FilteredFormat::new(format, &cli.only_sections, &cli.skip_sections)?;
+        if let Some(first) = cli.first_speaker {
+            if first >= participants.len() {
+                return Err(format!(
+                    "--first-speaker {} is out of range: there are {} participants (0-{})",
+                    first,
+                    participants.len(),
+                    participants.len() - 1
+                )
+                .into());
+            }
+            filtered = filtered.with_first_speaker(first);
+        }
+        let aliases = load_aliases(cli)?;
+        let closing_models: Vec<String> = cli
+            .closing_model
+            .iter()
+            .map(|model| aliases.resolve(model).to_string())
+            .collect();
+        match closing_models.as_slice() {
+            [] => {}
+            [model] => {
+                check_shared_endpoint(&participants)?;
+                filtered = filtered.with_closing_model(model);
+            }
+            models => filtered = filtered.with_closing_models(models),
+        }
+        Box::new(filtered)
+    };
+
+    // Get API configuration from environment
+    let (api_base, api_key) = resolve_api_settings(cli.allow_no_key, cli.strict)?;
+    let shared_context = read_context_files(&cli.shared_context_file)?;
+
+    if !cli.quiet {
+        print_header(cli, format.as_ref(), topic, &participants);
+    }
+
+    // Ask before debates that make many API calls
+    let api_calls = estimated_api_calls(format.as_ref());
+    if !cli.yes && !cli.dump_only && api_calls > CONFIRM_CALL_THRESHOLD {
+        println!();
+        let prompt = format!(
+            "This will make about {} API calls. Continue? [y/N] ",
+            api_calls
+        );
+        if !confirm(&prompt)? {
+            println!("{}", "Aborted.".yellow());
+            return Ok(None);
+        }
+    }
+
+    if !cli.quiet {
+        println!();
+        println!("{}", "─".repeat(70).dimmed());
+    }
+
+    // Create debate configuration
+    let mut debate_config = DebateConfig::new(topic, api_base, api_key)
+        .with_finish_truncated(cli.finish_truncated)
+        .with_max_continuations(cli.max_continuations)
+        .with_context_char_budget(cli.context_char_budget)
+        .with_shared_context(shared_context)
+        .with_strict(cli.strict)
+        .with_debug_prompts(cli.debug_prompts)
+        .with_warmup(cli.warmup)
+        .with_keep_raw(cli.keep_raw)
+        .with_halftime_recap(cli.halftime_recap)
+        .with_json_mode(cli.json_mode)
+        .with_api_retries(cli.api_retries)
+        .with_empty_retries(cli.empty_retries)
+        .with_fail_fast_on_refusal(cli.fail_fast_on_refusal)
+        .with_opponent_message_template(&cli.opponent_template)
+        .with_sanitize(
+            SanitizeConfig::preset(&cli.sanitize_preset)
+                .expect("clap only accepts known sanitize presets"),
+        );
+    if cli.moderator {
+        debate_config = debate_config.with_moderator_prompt(&config.prompts.moderator_prompt);
+    }
+    if cli.strip_all_asterisks {
+        debate_config = debate_config.with_markdown_stripping(MarkdownStripping::Aggressive);
+    }
+    if cli.keep_code_fences {
+        debate_config = debate_config.with_code_fence_unwrapping(false);
+    }
+    if let Some(path) = &cli.rules_file {
+        let rules = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read rules file {}: {}", path.display(), e))?;
+        debate_config = debate_config.with_rules(rules);
+    }
+    debate_config =
+        debate_config.with_debate_date(cli.debate_date.clone().unwrap_or_else(today_iso_date));
+    if let Some(threshold) = cli.repetition_threshold {
+        debate_config = debate_config.with_repetition_threshold(threshold);
+    }
+    if let Some(window) = cli.history_window {
+        debate_config = debate_config.with_history_window(window);
+    }
+    if let Some(ms) = cli.turn_delay
+        && !cli.quiet
+        && std::io::stdout().is_terminal()
+    {
+        debate_config = debate_config.with_turn_delay(Duration::from_millis(ms));
+    }
+
+    // Create orchestrator with event callback
+    let transcript_clone = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let transcript_for_callback = transcript_clone.clone();
+
+    let console = create_console_callback(transcript_for_callback, cli.quiet);
+    let profile = cli
+        .profile
+        .then(|| Arc::new(Mutex::new(RunProfile::default())));
+    let callback: Box<dyn Fn(DebateEvent) + Send + Sync> = match &profile {
+        Some(profile) => {
+            let profile = profile.clone();
+            Box::new(move |event| {
+                profile.lock().unwrap().record(&event);
+                console(event);
+            })
+        }
+        None => console,
+    };
+    let sections = format.sections();
+    let format_name = format.name().to_string();
+    let mut orchestrator = DebateOrchestrator::new(debate_config, participants.clone(), format)?
+        .with_callback(callback);
+
+    if let Some(dir) = &cli.dump_prompts {
+        std::fs::create_dir_all(dir)?;
+        for (i, (participant, prompt)) in participants
+            .iter()
+            .zip(orchestrator.system_prompts())
+            .enumerate()
+        {
+            let path = prompt_dump_path(dir, i, &participant.name);
+            std::fs::write(&path, prompt)?;
+            println!(
+                "{} {}",
+                "System prompt saved:".bold(),
+                path.display().to_string().bright_green()
+            );
+        }
+        if cli.dump_only {
+            return Ok(None);
+        }
+    }
+
+    // Run the debate
+    let debate_start = Instant::now();
+    let transcript = match orchestrator.run().await {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            if cli.verbose_errors {
+                eprintln!("{}\n{}", "Error details:".red().bold(), e.verbose_detail());
+            }
+            return Err(e.into());
+        }
+    };
+
+    if let Some(profile) = &profile {
+        profile.lock().unwrap().debate = debate_start.elapsed();
+    }
+
+    if !cli.quiet {
+        println!();
+        println!("{}", "═".repeat(70).bright_blue());
+        println!("{}", "  Debate concluded.".bright_green().bold());
+        println!("{}", "═".repeat(70).bright_blue());
+    }
+
+    if let Some(judge) = &judge {
+        match orchestrator.judge(judge).await {
+            Ok(verdict) => print_verdict(&verdict),
+            Err(e) => warn(cli.strict, format!("Judging failed: {}", e))?,
+        }
+    }
+
+    if let (Some(path), Some(transcript_format)) = (&outputs.transcript, transcript_format) {
+        // Only the saved copy is redacted; the audio uses the original
+        let mode = if cli.redact_substrings {
+            RedactionMode::Substring
+        } else {
+            RedactionMode::WholeWord
+        };
+        let mut saved = redact_transcript(&transcript, &cli.redact, &cli.redact_with, mode);
+        if cli.merge_turns {
+            saved = merge_consecutive(&saved);
+        }
+        let saved_participants: Vec<AIParticipant> = participants
+            .iter()
+            .map(|p| AIParticipant {
+                name: redact(&p.name, &cli.redact, &cli.redact_with, mode),
+                ..p.clone()
+            })
+            .collect();
+        let saved_topic = redact(topic, &cli.redact, &cli.redact_with, mode);
+        let content = match transcript_format {
+            TranscriptFormat::Markdown => {
+                let front_matter = FrontMatter {
+                    format: format_name,
+                    date: today_iso_date(),
+                };
+                render_markdown(
+                    &saved,
+                    &saved_participants,
+                    &saved_topic,
+                    (!cli.no_front_matter).then_some(&front_matter),
+                )
+            }
+            TranscriptFormat::Html => render_html(&saved, &saved_participants, &saved_topic),
+            TranscriptFormat::Json => render_json(&saved)?,
+            TranscriptFormat::Csv => render_csv(&saved),
+        };
+        std::fs::write(path, content)?;
+        println!();
+        println!(
+            "{} {}",
+            "Transcript saved:".bright_green().bold(),
+            path.display().to_string().bright_white()
+        );
+    }
+
+    // Generate TTS output unless disabled
+    if cli.audio_enabled() {
+        let timings = generate_audio(
+            cli,
+            config,
+            &participants,
+            &sections,
+            &transcript,
+            topic,
+            &outputs.audio,
+        )
+        .await?;
+        if let Some(profile) = &profile {
+            profile.lock().unwrap().audio = timings;
+        }
+    }
+
+    if !cli.quiet {
+        println!();
+        let written: Vec<&Path> = outputs
+            .transcript
+            .as_deref()
+            .into_iter()
+            .chain(Some(outputs.audio.as_path()).filter(|p| cli.audio_enabled() && p.exists()))
+            .collect();
+        println!("{}", closing_summary(&transcript, &participants, &written));
+    }
+    if let Some(profile) = &profile {
+        println!("{}", "Profile:".bold());
+        print!("{}", profile.lock().unwrap().report());
+        println!();
+    }
+
+    Ok(Some(transcript))
+}
+
+/// Summarize a finished debate: turns per participant, total words, and the
+/// files that were written.
+fn closing_summary(
+    transcript: &[DebateMessage],
+    participants: &[AIParticipant],
+    written: &[&Path],
+) -> String {
+    let turns: Vec<String> = participants
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let count = transcript
+                .iter()
+                .filter(|m| m.speaker_index == Some(i))
+                .count();
+            format!("{} {}", p.name, count)
+        })
+        .collect();
+    let words: usize = transcript
+        .iter()
+        .map(|m| m.content.split_whitespace().count())
+        .sum();
+
+    let mut summary = format!("Turns: {}\nWords: {}\n", turns.join(", "), words);
+    for path in written {
+        summary.push_str(&format!("Output: {}\n", path.display()));
+    }
+    summary
+}
+
+/// Normalize the topic, warning if it had to be shortened.
+fn prepare_topic(topic: &str, strict: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let normalized = normalize_topic(topic)?;
+    if normalized != topic.split_whitespace().collect::<Vec<_>>().join(" ") {
+        warn(
+            strict,
+            format!("Topic shortened to {} characters.", MAX_TOPIC_CHARS),
+        )?;
+    }
+    Ok(normalized)
+}
+
+/// Debates estimated to make more API calls than this ask for confirmation.
+const CONFIRM_CALL_THRESHOLD: usize = 24;
+
+/// Estimate the API calls a debate will make: one per speaker turn.
+fn estimated_api_calls(format: &dyn DebateFormat) -> usize {
+    format
+        .sections()
+        .iter()
+        .map(|section| section.speaker_order.len())
+        .sum()
+}
+
+/// Print the banner, topic and participants before a debate starts.
+fn print_header(cli: &Cli, format: &dyn DebateFormat, topic: &str, participants: &[AIParticipant]) {
+    println!();
+    println!("{}", "═".repeat(70).bright_blue());
+    println!(
+        "{}",
+        format!("  {} - {}", "DebateAI".bold(), format.display_name())
+            .bright_blue()
+            .bold()
+    );
+    println!("{}", "═".repeat(70).bright_blue());
+    println!();
+    println!("{} {}", "Topic:".bold(), topic.bright_white());
+    println!();
+    println!("{}", "Participants:".bold());
+    for (i, p) in participants.iter().enumerate() {
+        println!(
+            "  {}. {} ({}) - using {}",
+            i + 1,
+            p.name.bright_cyan(),
+            p.role.display_name().yellow(),
+            p.model.dimmed()
+        );
+    }
+
+    if cli.audio_enabled() {
+        println!();
+        println!(
+            "{} {}",
+            "Audio Output:".bold(),
+            cli.output_dir.display().to_string().bright_green()
+        );
+    }
+}
+
+/// Ask a yes/no question on stdin, defaulting to no.
+fn confirm(prompt: &str) -> std::io::Result<bool> {
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+    confirm_from(prompt, interactive, &mut stdin.lock())
+}
+
+/// Ask a yes/no question, reading the answer from `input`.
+///
+/// Fails rather than prompting when `input` is not interactive, so a piped
+/// or scripted run can't hang or be answered by accident.
+fn confirm_from(
+    prompt: &str,
+    interactive: bool,
+    input: &mut impl std::io::BufRead,
+) -> std::io::Result<bool> {
+    if !interactive {
+        return Err(std::io::Error::other(
+            "refusing to prompt on non-interactive stdin; pass --yes",
+        ));
+    }
+    print!("{}", prompt);
+    std::io::Write::flush(&mut std::io::stdout())?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// File for a participant's dumped system prompt, e.g. `1-Candidate_A.txt`.
+fn prompt_dump_path(dir: &Path, index: usize, name: &str) -> PathBuf {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{}-{}.txt", index + 1, name))
+}
+
+/// How much silence replaces a message that fails to synthesize.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FailureSilence {
+    /// A fixed number of seconds.
+    Fixed(f32),
+    /// As long as the text would take to speak.
+    Proportional,
+}
+
+impl FailureSilence {
+    /// Number of samples of silence for a message with this text, spoken at
+    /// `wpm` words per minute.
+    fn samples(self, text: &str, wpm: f32, sample_rate: u32) -> usize {
+        match self {
+            Self::Fixed(seconds) => (seconds * sample_rate as f32) as usize,
+            Self::Proportional => estimate_speech_samples(text, wpm, sample_rate),
+        }
+    }
+}
+
+/// Parse `--failure-silence`: "auto" or a non-negative number of seconds.
+fn parse_failure_silence(value: &str) -> Result<FailureSilence, String> {
+    if value.eq_ignore_ascii_case("auto") {
+        return Ok(FailureSilence::Proportional);
+    }
+    match value.parse::<f32>() {
+        Ok(seconds) if seconds >= 0.0 && seconds.is_finite() => Ok(FailureSilence::Fixed(seconds)),
+        _ => Err(format!(
+            "expected a number of seconds or \"auto\", got '{}'",
+            value
+        )),
+    }
+}
+
+/// Parse `--max-audio-minutes`: a finite number of minutes greater than zero.
+fn parse_max_audio_minutes(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(minutes) if minutes > 0.0 && minutes.is_finite() => Ok(minutes),
+        _ => Err(format!(
+            "expected a positive number of minutes, got '{}'",
+            value
+        )),
+    }
+}
+
+/// Parse `--background-gain`: a finite, non-negative gain.
+fn parse_background_gain(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(gain) if gain >= 0.0 && gain.is_finite() => Ok(gain),
+        _ => Err(format!("expected a non-negative gain, got '{}'", value)),
+    }
+}
+
+/// Parse `--judge-weight`: a criterion name and its weight, as `CRITERION=WEIGHT`.
+fn parse_judge_weight(value: &str) -> Result<(String, f32), String> {
+    let (criterion, weight) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected CRITERION=WEIGHT, got '{}'", value))?;
+    match weight.trim().parse::<f32>() {
+        Ok(weight) if weight >= 0.0 && weight.is_finite() && !criterion.trim().is_empty() => {
+            Ok((criterion.trim().to_string(), weight))
+        }
+        _ => Err(format!(
+            "expected a criterion and a non-negative weight, got '{}'",
+            value
+        )),
+    }
+}
+
+/// Check that every participant is served by the same endpoint, so a
+/// single `--closing-model` can be used by all of them.
+fn check_shared_endpoint(participants: &[AIParticipant]) -> Result<(), String> {
+    let first = participants.first().and_then(|p| p.api_base.as_deref());
+    if participants.iter().any(|p| p.api_base.as_deref() != first) {
+        return Err(
+            "Participants use different endpoints, so a single --closing-model \
+             may not be served at all of them; give one --closing-model per --model"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// The model aliases given with `--aliases`, if any.
+fn load_aliases(cli: &Cli) -> Result<ModelAliases, DebateError> {
+    match &cli.aliases {
+        Some(path) => ModelAliases::load(path),
+        None => Ok(ModelAliases::default()),
+    }
+}
+
+/// The judging rubric from the config, with the command line's overrides.
+fn judge_config(cli: &Cli, config: &Config) -> Result<JudgeConfig, DebateError> {
+    let mut judge = config.judge.clone();
+    if let Some(model) = &cli.judge_model {
+        judge.model = Some(model.clone());
+    }
+    if let Some(endpoint) = &cli.judge_endpoint {
+        judge.endpoint = Some(endpoint.clone());
+    }
+    let aliases = load_aliases(cli)?;
+    judge.model = judge.model.map(|model| aliases.resolve(&model).to_string());
+    for (criterion, weight) in &cli.judge_weight {
+        judge = judge.with_weight(criterion, *weight);
+    }
+    judge.validate()?;
+    Ok(judge)
+}
+
+/// Print each participant's weighted score from the judge, and the winner.
+fn print_verdict(verdict: &JudgeVerdict) {
+    println!();
+    println!("{}", "Judge's scores:".bold());
+    let width = verdict
+        .scores
+        .iter()
+        .map(|s| s.name.len())
+        .max()
+        .unwrap_or(0);
+    for score in &verdict.scores {
+        println!("  {:<width$}  {:>5.2}", score.name, score.total);
+    }
+    match verdict.winner() {
+        Some(winner) => println!("{} {}", "Winner:".bold(), winner.name.bright_green()),
+        None => println!("{}", "The judge scored the debate a tie.".bold()),
+    }
+}
+
+/// File formats for `--save-transcript`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TranscriptFormat {
+    Markdown,
+    Html,
+    Json,
+    Csv,
+}
+
+impl TranscriptFormat {
+    /// Pick the format from a file extension.
+    fn from_path(path: &std::path::Path) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match extension.as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "html" | "htm" => Ok(Self::Html),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "Unsupported transcript format for {}: use a .md, .html, .json or .csv extension",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Read context documents passed on the command line.
+fn read_context_files(paths: &[PathBuf]) -> Result<Vec<String>, String> {
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read context file {}: {}", path.display(), e))
+        })
+        .collect()
+}
+
+/// Synthesize the debate transcript to a WAV file in the output directory.
+///
+/// A debate run constructs the TTS engine only here (the `check` subcommand
+/// loads it separately), so text-only runs never load or download the model.
+/// Returns how long synthesis and saving took. Fails with
+/// [`DebateError::TtsError`] if the engine cannot be loaded or the audio
+/// cannot be saved, and, with `--strict`, if any segment fails to synthesize.
+async fn generate_audio(
+    cli: &Cli,
+    config: &Config,
+    participants: &[AIParticipant],
+    sections: &[DebateSection],
+    transcript: &[DebateMessage],
+    topic: &str,
+    output_path: &Path,
+) -> Result<AudioTimings, Box<dyn std::error::Error>> {
+    let mut timings = AudioTimings::default();
+    let synthesis_start = Instant::now();
+    // Progress lines are left out with --quiet
+    let progress = !cli.quiet;
+    if progress {
+        println!();
+        println!("{}", "Generating audio output...".bright_yellow());
+        let estimated_seconds: f32 = transcript
+            .iter()
+            .map(|m| estimate_speech_seconds(&m.content, cli.speech_wpm))
+            .sum();
+        println!(
+            "  Estimated speech length: {}:{:02} at {} wpm",
+            (estimated_seconds / 60.0) as u32,
+            (estimated_seconds % 60.0) as u32,
+            cli.speech_wpm
+        );
+    }
+
+    // Create output directory if needed
+    if let Some(dir) = output_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            DebateError::TtsError(format!(
+                "Failed to create audio directory {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+    }
+
+    // Load the background before synthesis so a bad file fails fast
+    let background = cli.background.as_deref().map(read_wav_mono).transpose()?;
+
+    // Initialize TTS engine
+    let mut tts = DebateTts::new(config.voices.clone())
+        .await?
+        .with_text_cleaning(cli.clean_tts_text)
+        .with_text_normalization(cli.tts_normalize)
+        .with_auto_language_voice(cli.auto_language_voice)
+        .with_censored_words(&config.audio.censored_words)
+        .with_pronunciations(&config.audio.pronunciations);
+    if cli.tight_audio {
+        tts = tts.with_timing(TtsTiming::tight());
+    }
+
+    // Sample rate for silence calculation
+    let sample_rate = 24000;
+    let section_pause_seconds = 2.0; // Pause between sections
+    let gaps = SegmentGaps {
+        same_speaker_secs: cli.same_speaker_gap,
+        speaker_change_secs: cli.speaker_change_gap,
+    };
+
+    if progress && cli.speech_rate != 1.0 {
+        println!("  Adjusting speech rate to {}x...", cli.speech_rate);
+    }
+    let output_rate = cli.audio_sample_rate.unwrap_or(sample_rate as u32);
+    if progress && output_rate != sample_rate as u32 {
+        println!("  Resampling audio to {} Hz...", output_rate);
+    }
+    // Bring the background to the output rate once, up front
+    let background = background.map(|(samples, rate)| {
+        if progress {
+            println!("  Mixing background at gain {}...", cli.background_gain);
+        }
+        resample_linear(&samples, rate, output_rate)
+    });
+
+    // Each segment is written as soon as it is synthesized and then
+    // dropped, so the whole debate is never held in memory
+    let mut sink = AudioSink {
+        writer: WavStreamWriter::create(output_path, output_rate)?,
+        // Speed adjustment happens on write, so scale the limit to
+        // the pre-adjustment length
+        budget: cli
+            .max_audio_minutes
+            .map(|minutes| AudioBudget::new(minutes * 60.0 * cli.speech_rate, sample_rate as u32)),
+        speech_rate: cli.speech_rate,
+        input_rate: sample_rate as u32,
+        output_rate,
+        background,
+        background_offset: 0,
+        background_gain: cli.background_gain,
+        write_time: Duration::ZERO,
+        truncated: false,
+    };
+    let mut failed_segments = 0;
+    // Chapter titles and the sample each starts at
+    let mut chapter_starts: Vec<(String, usize)> = vec![("Introduction".to_string(), 0)];
+
+    // Macro to synthesize announcer text (avoids closure borrow issues).
+    // Nothing is synthesized once the audio has hit --max-audio-minutes
+    macro_rules! synth_announcer {
+        ($text:expr, $label:expr) => {{
+            if !sink.truncated {
+                if progress {
+                    print!("  Synthesizing {}...", $label);
+                    let _ = std::io::Write::flush(&mut std::io::stdout());
+                }
+                let result = tts.synthesize_announcer($text);
+                if progress {
+                    print_synthesis_status(&result);
+                }
+                match result {
+                    Ok(audio) => sink.write(audio)?,
+                    Err(_) => failed_segments += 1,
+                }
+            }
+        }};
+    }
+
+    // === INTRO SECTION ===
+    // Opening announcement (topic and participants), from the config template
+    let opening = config.opening_announcement(topic, participants);
+    synth_announcer!(&opening, "opening");
+    sink.write(silence(section_pause_seconds, sample_rate))?;
+
+    // Let the debate begin
+    synth_announcer!("Let the debate begin.", "start");
+    sink.write(silence(section_pause_seconds, sample_rate))?;
+
+    let mut current_section: Option<String> = None;
+    let mut section_index = 0;
+    // Participant index of the last voice heard; `None` after the announcer
+    let mut previous_speaker: Option<usize> = None;
+
+    for message in transcript {
+        if sink.truncated {
+            break;
+        }
+
+        // Check if we're in a new section - add section announcement
+        if current_section.as_ref() != Some(&message.section) {
+            // Add pause before new section (except first)
+            if current_section.is_some() {
+                sink.write(silence(section_pause_seconds, sample_rate))?;
+            }
+
+            // Announce the new section with context
+            chapter_starts.push((message.section.clone(), sink.writer.samples_written()));
+            section_index += 1;
+            let description = sections
+                .iter()
+                .find(|s| s.name == message.section)
+                .map_or("", |s| s.description.as_str());
+            let section_text =
+                config.announcer_text(&message.section, description, section_index, sections.len());
+            synth_announcer!(&section_text, &format!("section: {}", message.section));
+            previous_speaker = None;
+
+            current_section = Some(message.section.clone());
+        }
+
+        let Some(speaker_index) = message.speaker_index else {
+            // The announcer's own segment, such as the halftime recap or a
+            // moderator question
+            sink.write(silence(gaps.speaker_change_secs, sample_rate))?;
+            synth_announcer!(&message.content, &message.speaker_name);
+            previous_speaker = None;
+            continue;
+        };
+
+        // Pause before the speaker; shorter when they also spoke last
+        let pause_seconds = gaps.between(previous_speaker, speaker_index);
+        sink.write(silence(pause_seconds, sample_rate))?;
+
+        // Announce the speaker before their turn, unless they simply
+        // carry on from their previous one
+        if previous_speaker != Some(speaker_index) {
+            let speaker_role = &participants[speaker_index].role;
+            let speaker_intro = format!(
+                "{}, speaking {}.",
+                message.speaker_name,
+                speaker_role.display_name().to_lowercase()
+            );
+            synth_announcer!(&speaker_intro, &format!("{} turn", message.speaker_name));
+            sink.write(silence(0.5, sample_rate))?;
+        }
+        previous_speaker = Some(speaker_index);
+
+        let role = &participants[speaker_index].role;
+        if progress {
+            print!(
+                "  Synthesizing {} ({})...",
+                message.speaker_name.bright_cyan(),
+                message.section
+            );
+            std::io::Write::flush(&mut std::io::stdout())?;
+        }
+
+        let result = tts.synthesize_message(message, role);
+        if progress {
+            print_synthesis_status(&result);
+        }
+        match result {
+            Ok(audio) => sink.write(audio)?,
+            Err(_) => {
+                failed_segments += 1;
+                // Add silence instead of failing completely
+                let silence = cli.failure_silence.samples(
+                    &message.content,
+                    cli.speech_wpm,
+                    sample_rate as u32,
+                );
+                sink.write(vec![0.0; silence])?;
+            }
+        }
+    }
+
+    // Add closing announcement
+    sink.write(silence(section_pause_seconds, sample_rate))?;
+    synth_announcer!(
+        "This concludes today's debate. Thank you for listening.",
+        "outro announcement"
+    );
+
+    if failed_segments > 0 {
+        warn(
+            cli.strict,
+            format!("{} segment(s) failed to synthesize", failed_segments),
+        )
+        .map_err(DebateError::TtsError)?;
+    }
+    if sink.truncated
+        && let Some(minutes) = cli.max_audio_minutes
+    {
+        warn(
+            cli.strict,
+            format!(
+                "Audio truncated to {} minute(s); the rest of the debate was not synthesized",
+                minutes
+            ),
+        )?;
+    }
+
+    let write_time = sink.write_time;
+    timings.synthesis = synthesis_start.elapsed().saturating_sub(write_time);
+    let save_start = Instant::now();
+    let total_samples = sink.writer.finalize()?;
+    timings.save = write_time + save_start.elapsed();
+    println!();
+    println!(
+        "{} {}",
+        "Audio saved:".bright_green().bold(),
+        output_path.display().to_string().bright_white()
+    );
+    if cli.chapters {
+        // A chapter whose announcement was cut off has no audio
+        chapter_starts.retain(|(_, start)| *start < total_samples);
+        write_chapters(output_path, &chapter_starts, output_rate)?;
+    }
+
+    Ok(timings)
+}
+
+/// Finish a "Synthesizing ..." progress line with the segment's outcome.
+fn print_synthesis_status<T>(result: &Result<T, DebateError>) {
+    match result {
+        Ok(_) => println!(" {}", "✓".bright_green()),
+        Err(e) => println!(" {} ({})", "✗".bright_red(), e),
+    }
+}
+
+/// `seconds` of silence at `sample_rate`.
+fn silence(seconds: f32, sample_rate: usize) -> Vec<f32> {
+    vec![0.0; (seconds * sample_rate as f32) as usize]
+}
+
+/// Post-processes synthesized segments and streams them into the WAV file.
+struct AudioSink {
+    writer: WavStreamWriter,
+    /// What is left of --max-audio-minutes, in samples at `input_rate`.
+    budget: Option<AudioBudget>,
+    speech_rate: f32,
+    input_rate: u32,
+    output_rate: u32,
+    /// Background at `output_rate`.
+    background: Option<Vec<f32>>,
+    /// Where the next segment picks up the background loop.
+    background_offset: usize,
+    background_gain: f32,
+    /// Time spent adjusting, resampling, mixing and writing.
+    write_time: Duration,
+    /// Set once a segment did not fit in the budget; later writes are ignored.
+    truncated: bool,
+}
+
+impl AudioSink {
+    /// Adjust, resample and mix a segment, then append it to the file.
+    ///
+    /// Once a segment does not fit in what is left of the length budget,
+    /// it and every later segment are dropped.
+    fn write(&mut self, segment: Vec<f32>) -> Result<(), DebateError> {
+        if self.truncated {
+            return Ok(());
+        }
+        if let Some(budget) = &mut self.budget
+            && !budget.take(segment.len())
+        {
+            self.truncated = true;
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let segment = adjust_audio_speed(segment, self.speech_rate);
+        let mut segment = if self.output_rate == self.input_rate {
+            segment
+        } else {
+            resample_linear(&segment, self.input_rate, self.output_rate)
+        };
+        if let Some(bg) = self.background.as_deref().filter(|bg| !bg.is_empty()) {
+            segment = mix_background(&segment, bg, self.background_offset, self.background_gain);
+            self.background_offset = (self.background_offset + segment.len()) % bg.len();
+        }
+        self.writer.write(&segment)?;
+        self.write_time += start.elapsed();
+        Ok(())
+    }
+}
+
+/// Write a `.chapters.txt` file next to the audio, from each chapter's
+/// starting sample offset.
+fn write_chapters(
+    audio_path: &Path,
+    chapter_starts: &[(String, usize)],
+    sample_rate: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = audio_path.with_extension("chapters.txt");
+    std::fs::write(&path, build_chapters(chapter_starts, sample_rate))?;
+    println!(
+        "{} {}",
+        "Chapters saved:".bright_green().bold(),
+        path.display().to_string().bright_white()
+    );
+    Ok(())
+}
+
+/// Print a warning, or return it as an error in strict mode.
+fn warn(strict: bool, message: impl Into<String>) -> Result<(), String> {
+    let message = message.into();
+    if strict {
+        return Err(message);
+    }
+    eprintln!("{}", format!("Warning: {}", message).yellow());
+    Ok(())
+}
+
+/// Resolve the API base URL and key from the environment.
+fn resolve_api_settings(allow_no_key: bool, strict: bool) -> Result<(String, String), String> {
+    let api_base = api_base_from_env();
+
+    let api_key = require_api_key(
+        env::var("OPENAI_API_KEY").ok(),
+        &api_base,
+        allow_no_key,
+        strict,
+    )?;
+
+    Ok((api_base, api_key))
+}
+
+/// The API base URL from the environment, defaulting to OpenAI.
+fn api_base_from_env() -> String {
+    env::var("OPENAI_API_BASE")
+        .or_else(|_| env::var("OPENAI_BASE_URL"))
+        .unwrap_or_else(|_| "https://api.openai.com/v1".to_string())
+}
+
+/// Check an API key is set for endpoints that need one.
+///
+/// Local endpoints may run without a key. For anything else a missing key is
+/// an error, unless explicitly allowed, in which case it is only a warning.
+fn require_api_key(
+    api_key: Option<String>,
+    api_base: &str,
+    allow_no_key: bool,
+    strict: bool,
+) -> Result<String, String> {
+    let api_key = api_key.unwrap_or_default();
+    if api_key.is_empty() && !is_local_endpoint(api_base) {
+        if !allow_no_key {
+            return Err(format!(
+                "OPENAI_API_KEY is not set, but {} requires one. \
+                 Set it, or pass --allow-no-key if this endpoint does not need a key.",
+                api_base
+            ));
+        }
+        warn(strict, "OPENAI_API_KEY not set. API calls may fail.")?;
+    }
+    Ok(api_key)
+}
+
+/// Whether an API base URL points at this machine (e.g. Ollama, llama.cpp).
+fn is_local_endpoint(api_base: &str) -> bool {
+    let without_scheme = api_base
+        .split_once("://")
+        .map_or(api_base, |(_, rest)| rest);
+    let authority = without_scheme.split('/').next().unwrap_or_default();
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+
+    // Strip the port, keeping bracketed IPv6 addresses intact
+    let host = match authority.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    }
+    .to_ascii_lowercase();
+
+    host == "localhost"
+        || host.ends_with(".localhost")
+        || host == "::1"
+        || host == "0.0.0.0"
+        || host.starts_with("127.")
+}
+
+/// Send a tiny request to the configured endpoint and report the outcome.
+async fn run_ping(model: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (api_base, api_key) = resolve_api_settings(true, false)?;
+    let backend = OpenAIBackend::new(&api_base, &api_key)?;
+
+    println!(
+        "Pinging {} with model {}...",
+        api_base.bright_white(),
+        model.dimmed()
+    );
+    let report = debateai_core::ping(&backend, model).await;
+    let latency_ms = report.latency.as_millis();
+
+    match &report.result {
+        Ok(_) => {
+            println!(
+                "{} {} responded in {} ms",
+                "OK".bright_green().bold(),
+                model,
+                latency_ms
+            );
+            Ok(())
+        }
+        Err(e) => {
+            println!("{} after {} ms: {}", "FAIL".red().bold(), latency_ms, e);
+            std::process::exit(exit_code_for(e).into());
+        }
+    }
+}
+
+/// Name of a `check` step and the reason it failed, if it did.
+type CheckOutcome = (String, Result<(), String>);
+
+/// Validate everything a debate needs without running one, print each
+/// check's result and fail if any check failed.
+async fn run_check(
+    config_path: Option<&Path>,
+    models: &[String],
+    format_name: &str,
+    ping: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut outcomes: Vec<CheckOutcome> = Vec::new();
+    match read_config(config_path) {
+        Ok(config) => {
+            // Check voices against the voices the TTS engine actually has
+            let tts = DebateTts::new(config.voices.clone()).await;
+            let voices = tts.as_ref().ok().map(|tts| tts.available_voices());
+            outcomes.extend(setup_checks(&config, format_name, models.len(), voices));
+            if let Err(e) = tts {
+                outcomes.push(("voices".to_string(), Err(e.to_string())));
+            }
+        }
+        Err(e) => outcomes.push(("config".to_string(), Err(e.to_string()))),
+    }
+
+    if ping {
+        match resolve_api_settings(false, false)
+            .and_then(|(base, key)| OpenAIBackend::new(&base, &key).map_err(|e| e.to_string()))
+        {
+            Ok(backend) => {
+                let mut pinged: Vec<&String> = Vec::new();
+                for model in models {
+                    if pinged.contains(&model) {
+                        continue;
+                    }
+                    pinged.push(model);
+                    let report = debateai_core::ping(&backend, model).await;
+                    let result = report.result.map(|_| ()).map_err(|e| e.to_string());
+                    outcomes.push((format!("api ({})", model), result));
+                }
+            }
+            Err(e) => outcomes.push(("api".to_string(), Err(e))),
+        }
+    }
+
+    for (name, result) in &outcomes {
+        match result {
+            Ok(()) => println!("{} {}", "PASS".bright_green().bold(), name),
+            Err(e) => println!("{} {}: {}", "FAIL".red().bold(), name, e),
+        }
+    }
+
+    let failed = outcomes.iter().filter(|(_, r)| r.is_err()).count();
+    if failed > 0 {
+        return Err(format!("{} of {} checks failed", failed, outcomes.len()).into());
+    }
+    Ok(())
+}
+
+/// The checks of a loaded config that need no network access: the config
+/// itself, the format, the participant count (if any models were given) and,
+/// when the engine's voices are known, the configured voices.
+fn setup_checks(
+    config: &Config,
+    format_name: &str,
+    model_count: usize,
+    available_voices: Option<&[String]>,
+) -> Vec<CheckOutcome> {
+    let mut outcomes = vec![(
+        "config".to_string(),
+        config.validate().map_err(|e| e.to_string()),
+    )];
+
+    match debate_format::get_format(format_name, debate_format::DEFAULT_ROUNDS) {
+        Some(format) => {
+            outcomes.push(("format".to_string(), Ok(())));
+            if model_count > 0 {
+                let result = check_participant_count(
+                    format_name,
+                    model_count,
+                    format.min_participants(),
+                    format.max_participants(),
+                );
+                outcomes.push(("participants".to_string(), result));
+            }
+        }
+        None => outcomes.push((
+            "format".to_string(),
+            Err(format!(
+                "Unknown debate format: '{}'. Available formats: {}",
+                format_name,
+                debate_format::available_formats().join(", ")
+            )),
+        )),
+    }
+
+    if let Some(available) = available_voices {
+        let result = check_voices(&config.voices, available).map_err(|e| e.to_string());
+        outcomes.push(("voices".to_string(), result));
+    }
+    outcomes
+}
+
+/// Print a saved JSON transcript as if the debate were running live.
+fn replay_saved_transcript(path: &Path, quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read transcript {}: {}", path.display(), e))?;
+    let messages = parse_json(&json)?;
+
+    // Transcripts don't record roles; assume the CLI's default alternation
+    let mut participants: Vec<AIParticipant> = Vec::new();
+    for index in messages.iter().filter_map(|m| m.speaker_index) {
+        while participants.len() <= index {
+            let (name, role) = default_participant(participants.len());
+            participants.push(AIParticipant::new(name, "", role));
+        }
+    }
+
+    let callback = create_console_callback(std::sync::Arc::default(), quiet);
+    replay_transcript(&messages, &participants, &callback);
+    Ok(())
+}
+
+/// Decide whether colored output should be used.
+///
+/// Follows the NO_COLOR convention: any non-empty value disables color.
+fn color_enabled(no_color_flag: bool, no_color_env: Option<std::ffi::OsString>) -> bool {
+    !no_color_flag && no_color_env.is_none_or(|v| v.is_empty())
+}
+
+/// Create a callback that prints debate events to the console.
+///
+/// With `quiet`, section banners are left out and only the turns are printed.
+fn create_console_callback(
+    _transcript: std::sync::Arc<std::sync::Mutex<Vec<debateai_core::DebateMessage>>>,
+    quiet: bool,
+) -> Box<dyn Fn(DebateEvent) + Send + Sync> {
+    Box::new(move |event| match event {
+        DebateEvent::SectionStart { .. } if quiet => {}
+        DebateEvent::SectionStart { name, description } => {
+            println!();
+            println!("{}", "═".repeat(70).bright_magenta());
+            println!(
+                "{}",
+                format!("  📢 ANNOUNCER: {}", name).bright_magenta().bold()
+            );
             println!("  {}", description.dimmed());
             println!("{}", "═".repeat(70).bright_magenta());
             println!();
@@ -553,29 +2300,682 @@ fn create_console_callback(
             }
             println!();
         }
+        DebateEvent::PromptDump {
+            speaker_index,
+            messages_json,
+        } => {
+            println!(
+                "{}",
+                format!("  [prompt for speaker {}]", speaker_index + 1).dimmed()
+            );
+            for line in messages_json.lines() {
+                println!("  {}", line.dimmed());
+            }
+            println!();
+        }
         DebateEvent::DebateEnd => {
             // Handled in main
         }
     })
 }
 
-/// Simple text wrapping function.
-fn textwrap(text: &str, width: usize) -> String {
-    let mut result = String::new();
-    let mut current_line_len = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use debateai_core::MockBackend;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_closing_summary() {
+        let participants = vec![
+            AIParticipant::new("Alice", "a", ParticipantRole::For),
+            AIParticipant::new("Bob", "b", ParticipantRole::Against),
+        ];
+        let message = |speaker_index: usize, content: &str| DebateMessage {
+            section: "Opening".to_string(),
+            speaker_index: Some(speaker_index),
+            speaker_name: participants[speaker_index].name.clone(),
+            content: content.to_string(),
+            raw_content: None,
+        };
+        let transcript = vec![
+            message(0, "We should act."),
+            message(1, "No."),
+            message(0, "Yes we should."),
+        ];
+
+        assert_eq!(
+            closing_summary(&transcript, &participants, &[Path::new("out/debate.md")]),
+            "Turns: Alice 2, Bob 1\nWords: 7\nOutput: out/debate.md\n"
+        );
+    }
+
+    #[test]
+    fn test_transcript_only_disables_audio() {
+        let cli = Cli::try_parse_from(["debateai", "Topic", "-m", "a", "-m", "b"]).unwrap();
+        assert!(cli.audio_enabled());
+
+        let cli = Cli::try_parse_from([
+            "debateai",
+            "Topic",
+            "-m",
+            "a",
+            "-m",
+            "b",
+            "--transcript-only",
+        ])
+        .unwrap();
+        assert!(!cli.audio_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_text_only_debate_completes_with_mock_backend() {
+        let participants = vec![
+            AIParticipant::new("Candidate A", "a", ParticipantRole::For),
+            AIParticipant::new("Candidate B", "b", ParticipantRole::Against),
+        ];
+        let config = DebateConfig::new("Topic", "http://localhost:1", "");
+        let callback = create_console_callback(Arc::new(std::sync::Mutex::new(Vec::new())), false);
+        let format = PresidentialDebateFormat::new(4);
+        let sections = format.sections();
+        let mut orchestrator = DebateOrchestrator::new(config, participants, Box::new(format))
+            .unwrap()
+            .with_backend(Arc::new(MockBackend::new()))
+            .with_callback(callback);
+
+        // Nothing here constructs a DebateTts; only generate_audio does
+        let transcript = orchestrator.run().await.unwrap();
+        assert_eq!(transcript.len(), 8);
+
+        // Each section's turns come in its speaker order, and every turn is
+        // the mock's numbered reply for that speaker's model and side
+        let mut turns = transcript.iter().enumerate();
+        for section in &sections {
+            for &speaker in &section.speaker_order {
+                let (i, message) = turns.next().unwrap();
+                let (model, side) = [("a", "FOR"), ("b", "AGAINST")][speaker];
+                assert_eq!(message.section, section.name);
+                assert_eq!(message.speaker_index, Some(speaker));
+                let reply = format!(
+                    "This is mock response number {} from {}. As the candidate arguing {} Topic, in {} ",
+                    i + 1,
+                    model,
+                    side,
+                    section.name.split(" - ").next().unwrap()
+                );
+                assert!(message.content.starts_with(&reply), "{}", message.content);
+            }
+        }
+        assert!(turns.next().is_none());
+    }
+
+    #[test]
+    fn test_default_participant_assignment() {
+        for count in 2..=4 {
+            let assigned: Vec<_> = (0..count).map(default_participant).collect();
+            let expected = [
+                ("Candidate A", ParticipantRole::For),
+                ("Candidate B", ParticipantRole::Against),
+                ("Candidate C", ParticipantRole::For),
+                ("Candidate D", ParticipantRole::Against),
+            ];
+            for ((name, role), (expected_name, expected_role)) in assigned.iter().zip(&expected) {
+                assert_eq!(name, expected_name);
+                assert_eq!(role, expected_role);
+            }
+        }
+
+        assert!(check_participant_count("presidential", 2, 2, 2).is_ok());
+        let error = check_participant_count("presidential", 3, 2, 2).unwrap_err();
+        assert!(error.contains("at most 2 models"), "{}", error);
+        let error = check_participant_count("presidential", 1, 2, 2).unwrap_err();
+        assert!(error.contains("at least 2 models"), "{}", error);
+    }
+
+    #[test]
+    fn test_judge_weights_override_config() {
+        assert_eq!(
+            parse_judge_weight("evidence = 2"),
+            Ok(("evidence".to_string(), 2.0))
+        );
+        assert!(parse_judge_weight("evidence").is_err());
+        assert!(parse_judge_weight("evidence=-1").is_err());
+        assert!(parse_judge_weight("=1").is_err());
+
+        let cli = Cli::try_parse_from([
+            "debateai",
+            "Topic",
+            "--judge",
+            "--judge-weight",
+            "evidence=3",
+            "--judge-weight",
+            "humor=1",
+        ])
+        .unwrap();
+        let judge = judge_config(&cli, &debateai_core::config::default_config()).unwrap();
+        assert!(judge.criteria.contains(&("evidence".to_string(), 3.0)));
+        assert_eq!(judge.criteria.last(), Some(&("humor".to_string(), 1.0)));
+        assert!(Cli::try_parse_from(["debateai", "Topic", "--judge-model", "m"]).is_err());
+
+        let aliases =
+            std::env::temp_dir().join(format!("debateai-judge-{}.toml", std::process::id()));
+        std::fs::write(&aliases, "fast = \"provider/fast-model\"\n").unwrap();
+        let cli = Cli::try_parse_from([
+            "debateai",
+            "Topic",
+            "--aliases",
+            aliases.to_str().unwrap(),
+            "--judge",
+            "--judge-model",
+            "fast",
+            "--judge-endpoint",
+            "http://judge:8080/v1",
+        ])
+        .unwrap();
+        let judge = judge_config(&cli, &debateai_core::config::default_config()).unwrap();
+        std::fs::remove_file(&aliases).unwrap();
+        assert_eq!(judge.model.as_deref(), Some("provider/fast-model"));
+        assert_eq!(judge.endpoint.as_deref(), Some("http://judge:8080/v1"));
+    }
+
+    #[test]
+    fn test_parse_failure_silence() {
+        assert_eq!(parse_failure_silence("2.5"), Ok(FailureSilence::Fixed(2.5)));
+        assert_eq!(
+            parse_failure_silence("AUTO"),
+            Ok(FailureSilence::Proportional)
+        );
+        assert!(parse_failure_silence("-1").is_err());
+        assert!(parse_failure_silence("long").is_err());
+
+        assert_eq!(
+            FailureSilence::Fixed(1.0).samples("any text", 150.0, 24000),
+            24000
+        );
+        assert_eq!(
+            FailureSilence::Proportional.samples(&"word ".repeat(15), 150.0, 24000),
+            6 * 24000
+        );
+        assert_eq!(
+            FailureSilence::Proportional.samples(&"word ".repeat(15), 75.0, 24000),
+            12 * 24000
+        );
+    }
+
+    #[test]
+    fn test_exit_code_for() {
+        assert_eq!(
+            exit_code_for(&DebateError::ConfigError("bad".to_string())),
+            2
+        );
+        assert_eq!(
+            exit_code_for(&DebateError::UnknownFormat("oxford".to_string())),
+            2
+        );
+        assert_eq!(
+            exit_code_for(&DebateError::EmptyResponse("silent".to_string())),
+            4
+        );
+        assert_eq!(
+            exit_code_for(&DebateError::TtsError("no engine".to_string())),
+            5
+        );
+        assert_eq!(
+            exit_code_for(&DebateError::Refusal {
+                participant: "Alice".to_string()
+            }),
+            6
+        );
+        assert_eq!(
+            exit_code_for(&DebateError::IoError(std::io::Error::other("disk"))),
+            1
+        );
+
+        let message: Box<dyn std::error::Error> = "Unknown debate format".into();
+        assert_eq!(exit_code(message.as_ref()), 2);
+        let io: Box<dyn std::error::Error> = std::io::Error::other("disk").into();
+        assert_eq!(exit_code(io.as_ref()), 1);
+    }
+
+    #[tokio::test]
+    async fn test_audio_failure_exits_with_tts_code() {
+        let cli = Cli::try_parse_from([
+            "debateai",
+            "Topic",
+            "--background",
+            "/nonexistent/debateai-background.wav",
+        ])
+        .unwrap();
+        let output = std::env::temp_dir().join("debateai-tts-failure.wav");
+        let err = generate_audio(
+            &cli,
+            &debateai_core::config::default_config(),
+            &[],
+            &[],
+            &[],
+            "Topic",
+            &output,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(exit_code(err.as_ref()), EXIT_TTS);
+    }
+
+    #[tokio::test]
+    async fn test_compare_swaps_sides_between_runs() {
+        let voices = VoicesConfig::default();
+        let participants = vec![
+            AIParticipant::new("Candidate A", "a", ParticipantRole::For)
+                .with_voice(&voices.for_voice),
+            AIParticipant::new("Candidate B", "b", ParticipantRole::Against)
+                .with_voice(&voices.against_voice),
+        ];
+        let swapped = swap_sides(&participants, &voices);
+
+        let mut transcripts = Vec::new();
+        for run in [&participants, &swapped] {
+            let config = DebateConfig::new("Topic", "http://localhost:1", "");
+            let mut orchestrator = DebateOrchestrator::new(
+                config,
+                run.clone(),
+                Box::new(PresidentialDebateFormat::new(4)),
+            )
+            .unwrap()
+            .with_backend(Arc::new(MockBackend::new()));
+            transcripts.push(orchestrator.run().await.unwrap());
+        }
+
+        // Model a argues FOR and opens the first run, then AGAINST second
+        assert!(
+            transcripts[0][0]
+                .content
+                .contains(" from a. As the candidate arguing FOR")
+        );
+        assert_eq!(transcripts[0][0].speaker_name, "Candidate A");
+        assert!(
+            transcripts[1][0]
+                .content
+                .contains(" from b. As the candidate arguing FOR")
+        );
+        assert_eq!(transcripts[1][0].speaker_name, "Candidate B");
+        assert_eq!(swapped[0].role, ParticipantRole::For);
+        assert_eq!(
+            swapped[0].voice_id.as_deref(),
+            Some(voices.for_voice.as_str())
+        );
+        assert_eq!(swapped[1].role, ParticipantRole::Against);
+
+        let report = comparison_report(&[
+            (&participants, &transcripts[0]),
+            (&swapped, &transcripts[1]),
+        ]);
+        assert!(report.contains("Candidate A (a): FOR in run 1"));
+        assert!(report.contains("AGAINST in run 2"));
+        assert_eq!(
+            path_with_suffix(Path::new("out/debate.md"), "swapped"),
+            PathBuf::from("out/debate-swapped.md")
+        );
+    }
+
+    #[test]
+    fn test_ping_subcommand_parses_without_topic() {
+        let cli = Cli::try_parse_from(["debateai", "ping", "-m", "llama3"]).unwrap();
+        assert!(cli.topic.is_none());
+        assert!(
+            matches!(cli.command, Some(Command::Ping { model }) if model.as_deref() == Some("llama3"))
+        );
+
+        let cli = Cli::try_parse_from(["debateai", "Topic", "-m", "a", "-m", "b"]).unwrap();
+        assert_eq!(cli.topic.as_deref(), Some("Topic"));
+        assert!(cli.command.is_none());
+
+        assert!(Cli::try_parse_from(["debateai", "-m", "a"]).is_err());
+
+        // Main options may precede a subcommand
+        let cli = Cli::try_parse_from(["debateai", "--no-color", "ping"]).unwrap();
+        assert!(cli.no_color);
+        assert!(matches!(cli.command, Some(Command::Ping { .. })));
+    }
+
+    #[test]
+    fn test_subcommands_inherit_main_options() {
+        let cli = Cli::try_parse_from(["debateai", "--config", "x.toml", "check"])
+            .unwrap()
+            .inherit_main_options();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Check { config: Some(path), .. }) if path == Path::new("x.toml")
+        ));
+
+        // An option after the subcommand name wins
+        let cli = Cli::try_parse_from([
+            "debateai", "--config", "x.toml", "-m", "a", "check", "--config", "y.toml",
+        ])
+        .unwrap()
+        .inherit_main_options();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Check { config: Some(path), model, .. })
+                if path == Path::new("y.toml") && model == ["a"]
+        ));
+
+        let cli = Cli::try_parse_from(["debateai", "-m", "llama3", "ping"])
+            .unwrap()
+            .inherit_main_options();
+        assert!(
+            matches!(cli.command, Some(Command::Ping { model }) if model.as_deref() == Some("llama3"))
+        );
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_serve_subcommand_takes_key_options() {
+        let cli = Cli::try_parse_from(["debateai", "serve", "--allow-no-key", "-r", "8"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Serve {
+                allow_no_key: true,
+                strict: false,
+                rounds: Some(8),
+                ..
+            })
+        ));
+
+        let cli =
+            Cli::try_parse_from(["debateai", "--strict", "-r", "8", "--allow-no-key", "serve"])
+                .unwrap()
+                .inherit_main_options();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Serve {
+                allow_no_key: true,
+                strict: true,
+                rounds: Some(8),
+                ..
+            })
+        ));
+    }
 
-    for word in text.split_whitespace() {
-        if current_line_len + word.len() + 1 > width && current_line_len > 0 {
-            result.push('\n');
-            current_line_len = 0;
+    #[test]
+    fn test_api_retries_are_bounded() {
+        let parse = |n: &str| Cli::try_parse_from(["debateai", "Topic", "--api-retries", n]);
+        assert_eq!(parse("10").unwrap().api_retries, 10);
+        assert!(parse("11").is_err());
+        assert!(parse("64").is_err());
+    }
+
+    #[test]
+    fn test_max_audio_minutes_must_be_positive() {
+        let parse = |n: &str| Cli::try_parse_from(["debateai", "Topic", "--max-audio-minutes", n]);
+        assert_eq!(parse("1.5").unwrap().max_audio_minutes, Some(1.5));
+        for bad in ["0", "-2", "NaN", "inf", "ten"] {
+            assert!(parse(bad).is_err(), "{} should be rejected", bad);
         }
-        if current_line_len > 0 {
-            result.push(' ');
-            current_line_len += 1;
+    }
+
+    #[test]
+    fn test_background_gain_must_be_finite_and_non_negative() {
+        let parse = |n: &str| Cli::try_parse_from(["debateai", "Topic", "--background-gain", n]);
+        assert_eq!(parse("0").unwrap().background_gain, 0.0);
+        assert_eq!(parse("0.25").unwrap().background_gain, 0.25);
+        for bad in ["-0.1", "NaN", "inf", "loud"] {
+            assert!(parse(bad).is_err(), "{} should be rejected", bad);
         }
-        result.push_str(word);
-        current_line_len += word.len();
     }
 
-    result
+    #[test]
+    fn test_confirm_refuses_non_interactive_stdin() {
+        let err = confirm_from("Continue? ", false, &mut "y\n".as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("pass --yes"));
+        assert_ne!(exit_code(&err), 0);
+
+        assert!(confirm_from("Continue? ", true, &mut "Yes\n".as_bytes()).unwrap());
+        assert!(!confirm_from("Continue? ", true, &mut "\n".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_schema_subcommand_parses() {
+        let cli = Cli::try_parse_from(["debateai", "schema"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Schema { kind }) if kind == "transcript"));
+        assert!(Cli::try_parse_from(["debateai", "schema", "manifest"]).is_ok());
+        assert!(Cli::try_parse_from(["debateai", "schema", "config"]).is_err());
+    }
+
+    #[test]
+    fn test_rematch_subcommand_parses() {
+        let cli = Cli::try_parse_from(["debateai", "rematch", "debate.json", "New topic"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Rematch { manifest, topic })
+                if manifest.as_path() == std::path::Path::new("debate.json") && topic == "New topic"
+        ));
+
+        assert!(Cli::try_parse_from(["debateai", "rematch", "debate.json"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "debateai",
+            "--disable-audio",
+            "--save-transcript",
+            "out.md",
+            "rematch",
+            "debate.json",
+            "New topic",
+        ])
+        .unwrap();
+        assert!(!cli.audio_enabled());
+        assert_eq!(cli.save_transcript, Some(PathBuf::from("out.md")));
+        assert!(matches!(cli.command, Some(Command::Rematch { .. })));
+    }
+
+    #[test]
+    fn test_section_filters_split_on_commas() {
+        let cli = Cli::try_parse_from([
+            "debateai",
+            "Topic",
+            "--only-sections",
+            "opening statements,Closing Statements",
+        ])
+        .unwrap();
+        let format = FilteredFormat::new(
+            Box::new(PresidentialDebateFormat::new(6)),
+            &cli.only_sections,
+            &cli.skip_sections,
+        )
+        .unwrap();
+        let names: Vec<_> = format.sections().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["Opening Statements", "Closing Statements"]);
+    }
+
+    #[test]
+    fn test_per_participant_args_limited_to_model_count() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(
+                ["debateai", "Topic", "-m", "a", "-m", "b"]
+                    .iter()
+                    .chain(args),
+            )
+            .unwrap()
+        };
+
+        assert!(
+            check_per_participant_args(&parse(&["--name", "Ann", "--voice", "af_sky"])).is_ok()
+        );
+        assert_eq!(
+            check_per_participant_args(&parse(&["--name", "A", "--name", "B", "--name", "C"])),
+            Err(
+                "--name was given 3 times but there are only 2 --models; give at most one per model"
+                    .to_string()
+            )
+        );
+        let voices = parse(&[
+            "--voice",
+            "bf_emma",
+            "--voice",
+            "bm_george",
+            "--voice",
+            "af_sky",
+        ]);
+        assert!(
+            check_per_participant_args(&voices)
+                .unwrap_err()
+                .starts_with("--voice")
+        );
+    }
+
+    #[test]
+    fn test_single_closing_model_needs_a_shared_endpoint() {
+        let participants = vec![
+            AIParticipant::new("A", "a", ParticipantRole::For),
+            AIParticipant::new("B", "b", ParticipantRole::Against),
+        ];
+        assert!(check_shared_endpoint(&participants).is_ok());
+
+        let mut mixed = participants.clone();
+        mixed[1] = mixed[1].clone().with_endpoint("http://localhost:11434/v1");
+        assert!(
+            check_shared_endpoint(&mixed)
+                .unwrap_err()
+                .contains("one --closing-model per --model")
+        );
+    }
+
+    #[test]
+    fn test_check_fails_on_unknown_voice() {
+        let mut config = debateai_core::config::default_config();
+        config.voices.against_voice = "bm_gorge".to_string();
+        let available: Vec<String> = ["bf_emma", "bm_george", "af_sky"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+
+        let outcomes = setup_checks(&config, "presidential", 2, Some(&available));
+        let failed: Vec<_> = outcomes.iter().filter(|(_, r)| r.is_err()).collect();
+        assert_eq!(failed.len(), 1);
+        let (name, result) = failed[0];
+        assert_eq!(name, "voices");
+        let message = result.as_ref().unwrap_err();
+        assert!(message.contains("Unknown voice 'bm_gorge'. Did you mean 'bm_george'?"));
+
+        config.voices.against_voice = "bm_george".to_string();
+        let outcomes = setup_checks(&config, "presidential", 2, Some(&available));
+        assert!(outcomes.iter().all(|(_, r)| r.is_ok()));
+    }
+
+    #[test]
+    fn test_dump_config_includes_voice_overrides() {
+        let cli = Cli::try_parse_from([
+            "debateai",
+            "--dump-config",
+            "json",
+            "--voice",
+            "am_adam",
+            "--announcer-voice",
+            "bf_isabella",
+        ])
+        .unwrap();
+
+        let dump = dump_config(&cli, true).unwrap();
+        assert!(dump.contains("\"for_voice\": \"am_adam\""));
+        assert!(dump.contains("\"announcer_voice\": \"bf_isabella\""));
+    }
+
+    #[test]
+    fn test_estimated_api_calls() {
+        // One turn per speaker in each of the six two-speaker sections
+        assert_eq!(estimated_api_calls(&PresidentialDebateFormat::new(6)), 12);
+        assert_eq!(estimated_api_calls(&PresidentialDebateFormat::new(20)), 40);
+    }
+
+    #[test]
+    fn test_prompt_dump_path() {
+        assert_eq!(
+            prompt_dump_path(Path::new("prompts"), 0, "Candidate A/B"),
+            Path::new("prompts").join("1-Candidate_A_B.txt")
+        );
+        assert!(Cli::try_parse_from(["debateai", "Topic", "--dump-only"]).is_err());
+    }
+
+    #[test]
+    fn test_missing_api_key_is_an_error_in_strict_mode() {
+        let remote = "https://api.example.com/v1";
+        assert_eq!(
+            require_api_key(None, remote, true, false),
+            Ok(String::new())
+        );
+        assert!(
+            require_api_key(None, remote, true, true)
+                .unwrap_err()
+                .contains("OPENAI_API_KEY")
+        );
+        assert_eq!(
+            require_api_key(Some("sk-test".to_string()), remote, true, true),
+            Ok("sk-test".to_string())
+        );
+    }
+
+    #[test]
+    fn test_missing_api_key_rejected_for_remote_endpoints() {
+        let openai = "https://api.openai.com/v1";
+        assert!(require_api_key(None, openai, false, false).is_err());
+        assert!(require_api_key(Some(String::new()), openai, false, false).is_err());
+        assert_eq!(
+            require_api_key(None, "http://localhost:11434/v1", false, true),
+            Ok(String::new())
+        );
+    }
+
+    #[test]
+    fn test_is_local_endpoint() {
+        assert!(is_local_endpoint("http://localhost:11434/v1"));
+        assert!(is_local_endpoint("http://LOCALHOST/v1"));
+        assert!(is_local_endpoint("http://127.0.0.1:8080/v1"));
+        assert!(is_local_endpoint("http://[::1]:8080/v1"));
+        assert!(is_local_endpoint("http://0.0.0.0:1234"));
+        assert!(is_local_endpoint("localhost:8000"));
+
+        assert!(!is_local_endpoint("https://api.openai.com/v1"));
+        assert!(!is_local_endpoint("https://openrouter.ai/api/v1"));
+        assert!(!is_local_endpoint("http://localhost.example.com/v1"));
+        assert!(!is_local_endpoint("http://192.168.1.10:11434/v1"));
+    }
+
+    #[test]
+    fn test_transcript_format_from_path() {
+        use std::path::Path;
+
+        assert_eq!(
+            TranscriptFormat::from_path(Path::new("debate.md")),
+            Ok(TranscriptFormat::Markdown)
+        );
+        assert_eq!(
+            TranscriptFormat::from_path(Path::new("debate.html")),
+            Ok(TranscriptFormat::Html)
+        );
+        assert_eq!(
+            TranscriptFormat::from_path(Path::new("out/Debate.JSON")),
+            Ok(TranscriptFormat::Json)
+        );
+        assert_eq!(
+            TranscriptFormat::from_path(Path::new("debate.csv")),
+            Ok(TranscriptFormat::Csv)
+        );
+        assert!(TranscriptFormat::from_path(Path::new("debate.docx")).is_err());
+        assert!(TranscriptFormat::from_path(Path::new("debate")).is_err());
+    }
+
+    #[test]
+    fn test_color_enabled() {
+        assert!(color_enabled(false, None));
+        assert!(color_enabled(false, Some("".into())));
+        assert!(!color_enabled(true, None));
+        assert!(!color_enabled(false, Some("1".into())));
+    }
+
+    #[test]
+    fn test_color_override_produces_plain_text() {
+        colored::control::set_override(false);
+        assert_eq!("Debate".bright_blue().bold().to_string(), "Debate");
+        assert_eq!("Warning".yellow().to_string(), "Warning");
+        colored::control::unset_override();
+    }
 }
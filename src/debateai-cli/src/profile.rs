@@ -0,0 +1,99 @@
+//! Timing breakdown for `--profile`.
+
+use debateai_core::DebateEvent;
+use std::time::{Duration, Instant};
+
+/// Time spent in each phase of a run.
+#[derive(Debug, Default)]
+pub struct RunProfile {
+    /// Time from each speaker starting to their response arriving, with the
+    /// section it was in.
+    turns: Vec<(String, Duration)>,
+    current_section: String,
+    turn_start: Option<Instant>,
+    /// Wall-clock time of the whole debate.
+    pub debate: Duration,
+    /// Audio timings, when audio was generated.
+    pub audio: AudioTimings,
+}
+
+/// Time spent producing the audio file.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AudioTimings {
+    /// Synthesizing announcements and messages.
+    pub synthesis: Duration,
+    /// Speed adjustment, resampling and writing the WAV file.
+    pub save: Duration,
+}
+
+impl RunProfile {
+    /// Record the timing of a debate event as it is emitted.
+    pub fn record(&mut self, event: &DebateEvent) {
+        match event {
+            DebateEvent::SectionStart { name, .. } => self.current_section = name.clone(),
+            DebateEvent::SpeakerStart { .. } => self.turn_start = Some(Instant::now()),
+            DebateEvent::SpeakerMessage { .. } => {
+                if let Some(start) = self.turn_start.take() {
+                    self.turns
+                        .push((self.current_section.clone(), start.elapsed()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Render the breakdown as a table.
+    pub fn report(&self) -> String {
+        let api: Duration = self.turns.iter().map(|(_, d)| *d).sum();
+        let mut rows = vec![("Debate (total)".to_string(), self.debate)];
+        rows.push(("  API requests".to_string(), api));
+        for (section, time) in sum_by_section(&self.turns) {
+            rows.push((format!("    {}", section), time));
+        }
+        rows.push(("TTS synthesis".to_string(), self.audio.synthesis));
+        rows.push(("Combine and save".to_string(), self.audio.save));
+
+        let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        rows.iter()
+            .map(|(label, time)| format!("{:<width$}  {:>8.2}s\n", label, time.as_secs_f32()))
+            .collect()
+    }
+}
+
+/// Total the turn times of each section, in the order sections first appear.
+///
+/// Sections with the same name (e.g. repeated rebuttal rounds) are combined.
+pub fn sum_by_section(turns: &[(String, Duration)]) -> Vec<(String, Duration)> {
+    let mut totals: Vec<(String, Duration)> = Vec::new();
+    for (section, time) in turns {
+        match totals.iter_mut().find(|(name, _)| name == section) {
+            Some((_, total)) => *total += *time,
+            None => totals.push((section.clone(), *time)),
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sum_by_section() {
+        let ms = Duration::from_millis;
+        let turns = vec![
+            ("Opening".to_string(), ms(100)),
+            ("Opening".to_string(), ms(250)),
+            ("Rebuttal".to_string(), ms(40)),
+            ("Opening".to_string(), ms(10)),
+        ];
+        assert_eq!(
+            sum_by_section(&turns),
+            [
+                ("Opening".to_string(), ms(360)),
+                ("Rebuttal".to_string(), ms(40))
+            ]
+        );
+        assert!(sum_by_section(&[]).is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+//! Small text helpers for console output.
+
+/// Wrap text to `width` characters.
+///
+/// Each line of the input is wrapped on its own, so existing line breaks
+/// (and blank lines between paragraphs) are kept. Words longer than the
+/// width are split across lines.
+pub fn textwrap(text: &str, width: usize) -> String {
+    let width = width.max(1);
+    text.lines()
+        .map(|line| wrap_line(line, width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Wrap a single line of text, hard-breaking words longer than `width`.
+fn wrap_line(line: &str, width: usize) -> String {
+    let mut result = String::new();
+    let mut current_line_len = 0;
+
+    for word in line.split_whitespace() {
+        let mut word_len = word.chars().count();
+        if current_line_len + word_len + 1 > width && current_line_len > 0 {
+            result.push('\n');
+            current_line_len = 0;
+        }
+        if current_line_len > 0 {
+            result.push(' ');
+            current_line_len += 1;
+        }
+
+        // A word wider than the line starts on its own line; split it there
+        let mut rest = word;
+        while word_len > width {
+            let split = rest
+                .char_indices()
+                .nth(width)
+                .map_or(rest.len(), |(i, _)| i);
+            result.push_str(&rest[..split]);
+            result.push('\n');
+            rest = &rest[split..];
+            word_len -= width;
+        }
+        result.push_str(rest);
+        current_line_len += word_len;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_word_is_split_at_width() {
+        let token = "x".repeat(100);
+        let wrapped = textwrap(&format!("see {}", token), 30);
+        let lines: Vec<&str> = wrapped.lines().collect();
+
+        assert_eq!(
+            lines,
+            [
+                "see",
+                &token[..30],
+                &token[30..60],
+                &token[60..90],
+                &token[90..]
+            ]
+        );
+        assert!(lines.iter().all(|l| l.chars().count() <= 30));
+    }
+
+    #[test]
+    fn test_newlines_are_preserved() {
+        assert_eq!(
+            textwrap("First point.\n\nSecond point is longer", 12),
+            "First point.\n\nSecond point\nis longer"
+        );
+    }
+}
@@ -0,0 +1,612 @@
+//! Completion backends.
+//!
+//! Abstracts the chat completion call so the orchestrator can talk to an
+//! OpenAI-compatible API or to an offline mock.
+
+use crate::error::DebateError;
+
+use async_openai::Client;
+use async_openai::config::OpenAIConfig;
+use async_openai::error::OpenAIError;
+use async_openai::types::chat::{
+    ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageContent,
+    ChatCompletionRequestUserMessage, ChatCompletionRequestUserMessageContent,
+    CreateChatCompletionRequest, CreateChatCompletionRequestArgs, FinishReason, ResponseFormat,
+};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+#[cfg(any(test, feature = "test-util"))]
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+/// A single chat completion request.
+#[derive(Debug, Clone)]
+pub struct CompletionRequest {
+    /// The model to query.
+    pub model: String,
+    /// Full message history, starting with the system prompt.
+    pub messages: Vec<ChatCompletionRequestMessage>,
+    /// Maximum number of completion tokens.
+    pub max_tokens: u32,
+    /// Index of the requesting participant, when part of a debate.
+    pub participant_index: Option<usize>,
+    /// Ask for a strict JSON object reply (`response_format: json_object`),
+    /// for requests whose output is parsed rather than read.
+    pub json_mode: bool,
+}
+
+/// The assistant's reply to a completion request.
+#[derive(Debug, Clone, Default)]
+pub struct CompletionResponse {
+    /// The reply text.
+    pub content: String,
+    /// Why the model stopped generating, if reported.
+    pub finish_reason: Option<FinishReason>,
+}
+
+impl CompletionResponse {
+    /// Whether generation stopped because the token limit was reached.
+    pub fn is_truncated(&self) -> bool {
+        self.finish_reason == Some(FinishReason::Length)
+    }
+}
+
+/// Trait for anything that can answer chat completion requests.
+#[async_trait]
+pub trait CompletionBackend: Send + Sync {
+    /// Return the assistant's reply for the given request.
+    async fn complete(&self, request: CompletionRequest)
+    -> Result<CompletionResponse, DebateError>;
+}
+
+/// Backend for OpenAI-compatible chat completion APIs.
+#[derive(Clone)]
+pub struct OpenAIBackend {
+    client: Client<OpenAIConfig>,
+    retries: u32,
+}
+
+impl OpenAIBackend {
+    /// Default number of retries after a failed request.
+    pub const DEFAULT_RETRIES: u32 = 2;
+
+    /// Most retries the CLI accepts; backoff stops growing well before this.
+    pub const MAX_RETRIES: u32 = 10;
+
+    /// Create a backend for the given API base URL and key.
+    pub fn new(api_base: &str, api_key: &str) -> Result<Self, DebateError> {
+        // Create custom HTTP client that skips SSL verification with timeout
+        let http_client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .timeout(std::time::Duration::from_secs(120))
+            .connect_timeout(std::time::Duration::from_secs(30))
+            .build()
+            .map_err(|e| {
+                DebateError::ConfigError(format!("Failed to create HTTP client: {}", e))
+            })?;
+
+        let config = OpenAIConfig::new()
+            .with_api_key(api_key)
+            .with_api_base(api_base);
+
+        Ok(Self {
+            client: Client::with_config(config).with_http_client(http_client),
+            retries: Self::DEFAULT_RETRIES,
+        })
+    }
+
+    /// Set how many times a failed request is retried.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Send a request, with retry logic and exponential backoff for resilience.
+    async fn send(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<CompletionResponse, DebateError> {
+        // Retry logic with exponential backoff
+        let max_attempts = self.retries + 1;
+        let mut last_error = None;
+
+        for attempt in 0..max_attempts {
+            if attempt > 0 {
+                tokio::time::sleep(backoff_delay(attempt)).await;
+            }
+
+            match self.client.chat().create(request.clone()).await {
+                Ok(response) => {
+                    let choice = response.choices.first();
+                    return Ok(CompletionResponse {
+                        content: choice
+                            .and_then(|c| c.message.content.clone())
+                            .unwrap_or_default(),
+                        finish_reason: choice.and_then(|c| c.finish_reason),
+                    });
+                }
+                Err(e) => {
+                    // Rejecting response_format is deterministic, so let the
+                    // caller ask again without it rather than retrying
+                    if request.response_format.is_some() && rejects_response_format(&e) {
+                        return Err(e.into());
+                    }
+                    last_error = Some(e);
+                    // Only retry on transient errors
+                    if attempt < max_attempts - 1 {
+                        continue;
+                    }
+                }
+            }
+        }
+
+        Err(last_error.map(DebateError::from).unwrap_or_else(|| {
+            DebateError::ConfigError("Unknown API error after retries".to_string())
+        }))
+    }
+}
+
+#[async_trait]
+impl CompletionBackend for OpenAIBackend {
+    async fn complete(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse, DebateError> {
+        let result = self
+            .send(build_chat_request(&request, request.json_mode)?)
+            .await;
+        match result {
+            // Not every compatible endpoint supports JSON mode; ask again
+            // without it rather than failing
+            Err(DebateError::OpenAIError(e))
+                if request.json_mode && rejects_response_format(&e) =>
+            {
+                self.send(build_chat_request(&request, false)?).await
+            }
+            result => result,
+        }
+    }
+}
+
+/// Largest backoff exponent: retries wait at most 2^5 = 32 seconds.
+const MAX_BACKOFF_EXPONENT: u32 = 5;
+
+/// Delay before retry number `attempt`: 2s, 4s, 8s, ... capped at 32s, plus
+/// up to 250 ms of jitter so concurrent debates don't retry in lockstep.
+fn backoff_delay(attempt: u32) -> Duration {
+    let jitter_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos() % 250);
+    Duration::from_secs(1u64 << attempt.min(MAX_BACKOFF_EXPONENT))
+        + Duration::from_millis(jitter_ms.into())
+}
+
+/// Build the API request for a completion, optionally in JSON mode.
+fn build_chat_request(
+    request: &CompletionRequest,
+    json_mode: bool,
+) -> Result<CreateChatCompletionRequest, DebateError> {
+    let mut args = CreateChatCompletionRequestArgs::default();
+    args.model(&request.model)
+        .max_completion_tokens(request.max_tokens)
+        .messages(request.messages.clone());
+    if json_mode {
+        args.response_format(ResponseFormat::JsonObject);
+    }
+    Ok(args.build()?)
+}
+
+/// Whether an API error is the endpoint rejecting the `response_format` parameter.
+fn rejects_response_format(error: &OpenAIError) -> bool {
+    match error {
+        OpenAIError::ApiError(api) => {
+            api.param.as_deref() == Some("response_format")
+                || api.message.contains("response_format")
+        }
+        _ => false,
+    }
+}
+
+/// Offline backend that answers requests without an API.
+///
+/// By default each reply is numbered and, for debate turns, continues with
+/// a plausible sentence built from the section and the speaker's side, so
+/// offline runs read naturally. Replies depend only on the request and how
+/// many came before it, so a run is reproducible.
+///
+/// Useful for tests and for exercising the debate flow without an API.
+#[derive(Debug, Default)]
+pub struct MockBackend {
+    response: Option<String>,
+    finish_reason: Option<FinishReason>,
+    calls: AtomicUsize,
+}
+
+impl MockBackend {
+    /// Create a mock backend that generates replies from the prompt.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the response returned for every request.
+    pub fn with_response(mut self, response: impl Into<String>) -> Self {
+        self.response = Some(response.into());
+        self
+    }
+
+    /// Set the finish reason reported with every response.
+    pub fn with_finish_reason(mut self, finish_reason: FinishReason) -> Self {
+        self.finish_reason = Some(finish_reason);
+        self
+    }
+
+    /// Number of requests answered so far.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl CompletionBackend for MockBackend {
+    async fn complete(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse, DebateError> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let content = self
+            .response
+            .clone()
+            .unwrap_or_else(|| mock_reply(&request, call));
+
+        Ok(CompletionResponse {
+            content,
+            finish_reason: self.finish_reason.or(Some(FinishReason::Stop)),
+        })
+    }
+}
+
+/// Claims [`MockBackend`] chooses from for its generated replies.
+const MOCK_CLAIMS: &[&str] = &[
+    "the evidence points clearly in my direction.",
+    "the costs of the alternative have been badly underestimated.",
+    "history offers several instructive precedents here.",
+    "my opponent's case rests on assumptions that do not hold.",
+    "the practical consequences matter more than the theory.",
+];
+
+/// A deterministic reply to `request`, numbered `call`.
+///
+/// For a debate turn (a request whose latest prompt has a `[Section - ...]`
+/// header) the reply continues with the section name and the side and topic
+/// from the system prompt, and a claim picked from those.
+fn mock_reply(request: &CompletionRequest, call: usize) -> String {
+    let mut topic = None;
+    let mut stance = None;
+    let mut section = None;
+    for message in &request.messages {
+        match message {
+            ChatCompletionRequestMessage::System(m) => {
+                if let ChatCompletionRequestSystemMessageContent::Text(text) = &m.content {
+                    topic = text
+                        .lines()
+                        .find_map(|line| line.strip_prefix("TOPIC:"))
+                        .map(str::trim);
+                    stance = if text.contains("argue IN FAVOR OF") {
+                        Some("FOR")
+                    } else if text.contains("argue AGAINST") {
+                        Some("AGAINST")
+                    } else {
+                        None
+                    };
+                }
+            }
+            // Opponent statements carry a name; section prompts don't
+            ChatCompletionRequestMessage::User(m) if m.name.is_none() => {
+                if let ChatCompletionRequestUserMessageContent::Text(text) = &m.content
+                    && let Some(header) = text.strip_prefix('[')
+                {
+                    let header = header.split([']', '\n']).next().unwrap_or_default();
+                    section = Some(header.split(" - ").next().unwrap_or(header).trim());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut reply = format!(
+        "This is mock response number {} from {}.",
+        call, request.model
+    );
+    let Some(section) = section else {
+        return reply;
+    };
+    let side = match stance {
+        Some(stance) => format!(
+            "As the candidate arguing {} {}",
+            stance,
+            topic.unwrap_or("the motion")
+        ),
+        None => "As a participant in this debate".to_string(),
+    };
+    let seed = section.bytes().chain(side.bytes()).fold(call, |hash, b| {
+        hash.wrapping_mul(31).wrapping_add(usize::from(b))
+    });
+    reply.push_str(&format!(
+        " {}, in {} I contend that {}",
+        side,
+        section,
+        MOCK_CLAIMS[seed % MOCK_CLAIMS.len()]
+    ));
+    reply
+}
+
+/// Test backend that replays pre-seeded responses per participant.
+///
+/// Each request pops the next response from the queue of the requesting
+/// participant, so tests can assert exact transcript contents and ordering.
+/// Running out of responses is an error.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Default)]
+pub struct ScriptedBackend {
+    queues: Mutex<HashMap<usize, VecDeque<String>>>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl ScriptedBackend {
+    /// Create a backend with no scripted responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue responses for the participant at `participant_index`.
+    pub fn with_responses<I, S>(self, participant_index: usize, responses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.queues
+            .lock()
+            .unwrap()
+            .entry(participant_index)
+            .or_default()
+            .extend(responses.into_iter().map(Into::into));
+        self
+    }
+
+    /// Number of responses still queued for a participant.
+    pub fn remaining(&self, participant_index: usize) -> usize {
+        self.queues
+            .lock()
+            .unwrap()
+            .get(&participant_index)
+            .map_or(0, VecDeque::len)
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+#[async_trait]
+impl CompletionBackend for ScriptedBackend {
+    async fn complete(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse, DebateError> {
+        let index = request.participant_index.unwrap_or_default();
+        let content = self
+            .queues
+            .lock()
+            .unwrap()
+            .get_mut(&index)
+            .and_then(VecDeque::pop_front)
+            .ok_or_else(|| {
+                DebateError::ConfigError(format!(
+                    "ScriptedBackend has no response left for participant {}",
+                    index
+                ))
+            })?;
+
+        Ok(CompletionResponse {
+            content,
+            finish_reason: Some(FinishReason::Stop),
+        })
+    }
+}
+
+/// Result of a connectivity check against a backend.
+#[derive(Debug)]
+pub struct PingReport {
+    /// The model that was queried.
+    pub model: String,
+    /// Time taken for the request, including any retries.
+    pub latency: Duration,
+    /// The reply on success, or the error on failure.
+    pub result: Result<CompletionResponse, DebateError>,
+}
+
+impl PingReport {
+    /// Whether the backend answered successfully.
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Send a tiny completion request to check the endpoint and key work.
+pub async fn ping(backend: &dyn CompletionBackend, model: &str) -> PingReport {
+    let request = CompletionRequest {
+        model: model.to_string(),
+        messages: vec![ChatCompletionRequestMessage::User(
+            ChatCompletionRequestUserMessage {
+                content: "Reply with the single word: pong".into(),
+                name: None,
+            },
+        )],
+        max_tokens: 16,
+        participant_index: None,
+        json_mode: false,
+    };
+
+    let start = Instant::now();
+    let result = backend.complete(request).await;
+
+    PingReport {
+        model: model.to_string(),
+        latency: start.elapsed(),
+        result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_is_capped() {
+        let first = backoff_delay(1);
+        assert!(first >= Duration::from_secs(2) && first < Duration::from_millis(2250));
+        for attempt in [5, 6, 64, u32::MAX] {
+            let delay = backoff_delay(attempt);
+            assert!(delay >= Duration::from_secs(32) && delay < Duration::from_millis(32250));
+        }
+    }
+
+    #[test]
+    fn test_json_mode_sets_response_format() {
+        let mut request = CompletionRequest {
+            model: "judge".to_string(),
+            messages: Vec::new(),
+            max_tokens: 100,
+            participant_index: None,
+            json_mode: true,
+        };
+        let body = serde_json::to_value(build_chat_request(&request, true).unwrap()).unwrap();
+        assert_eq!(body["response_format"]["type"], "json_object");
+
+        request.json_mode = false;
+        let body = serde_json::to_value(build_chat_request(&request, false).unwrap()).unwrap();
+        assert!(body.get("response_format").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_json_mode_rejection_falls_back_without_retrying() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Endpoint that rejects response_format and answers anything else
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = std::sync::Arc::new(AtomicUsize::new(0));
+        let counted = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                counted.fetch_add(1, Ordering::SeqCst);
+                let mut request = Vec::new();
+                let mut buf = [0; 4096];
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_lowercase();
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length: usize = text
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .map_or(0, |v| v.trim().parse().unwrap());
+                        if request.len() >= end + 4 + length || n == 0 {
+                            break;
+                        }
+                    }
+                }
+                let (status, body) = if String::from_utf8_lossy(&request)
+                    .contains("response_format")
+                {
+                    (
+                        "400 Bad Request",
+                        r#"{"error":{"message":"Unrecognized request argument supplied: response_format","type":"invalid_request_error","param":"response_format","code":null}}"#,
+                    )
+                } else {
+                    (
+                        "200 OK",
+                        r#"{"id":"1","object":"chat.completion","created":0,"model":"m","choices":[{"index":0,"message":{"role":"assistant","content":"plain"},"finish_reason":"stop"}]}"#,
+                    )
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let backend = OpenAIBackend::new(&format!("http://{}/v1", addr), "key")
+            .unwrap()
+            .with_retries(2);
+        let request = CompletionRequest {
+            model: "m".to_string(),
+            messages: vec![ChatCompletionRequestMessage::User(
+                ChatCompletionRequestUserMessage {
+                    content: "Reply in JSON".into(),
+                    name: None,
+                },
+            )],
+            max_tokens: 10,
+            participant_index: None,
+            json_mode: true,
+        };
+        let start = Instant::now();
+        let response = backend.complete(request).await.unwrap();
+        assert_eq!(response.content, "plain");
+        // One rejected request, then one without response_format; no backoff
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_mock_reply_follows_section_and_is_reproducible() {
+        use async_openai::types::chat::ChatCompletionRequestSystemMessage;
+
+        /// A fresh backend's reply to Alice's turn in `section`.
+        async fn reply(section: &str) -> String {
+            let request = CompletionRequest {
+                model: "model-a".to_string(),
+                messages: vec![
+                    ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
+                        content: "You are Alice.\n\nTOPIC: Ban cars downtown\n\n\
+                              Your role is to argue IN FAVOR OF the topic."
+                            .into(),
+                        name: None,
+                    }),
+                    ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+                        content: format!("[{} - Make your case]\nPlease begin.", section).into(),
+                        name: None,
+                    }),
+                ],
+                max_tokens: 100,
+                participant_index: Some(0),
+                json_mode: false,
+            };
+            MockBackend::new().complete(request).await.unwrap().content
+        }
+
+        let opening = reply("Opening Statements").await;
+        assert!(opening.starts_with(
+            "This is mock response number 1 from model-a. As the candidate arguing FOR \
+             Ban cars downtown, in Opening Statements I contend that "
+        ));
+        assert_ne!(opening, reply("Closing Statements").await);
+        assert_eq!(opening, reply("Opening Statements").await);
+    }
+
+    #[tokio::test]
+    async fn test_ping_mock_backend() {
+        let backend = MockBackend::new().with_response("pong");
+        let report = ping(&backend, "test-model").await;
+
+        assert!(report.is_ok());
+        assert_eq!(report.model, "test-model");
+        assert_eq!(report.result.unwrap().content, "pong");
+        assert_eq!(backend.calls(), 1);
+    }
+}
@@ -0,0 +1,129 @@
+//! Fluent construction of debates for library users.
+
+use crate::debate_format;
+use crate::error::DebateError;
+use crate::orchestrator::{DebateConfig, DebateOrchestrator};
+use crate::participant::{AIParticipant, ParticipantRole};
+
+/// Builds a [`DebateOrchestrator`] from a topic, format, participants and API settings.
+///
+/// ```no_run
+/// use debateai_core::{DebateBuilder, ParticipantRole};
+///
+/// let orchestrator = DebateBuilder::new("Should cities ban cars?")
+///     .format("presidential")
+///     .rounds(6)
+///     .participant("Alice", "gpt-4o", ParticipantRole::For)
+///     .participant("Bob", "gpt-4o-mini", ParticipantRole::Against)
+///     .api("https://api.openai.com/v1", "sk-...")
+///     .build()?;
+/// # Ok::<(), debateai_core::DebateError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct DebateBuilder {
+    topic: String,
+    format: String,
+    rounds: u32,
+    participants: Vec<AIParticipant>,
+    api_base: String,
+    api_key: String,
+}
+
+impl DebateBuilder {
+    /// Start building a debate on the given topic.
+    ///
+    /// Defaults to the presidential format with 6 rounds against the
+    /// OpenAI API with no key.
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            format: "presidential".to_string(),
+            rounds: 6,
+            participants: Vec::new(),
+            api_base: "https://api.openai.com/v1".to_string(),
+            api_key: String::new(),
+        }
+    }
+
+    /// Set the debate format by name (see [`debate_format::available_formats`]).
+    pub fn format(mut self, name: impl Into<String>) -> Self {
+        self.format = name.into();
+        self
+    }
+
+    /// Set the number of rounds.
+    pub fn rounds(mut self, rounds: u32) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Add a participant. Participants speak in the order they are added.
+    pub fn participant(
+        self,
+        name: impl Into<String>,
+        model: impl Into<String>,
+        role: ParticipantRole,
+    ) -> Self {
+        self.add_participant(AIParticipant::new(name, model, role))
+    }
+
+    /// Add a fully configured participant (persona, voice, overrides).
+    pub fn add_participant(mut self, participant: AIParticipant) -> Self {
+        self.participants.push(participant);
+        self
+    }
+
+    /// Set the OpenAI-compatible API base URL and key.
+    pub fn api(mut self, api_base: impl Into<String>, api_key: impl Into<String>) -> Self {
+        self.api_base = api_base.into();
+        self.api_key = api_key.into();
+        self
+    }
+
+    /// Validate the setup and create the orchestrator.
+    pub fn build(self) -> Result<DebateOrchestrator, DebateError> {
+        let format = debate_format::get_format(&self.format, self.rounds)
+            .ok_or_else(|| DebateError::UnknownFormat(self.format.clone()))?;
+        let config = DebateConfig::new(self.topic, self.api_base, self.api_key);
+
+        DebateOrchestrator::new(config, self.participants, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builds_valid_orchestrator() {
+        let orchestrator = DebateBuilder::new("Test topic")
+            .format("presidential")
+            .rounds(6)
+            .participant("Alice", "model-a", ParticipantRole::For)
+            .participant("Bob", "model-b", ParticipantRole::Against)
+            .api("http://localhost:1", "")
+            .build()
+            .unwrap();
+
+        assert_eq!(orchestrator.config().topic, "Test topic");
+        assert_eq!(orchestrator.config().api_base, "http://localhost:1");
+    }
+
+    #[test]
+    fn test_too_few_participants_is_an_error() {
+        let result = DebateBuilder::new("Test topic")
+            .participant("Alice", "model-a", ParticipantRole::For)
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(DebateError::InvalidParticipantCount { actual: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_unknown_format_is_an_error() {
+        let result = DebateBuilder::new("Test topic").format("oxford").build();
+        assert!(matches!(result, Err(DebateError::UnknownFormat(name)) if name == "oxford"));
+    }
+}
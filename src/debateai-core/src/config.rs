@@ -1,27 +1,36 @@
 //! Configuration module for loading TOML config files.
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
 use crate::error::DebateError;
+use crate::judge::JudgeConfig;
+use crate::participant::{AIParticipant, ParticipantRole};
 
 /// Root configuration structure.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub debate: DebateFormatsConfig,
+    #[serde(default)]
     pub voices: VoicesConfig,
     pub prompts: PromptsConfig,
+    #[serde(default)]
+    pub audio: AudioConfig,
+    #[serde(default)]
+    pub judge: JudgeConfig,
 }
 
 /// Configuration for all debate formats.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DebateFormatsConfig {
     pub presidential: PresidentialConfig,
 }
 
 /// Configuration for presidential debate format.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PresidentialConfig {
     pub name: String,
     pub display_name: String,
@@ -32,7 +41,7 @@ pub struct PresidentialConfig {
 }
 
 /// Configuration for a debate section.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SectionConfig {
     pub name: String,
     pub description: String,
@@ -41,11 +50,16 @@ pub struct SectionConfig {
 }
 
 /// Voice configuration for TTS.
-#[derive(Debug, Clone, Deserialize)]
+///
+/// Any subset of fields may be given; the rest use the defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct VoicesConfig {
     pub for_voice: String,
     pub against_voice: String,
     pub announcer_voice: String,
+    /// Voices for custom roles, keyed by role label (e.g. "Prime Minister").
+    pub roles: HashMap<String, String>,
 }
 
 impl Default for VoicesConfig {
@@ -54,17 +68,83 @@ impl Default for VoicesConfig {
             for_voice: "bf_emma".to_string(),
             against_voice: "bm_george".to_string(),
             announcer_voice: "af_sky".to_string(),
+            roles: HashMap::new(),
         }
     }
 }
 
+impl VoicesConfig {
+    /// Get the voice ID for a participant role.
+    ///
+    /// Custom roles without an entry in `roles` use the announcer voice.
+    pub fn voice_for_role(&self, role: &ParticipantRole) -> &str {
+        match role {
+            ParticipantRole::For => &self.for_voice,
+            ParticipantRole::Against => &self.against_voice,
+            ParticipantRole::Neutral => &self.announcer_voice,
+            ParticipantRole::Custom(label) => self
+                .roles
+                .get(label)
+                .map(String::as_str)
+                .unwrap_or(&self.announcer_voice),
+        }
+    }
+}
+
+/// Audio post-processing configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AudioConfig {
+    /// Words replaced with a censor tone in the synthesized audio
+    /// (matched case-insensitively as whole words).
+    #[serde(default)]
+    pub censored_words: Vec<String>,
+    /// Phonetic respellings for words the TTS engine mispronounces.
+    #[serde(default)]
+    pub pronunciations: Pronunciations,
+}
+
 /// System prompts configuration.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PromptsConfig {
     pub for_prompt: String,
     pub against_prompt: String,
-    #[serde(default)]
+    /// Spoken at each section transition. Supports `{section_name}`,
+    /// `{section_description}`, `{section_index}` and `{section_total}`.
+    #[serde(default = "default_announcer_template")]
     pub announcer_template: String,
+    /// Spoken at the start of the audio. Supports `{topic}`, `{for_name}`,
+    /// `{against_name}`, `{for_model}` and `{against_model}`.
+    #[serde(default = "default_opening_announcement")]
+    pub opening_announcement: String,
+    /// System prompt for a moderator model that writes framing questions.
+    /// Supports `{topic}`, `{section_name}` and `{section_description}`.
+    #[serde(default = "default_moderator_prompt")]
+    pub moderator_prompt: String,
+}
+
+/// Replace the placeholders in a moderator prompt template.
+pub(crate) fn render_moderator_prompt(
+    template: &str,
+    topic: &str,
+    section_name: &str,
+    section_description: &str,
+) -> String {
+    template
+        .replace("{topic}", topic)
+        .replace("{section_name}", section_name)
+        .replace("{section_description}", section_description)
+}
+
+fn default_announcer_template() -> String {
+    DEFAULT_ANNOUNCER_TEMPLATE.to_string()
+}
+
+fn default_opening_announcement() -> String {
+    DEFAULT_OPENING_ANNOUNCEMENT.to_string()
+}
+
+fn default_moderator_prompt() -> String {
+    DEFAULT_MODERATOR_PROMPT.to_string()
 }
 
 impl Config {
@@ -73,17 +153,91 @@ impl Config {
         let content = fs::read_to_string(path.as_ref())
             .map_err(|e| DebateError::ConfigError(format!("Failed to read config: {}", e)))?;
 
-        toml::from_str(&content)
-            .map_err(|e| DebateError::ConfigError(format!("Failed to parse config: {}", e)))
+        Self::from_str(&content)
     }
 
     /// Load configuration from string content.
     pub fn from_str(content: &str) -> Result<Self, DebateError> {
-        toml::from_str(content)
-            .map_err(|e| DebateError::ConfigError(format!("Failed to parse config: {}", e)))
+        let config: Self = toml::from_str(content)
+            .map_err(|e| DebateError::ConfigError(format!("Failed to parse config: {}", e)))?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check the configuration for values that would fail at run time.
+    ///
+    /// Voices are not checked here, as that needs the TTS engine; see
+    /// [`check_voices`](crate::tts::check_voices).
+    pub fn validate(&self) -> Result<(), DebateError> {
+        let presidential = &self.debate.presidential;
+        if presidential.min_participants > presidential.max_participants {
+            return Err(DebateError::ConfigError(format!(
+                "min_participants ({}) is greater than max_participants ({})",
+                presidential.min_participants, presidential.max_participants
+            )));
+        }
+        for section in &presidential.sections {
+            if section.speaker_order.is_empty() {
+                return Err(DebateError::ConfigError(format!(
+                    "Section '{}' has no speakers",
+                    section.name
+                )));
+            }
+            if section.max_tokens == 0 {
+                return Err(DebateError::ConfigError(format!(
+                    "Section '{}' has a token budget of 0",
+                    section.name
+                )));
+            }
+        }
+
+        // Older configs wrapped the section in brackets, which the TTS
+        // engine reads aloud
+        if self.prompts.announcer_template.contains("[DEBATE SECTION:") {
+            return Err(DebateError::ConfigError(format!(
+                "announcer_template uses the old '[DEBATE SECTION: ...]' syntax; \
+                 use plain text such as \"{}\" or remove the key for the default",
+                DEFAULT_ANNOUNCER_TEMPLATE
+            )));
+        }
+
+        #[cfg(feature = "templating")]
+        {
+            crate::template::validate(&self.prompts.for_prompt)?;
+            crate::template::validate(&self.prompts.against_prompt)?;
+        }
+
+        self.judge.validate()?;
+
+        Ok(())
+    }
+
+    /// Serialize the configuration together with the API settings in effect,
+    /// as TOML or (if `json` is set) pretty-printed JSON. The API key is redacted.
+    pub fn dump(&self, api_base: &str, api_key: &str, json: bool) -> Result<String, DebateError> {
+        let effective = EffectiveConfig {
+            api: ApiSettings {
+                base: api_base,
+                key: redact_api_key(api_key),
+            },
+            config: self,
+        };
+
+        if json {
+            serde_json::to_string_pretty(&effective)
+                .map_err(|e| DebateError::ConfigError(format!("Failed to serialize config: {}", e)))
+        } else {
+            toml::to_string(&effective)
+                .map_err(|e| DebateError::ConfigError(format!("Failed to serialize config: {}", e)))
+        }
     }
 
     /// Get the system prompt for a participant, with placeholders replaced.
+    ///
+    /// With the `templating` feature, prompts containing `{{...}}` tags are
+    /// rendered as templates with the variables `name`, `topic`,
+    /// `opponent_name`, `role`, `is_for` and `is_against`.
     pub fn get_prompt(&self, is_for: bool, name: &str, topic: &str, opponent_name: &str) -> String {
         let template = if is_for {
             &self.prompts.for_prompt
@@ -91,12 +245,94 @@ impl Config {
             &self.prompts.against_prompt
         };
 
+        #[cfg(feature = "templating")]
+        if template.contains("{{") {
+            let vars = HashMap::from([
+                ("name", name.to_string()),
+                ("topic", topic.to_string()),
+                ("opponent_name", opponent_name.to_string()),
+                ("role", if is_for { "FOR" } else { "AGAINST" }.to_string()),
+                ("is_for", is_for.to_string()),
+                ("is_against", (!is_for).to_string()),
+            ]);
+            // Templates are validated on load; fall through to plain
+            // replacement for configs built by hand.
+            if let Ok(prompt) = crate::template::render(template, &vars) {
+                return prompt;
+            }
+        }
+
         template
             .replace("{name}", name)
             .replace("{topic}", topic)
             .replace("{opponent_name}", opponent_name)
     }
 
+    /// Build the opening announcement for the given topic and participants.
+    ///
+    /// Participants are matched by role, falling back to list position when
+    /// no participant has the `For`/`Against` role.
+    pub fn opening_announcement(&self, topic: &str, participants: &[AIParticipant]) -> String {
+        let find = |role: ParticipantRole, index: usize| {
+            participants
+                .iter()
+                .find(|p| p.role == role)
+                .or_else(|| participants.get(index))
+        };
+        let for_participant = find(ParticipantRole::For, 0);
+        let against_participant = find(ParticipantRole::Against, 1);
+
+        self.prompts
+            .opening_announcement
+            .replace("{topic}", topic)
+            .replace("{for_name}", for_participant.map_or("", |p| &p.name))
+            .replace(
+                "{against_name}",
+                against_participant.map_or("", |p| &p.name),
+            )
+            .replace("{for_model}", for_participant.map_or("", |p| &p.model))
+            .replace(
+                "{against_model}",
+                against_participant.map_or("", |p| &p.model),
+            )
+    }
+
+    /// Build the announcement for a section from the announcer template.
+    ///
+    /// Supports `{section_name}`, `{section_description}`, and the 1-based
+    /// `{section_index}` out of `{section_total}`.
+    pub fn announcer_text(
+        &self,
+        section_name: &str,
+        section_description: &str,
+        section_index: usize,
+        section_total: usize,
+    ) -> String {
+        self.prompts
+            .announcer_template
+            .replace("{section_name}", section_name)
+            .replace("{section_description}", section_description)
+            .replace("{section_index}", &section_index.to_string())
+            .replace("{section_total}", &section_total.to_string())
+            .trim()
+            .to_string()
+    }
+
+    /// Get the moderator's system prompt for a section, with placeholders replaced.
+    pub fn moderator_prompt(
+        &self,
+        topic: &str,
+        section_name: &str,
+        section_description: &str,
+    ) -> String {
+        render_moderator_prompt(
+            &self.prompts.moderator_prompt,
+            topic,
+            section_name,
+            section_description,
+        )
+    }
+
     /// Get voice ID for a participant role.
     pub fn get_voice(&self, is_for: bool) -> &str {
         if is_for {
@@ -107,6 +343,100 @@ impl Config {
     }
 }
 
+/// The configuration in effect for a run, as shown by `--dump-config`.
+#[derive(Serialize)]
+struct EffectiveConfig<'a> {
+    api: ApiSettings<'a>,
+    #[serde(flatten)]
+    config: &'a Config,
+}
+
+/// Resolved API settings, with the key redacted.
+#[derive(Serialize)]
+struct ApiSettings<'a> {
+    base: &'a str,
+    key: String,
+}
+
+/// Hide all but the last four characters of an API key.
+pub fn redact_api_key(key: &str) -> String {
+    let len = key.chars().count();
+    if len == 0 {
+        String::new()
+    } else if len <= 8 {
+        "****".to_string()
+    } else {
+        let tail: String = key.chars().skip(len - 4).collect();
+        format!("****{}", tail)
+    }
+}
+
+/// Short names for long model IDs (e.g. `sonnet` for a provider-specific ID).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct ModelAliases(HashMap<String, String>);
+
+impl ModelAliases {
+    /// Load aliases from a file: JSON if it has a `.json` extension, TOML otherwise.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, DebateError> {
+        load_map_file(path.as_ref(), "aliases")
+    }
+
+    /// Resolve a model name, returning it unchanged if it is not an alias.
+    pub fn resolve<'a>(&'a self, model: &'a str) -> &'a str {
+        self.0.get(model).map_or(model, String::as_str)
+    }
+}
+
+/// Phonetic respellings for words the TTS engine mispronounces
+/// (e.g. `Qwen = "chwen"`, `LLM = "el el em"`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Pronunciations(HashMap<String, String>);
+
+impl Pronunciations {
+    /// Load respellings from a file: JSON if it has a `.json` extension, TOML otherwise.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, DebateError> {
+        load_map_file(path.as_ref(), "pronunciations")
+    }
+
+    /// Add respellings, replacing existing entries for the same phrase.
+    pub fn extend(&mut self, other: Pronunciations) {
+        self.0.extend(other.0);
+    }
+
+    /// Iterate over `(phrase, respelling)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+impl FromIterator<(String, String)> for Pronunciations {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// Read a JSON (by `.json` extension) or TOML file; `what` names it in errors.
+fn load_map_file<T: serde::de::DeserializeOwned>(
+    path: &Path,
+    what: &str,
+) -> Result<T, DebateError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| DebateError::ConfigError(format!("Failed to read {}: {}", what, e)))?;
+
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    if is_json {
+        serde_json::from_str(&content)
+            .map_err(|e| DebateError::ConfigError(format!("Failed to parse {}: {}", what, e)))
+    } else {
+        toml::from_str(&content)
+            .map_err(|e| DebateError::ConfigError(format!("Failed to parse {}: {}", what, e)))
+    }
+}
+
 /// Default configuration embedded in the binary.
 pub fn default_config() -> Config {
     Config {
@@ -160,12 +490,36 @@ pub fn default_config() -> Config {
         prompts: PromptsConfig {
             for_prompt: DEFAULT_FOR_PROMPT.to_string(),
             against_prompt: DEFAULT_AGAINST_PROMPT.to_string(),
-            announcer_template: "[DEBATE SECTION: {section_name}]\n{section_description}"
-                .to_string(),
+            announcer_template: default_announcer_template(),
+            opening_announcement: default_opening_announcement(),
+            moderator_prompt: default_moderator_prompt(),
         },
+        audio: AudioConfig::default(),
+        judge: JudgeConfig::default(),
     }
 }
 
+const DEFAULT_ANNOUNCER_TEMPLATE: &str =
+    "Section {section_index} of {section_total}: {section_name}. {section_description}";
+
+const DEFAULT_OPENING_ANNOUNCEMENT: &str =
+    "Tonight's debate: {topic}. Arguing in favor, {for_name}; arguing against, {against_name}.";
+
+const DEFAULT_MODERATOR_PROMPT: &str = r#"You are the moderator of a formal presidential-style debate.
+
+DEBATE TOPIC: {topic}
+CURRENT SECTION: {section_name} - {section_description}
+
+YOUR ROLE:
+- Ask one clear, neutral question that frames this section for both candidates
+- Do NOT take a side or argue either position
+- Keep it to one or two sentences
+
+FORMAT:
+- Plain spoken English only. NO asterisks, markdown, bullets, or formatting.
+- This will be read aloud by text-to-speech.
+"#;
+
 const DEFAULT_FOR_PROMPT: &str = r#"You are {name} participating in a formal presidential-style debate.
 
 DEBATE TOPIC: {topic}
@@ -221,3 +575,190 @@ CRITICAL OUTPUT RULES:
 - Do NOT include asterisks for emphasis or any markdown formatting
 - The announcer provides context - just deliver your argument directly
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_aliases_resolve() {
+        let dir = std::env::temp_dir().join(format!("debateai-aliases-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let toml_path = dir.join("aliases.toml");
+        let json_path = dir.join("aliases.json");
+        fs::write(&toml_path, "fast = \"provider/fast-model-2025-01-01\"\n").unwrap();
+        fs::write(&json_path, r#"{"fast": "provider/fast-model-2025-01-01"}"#).unwrap();
+
+        for path in [&toml_path, &json_path] {
+            let aliases = ModelAliases::load(path).unwrap();
+            assert_eq!(aliases.resolve("fast"), "provider/fast-model-2025-01-01");
+            assert_eq!(aliases.resolve("llama3:8b"), "llama3:8b");
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "templating")]
+    #[test]
+    fn test_get_prompt_renders_conditionals() {
+        let mut config = default_config();
+        let template =
+            "{{name}} vs {{opponent_name}}: {{#if is_for}}support{{else}}oppose{{/if}} {{topic}}";
+        config.prompts.for_prompt = template.to_string();
+        config.prompts.against_prompt = template.to_string();
+
+        assert_eq!(
+            config.get_prompt(true, "Alice", "the motion", "Bob"),
+            "Alice vs Bob: support the motion"
+        );
+        assert_eq!(
+            config.get_prompt(false, "Bob", "the motion", "Alice"),
+            "Bob vs Alice: oppose the motion"
+        );
+    }
+
+    #[test]
+    fn test_announcer_text_with_section_index() {
+        let mut config = default_config();
+        config.prompts.announcer_template =
+            "[{section_index}/{section_total}] {section_name}: {section_description}".to_string();
+
+        assert_eq!(
+            config.announcer_text("Rebuttals", "Respond to your opponent.", 3, 6),
+            "[3/6] Rebuttals: Respond to your opponent."
+        );
+        assert_eq!(
+            default_config().announcer_text("Closing Statements", "Final remarks.", 6, 6),
+            "Section 6 of 6: Closing Statements. Final remarks."
+        );
+    }
+
+    #[test]
+    fn test_missing_announcer_template_uses_default() {
+        let shipped = include_str!("../../../config.toml");
+        let line = shipped
+            .lines()
+            .find(|line| line.starts_with("announcer_template"))
+            .unwrap();
+        let content = shipped.replace(line, "");
+
+        let config = Config::from_str(&content).unwrap();
+        assert_eq!(
+            config.announcer_text("Rebuttals", "Respond.", 3, 6),
+            "Section 3 of 6: Rebuttals. Respond."
+        );
+
+        let legacy = shipped.replace(
+            line,
+            "announcer_template = \"[DEBATE SECTION: {section_name}]\"",
+        );
+        let err = Config::from_str(&legacy).unwrap_err().to_string();
+        assert!(err.contains("[DEBATE SECTION: ...]"), "{}", err);
+    }
+
+    #[test]
+    fn test_moderator_prompt_substitutes_topic_and_section() {
+        let mut config = default_config();
+        config.prompts.moderator_prompt =
+            "Moderate '{topic}'. Now: {section_name} ({section_description})".to_string();
+
+        assert_eq!(
+            config.moderator_prompt("Nuclear power", "Rebuttals", "Respond directly."),
+            "Moderate 'Nuclear power'. Now: Rebuttals (Respond directly.)"
+        );
+
+        let default = default_config().moderator_prompt("Nuclear power", "Rebuttals", "");
+        assert!(default.contains("DEBATE TOPIC: Nuclear power"));
+        assert!(default.contains("CURRENT SECTION: Rebuttals"));
+        // Independent of the debater prompts
+        assert!(!default.contains("{"));
+    }
+
+    #[test]
+    fn test_opening_announcement() {
+        let config = default_config();
+        let participants = vec![
+            AIParticipant::new("Candidate A", "model-a", ParticipantRole::For),
+            AIParticipant::new("Candidate B", "model-b", ParticipantRole::Against),
+        ];
+
+        assert_eq!(
+            config.opening_announcement("Universal basic income", &participants),
+            "Tonight's debate: Universal basic income. Arguing in favor, Candidate A; \
+             arguing against, Candidate B."
+        );
+    }
+
+    #[test]
+    fn test_voice_for_custom_role() {
+        let mut voices = VoicesConfig::default();
+        voices
+            .roles
+            .insert("Prime Minister".to_string(), "bm_fable".to_string());
+
+        let pm = ParticipantRole::Custom("Prime Minister".to_string());
+        let whip = ParticipantRole::Custom("Whip".to_string());
+
+        assert_eq!(voices.voice_for_role(&pm), "bm_fable");
+        assert_eq!(voices.voice_for_role(&whip), voices.announcer_voice);
+        assert_eq!(voices.voice_for_role(&ParticipantRole::For), "bf_emma");
+    }
+
+    #[test]
+    fn test_dump_redacts_key() {
+        let config = default_config();
+        let key = "sk-secret-key-1234";
+
+        let toml_dump = config
+            .dump("http://localhost:11434/v1", key, false)
+            .unwrap();
+        assert!(toml_dump.contains("base = \"http://localhost:11434/v1\""));
+        assert!(toml_dump.contains("****1234"));
+        assert!(!toml_dump.contains(key));
+        assert!(toml_dump.contains("[voices]"));
+
+        let json_dump = config.dump("http://localhost:11434/v1", key, true).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json_dump).unwrap();
+        assert_eq!(value["api"]["key"], "****1234");
+        assert_eq!(value["voices"]["for_voice"], config.voices.for_voice);
+
+        assert_eq!(redact_api_key(""), "");
+        assert_eq!(redact_api_key("short"), "****");
+    }
+
+    #[test]
+    fn test_partial_voices_use_defaults() {
+        let content = include_str!("../../../config.toml")
+            .replace("for_voice = \"bf_emma\"\n", "")
+            .replace("against_voice = \"bm_fable\"\n", "")
+            .replace(
+                "announcer_voice = \"af_sky\"",
+                "announcer_voice = \"am_adam\"",
+            );
+        assert!(!content.contains("for_voice"));
+
+        let config = Config::from_str(&content).unwrap();
+        let defaults = VoicesConfig::default();
+        assert_eq!(config.voices.announcer_voice, "am_adam");
+        assert_eq!(config.voices.for_voice, defaults.for_voice);
+        assert_eq!(config.voices.against_voice, defaults.against_voice);
+    }
+
+    #[test]
+    fn test_voices_roles_from_toml() {
+        let voices: VoicesConfig = toml::from_str(
+            r#"
+            for_voice = "bf_emma"
+            against_voice = "bm_fable"
+            announcer_voice = "af_sky"
+
+            [roles]
+            "Devil's Advocate" = "am_adam"
+            "#,
+        )
+        .unwrap();
+
+        let role = ParticipantRole::Custom("Devil's Advocate".to_string());
+        assert_eq!(voices.voice_for_role(&role), "am_adam");
+    }
+}
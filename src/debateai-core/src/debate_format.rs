@@ -4,6 +4,10 @@
 //! for different debate styles (presidential, parliamentary, etc.).
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::error::DebateError;
+use crate::participant::ParticipantRole;
 
 /// A section within a debate (e.g., opening statements, rebuttals).
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +21,42 @@ pub struct DebateSection {
     pub speaker_order: Vec<usize>,
     /// Maximum response length hint for each speaker in this section.
     pub max_tokens: u32,
+    /// Instructions for specific speakers, keyed by participant index.
+    /// Speakers without an entry get the generic section prompt.
+    #[serde(default)]
+    pub per_speaker_prompts: HashMap<usize, String>,
+    /// Remind each speaker of their opponents' earlier turns in this section's prompt.
+    #[serde(default)]
+    pub summarize_opponent: bool,
+    /// Model every speaker uses in this section instead of their own
+    /// (e.g. a stronger model for closing statements).
+    #[serde(default)]
+    pub model_override: Option<String>,
+    /// Models specific speakers use in this section, keyed by participant
+    /// index. An entry takes precedence over `model_override`.
+    #[serde(default)]
+    pub per_speaker_models: HashMap<usize, String>,
+    /// Keep participants' histories up to the end of this section when
+    /// trimming to a history window (e.g. the opening statements).
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl DebateSection {
+    /// Build the turn prompt for a speaker in this section.
+    pub fn speaker_prompt(&self, speaker_idx: usize) -> String {
+        match self.per_speaker_prompts.get(&speaker_idx) {
+            Some(instructions) => {
+                format!("[{} - {}]\n{}", self.name, self.description, instructions)
+            }
+            None => format!(
+                "[{} - {}]\nPlease provide your {}.",
+                self.name,
+                self.description,
+                self.name.to_lowercase()
+            ),
+        }
+    }
 }
 
 /// Trait for defining debate formats.
@@ -39,10 +79,46 @@ pub trait DebateFormat: Send + Sync {
     /// Minimum number of participants required.
     fn min_participants(&self) -> usize;
 
+    /// Number of rounds the format runs, or `None` if it has no notion of
+    /// rounds.
+    ///
+    /// Formats with a fixed number of rounds report that fixed count.
+    fn rounds(&self) -> Option<u32> {
+        None
+    }
+
+    /// Set the number of rounds, for formats where it is configurable.
+    ///
+    /// Formats with a fixed number of rounds keep the default, which ignores
+    /// the value. Formats may also clamp it, so read [`rounds`](Self::rounds)
+    /// afterwards for the count actually used.
+    fn set_rounds(&mut self, rounds: u32) {
+        let _ = rounds;
+    }
+
     /// Get system prompt for a participant based on their role.
     fn system_prompt(&self, topic: &str, role_name: &str, opponent_name: &str) -> String;
+
+    /// Get system prompt for a participant, taking their stance from `role`
+    /// rather than from the text of `role_name`.
+    ///
+    /// Defaults to [`system_prompt`](Self::system_prompt); formats whose
+    /// prompt depends on the side should override it.
+    fn system_prompt_for_role(
+        &self,
+        topic: &str,
+        role_name: &str,
+        role: &ParticipantRole,
+        opponent_name: &str,
+    ) -> String {
+        let _ = role;
+        self.system_prompt(topic, role_name, opponent_name)
+    }
 }
 
+/// Number of rounds formats with a configurable round count run by default.
+pub const DEFAULT_ROUNDS: u32 = 6;
+
 /// Presidential Debate Format (Michael Douglass style).
 ///
 /// A formal two-person debate with configurable rounds:
@@ -52,20 +128,75 @@ pub trait DebateFormat: Send + Sync {
 /// - Closing statements (1 round)
 #[derive(Debug, Clone)]
 pub struct PresidentialDebateFormat {
+    /// Rounds as requested; clamped to the allowed range when read.
     rounds: u32,
+    max_rounds: u32,
+    opening_tokens: u32,
+    main_tokens: u32,
+    rebuttal_tokens: u32,
+    closing_tokens: u32,
 }
 
 impl PresidentialDebateFormat {
+    /// Default token budget for opening statements.
+    pub const DEFAULT_OPENING_TOKENS: u32 = 300;
+    /// Default token budget for each main argument round.
+    pub const DEFAULT_MAIN_TOKENS: u32 = 400;
+    /// Default token budget for rebuttals.
+    pub const DEFAULT_REBUTTAL_TOKENS: u32 = 400;
+    /// Default token budget for closing statements.
+    pub const DEFAULT_CLOSING_TOKENS: u32 = 250;
+    /// Fewest rounds the format runs: opening, one main round, rebuttal and
+    /// closing.
+    pub const MIN_ROUNDS: u32 = 4;
+    /// Default cap on the number of rounds, so a mistyped round count
+    /// cannot start a runaway debate.
+    pub const DEFAULT_MAX_ROUNDS: u32 = 50;
+
     pub fn new(rounds: u32) -> Self {
         Self {
-            rounds: rounds.max(4),
+            rounds,
+            max_rounds: Self::DEFAULT_MAX_ROUNDS,
+            opening_tokens: Self::DEFAULT_OPENING_TOKENS,
+            main_tokens: Self::DEFAULT_MAIN_TOKENS,
+            rebuttal_tokens: Self::DEFAULT_REBUTTAL_TOKENS,
+            closing_tokens: Self::DEFAULT_CLOSING_TOKENS,
         }
     }
+
+    /// Set the `max_tokens` budget for each kind of section.
+    pub fn with_section_tokens(
+        mut self,
+        opening: u32,
+        main: u32,
+        rebuttal: u32,
+        closing: u32,
+    ) -> Self {
+        self.opening_tokens = opening;
+        self.main_tokens = main;
+        self.rebuttal_tokens = rebuttal;
+        self.closing_tokens = closing;
+        self
+    }
+
+    /// Cap the number of rounds at `max` (at least [`MIN_ROUNDS`](Self::MIN_ROUNDS)).
+    ///
+    /// The cap applies to the requested rounds whether they were set before
+    /// or after it.
+    pub fn with_max_rounds(mut self, max: u32) -> Self {
+        self.max_rounds = max.max(Self::MIN_ROUNDS);
+        self
+    }
+
+    /// The requested rounds, clamped to the allowed range.
+    fn clamped_rounds(&self) -> u32 {
+        self.rounds.clamp(Self::MIN_ROUNDS, self.max_rounds)
+    }
 }
 
 impl Default for PresidentialDebateFormat {
     fn default() -> Self {
-        Self::new(6)
+        Self::new(DEFAULT_ROUNDS)
     }
 }
 
@@ -86,11 +217,16 @@ impl DebateFormat for PresidentialDebateFormat {
             name: "Opening Statements".to_string(),
             description: "Each candidate presents their initial position on the topic.".to_string(),
             speaker_order: vec![0, 1],
-            max_tokens: 300,
+            max_tokens: self.opening_tokens,
+            per_speaker_prompts: HashMap::new(),
+            summarize_opponent: false,
+            model_override: None,
+            per_speaker_models: HashMap::new(),
+            pinned: true,
         });
 
         // Main argument rounds (rounds - 3 to account for opening, rebuttal, closing)
-        let main_rounds = (self.rounds as i32 - 3).max(1) as usize;
+        let main_rounds = (self.clamped_rounds() - 3) as usize;
         for i in 0..main_rounds {
             let alternate = i % 2 == 1;
             sections.push(DebateSection {
@@ -98,7 +234,12 @@ impl DebateFormat for PresidentialDebateFormat {
                 description: "Candidates elaborate on their positions with supporting arguments."
                     .to_string(),
                 speaker_order: if alternate { vec![1, 0] } else { vec![0, 1] },
-                max_tokens: 400,
+                max_tokens: self.main_tokens,
+                per_speaker_prompts: HashMap::new(),
+                summarize_opponent: false,
+                model_override: None,
+                per_speaker_models: HashMap::new(),
+                pinned: false,
             });
         }
 
@@ -107,7 +248,12 @@ impl DebateFormat for PresidentialDebateFormat {
             name: "Rebuttals".to_string(),
             description: "Candidates respond to their opponent's arguments.".to_string(),
             speaker_order: vec![1, 0], // Reversed order for rebuttals
-            max_tokens: 400,
+            max_tokens: self.rebuttal_tokens,
+            per_speaker_prompts: HashMap::new(),
+            summarize_opponent: false,
+            model_override: None,
+            per_speaker_models: HashMap::new(),
+            pinned: false,
         });
 
         // Closing Statements (final round)
@@ -115,7 +261,12 @@ impl DebateFormat for PresidentialDebateFormat {
             name: "Closing Statements".to_string(),
             description: "Final remarks and summation of positions.".to_string(),
             speaker_order: vec![0, 1],
-            max_tokens: 250,
+            max_tokens: self.closing_tokens,
+            per_speaker_prompts: HashMap::new(),
+            summarize_opponent: true,
+            model_override: None,
+            per_speaker_models: HashMap::new(),
+            pinned: false,
         });
 
         sections
@@ -129,9 +280,45 @@ impl DebateFormat for PresidentialDebateFormat {
         2
     }
 
+    fn rounds(&self) -> Option<u32> {
+        Some(self.clamped_rounds())
+    }
+
+    fn set_rounds(&mut self, rounds: u32) {
+        self.rounds = rounds;
+    }
+
     fn system_prompt(&self, topic: &str, role_name: &str, opponent_name: &str) -> String {
-        format!(
-            r#"You are {} participating in a formal presidential-style debate.
+        // Without a role, fall back to the display name
+        let in_favor = role_name.contains("(FOR)");
+        presidential_prompt(topic, role_name, in_favor, opponent_name)
+    }
+
+    fn system_prompt_for_role(
+        &self,
+        topic: &str,
+        role_name: &str,
+        role: &ParticipantRole,
+        opponent_name: &str,
+    ) -> String {
+        presidential_prompt(
+            topic,
+            role_name,
+            *role == ParticipantRole::For,
+            opponent_name,
+        )
+    }
+}
+
+/// System prompt for the presidential format; `in_favor` picks the side.
+fn presidential_prompt(
+    topic: &str,
+    role_name: &str,
+    in_favor: bool,
+    opponent_name: &str,
+) -> String {
+    format!(
+        r#"You are {} participating in a formal presidential-style debate.
 
 TOPIC: {}
 
@@ -157,24 +344,165 @@ CRITICAL OUTPUT RULES:
 - Do NOT include narration, descriptions of gestures, movements, or tone
 - Do NOT include asterisks for emphasis or any markdown formatting
 - The announcer provides context - just deliver your argument directly"#,
-            role_name,
-            topic,
-            if role_name.contains("FOR") || role_name.contains("Pro") {
-                "IN FAVOR OF"
-            } else {
-                "AGAINST"
-            },
-            opponent_name
-        )
+        role_name,
+        topic,
+        if in_favor { "IN FAVOR OF" } else { "AGAINST" },
+        opponent_name
+    )
+}
+
+/// A format that runs only a subset of another format's sections,
+/// optionally with a different opening speaker.
+///
+/// Useful for cheaply iterating on prompts, e.g. running only the opening
+/// statements.
+pub struct FilteredFormat {
+    inner: Box<dyn DebateFormat>,
+    sections: Vec<DebateSection>,
+}
+
+impl FilteredFormat {
+    /// Keep the sections named in `only` (or all sections if it is empty),
+    /// minus any named in `skip`, in the format's original order.
+    ///
+    /// Names match case-insensitively. Naming a section the format does not
+    /// have, or filtering out every section, is an error.
+    pub fn new(
+        inner: Box<dyn DebateFormat>,
+        only: &[String],
+        skip: &[String],
+    ) -> Result<Self, DebateError> {
+        let all = inner.sections();
+
+        for name in only.iter().chain(skip) {
+            if !all.iter().any(|s| s.name.eq_ignore_ascii_case(name.trim())) {
+                return Err(DebateError::ConfigError(format!(
+                    "Unknown section '{}' in the {} format. Available sections: {}",
+                    name.trim(),
+                    inner.name(),
+                    all.iter()
+                        .map(|s| s.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+        }
+
+        let named = |names: &[String], section: &DebateSection| {
+            names
+                .iter()
+                .any(|n| section.name.eq_ignore_ascii_case(n.trim()))
+        };
+        let sections: Vec<DebateSection> = all
+            .into_iter()
+            .filter(|s| only.is_empty() || named(only, s))
+            .filter(|s| !named(skip, s))
+            .collect();
+
+        if sections.is_empty() {
+            return Err(DebateError::ConfigError(
+                "Section filters left no sections to run".to_string(),
+            ));
+        }
+
+        Ok(Self { inner, sections })
+    }
+
+    /// Have every speaker use `model` in the format's final section
+    /// (normally the closing statements), if that section is kept.
+    pub fn with_closing_model(mut self, model: impl Into<String>) -> Self {
+        let model = model.into();
+        for section in self.closing_sections() {
+            section.model_override = Some(model.clone());
+        }
+        self
+    }
+
+    /// Have each speaker use their own model in the format's final section:
+    /// `models[i]` for participant `i`. Participants past the end of
+    /// `models` keep their usual model.
+    pub fn with_closing_models(mut self, models: &[String]) -> Self {
+        for section in self.closing_sections() {
+            section.per_speaker_models = models.iter().cloned().enumerate().collect();
+        }
+        self
+    }
+
+    /// The kept sections named like the format's final section.
+    fn closing_sections(&mut self) -> impl Iterator<Item = &mut DebateSection> {
+        let last = self.inner.sections().pop().map(|s| s.name);
+        self.sections
+            .iter_mut()
+            .filter(move |s| last.as_ref() == Some(&s.name))
+    }
+
+    /// Rotate each section's speaker order so `index` speaks first.
+    ///
+    /// Sections in which `index` does not speak are left unchanged.
+    pub fn with_first_speaker(mut self, index: usize) -> Self {
+        for section in &mut self.sections {
+            if let Some(position) = section.speaker_order.iter().position(|&i| i == index) {
+                section.speaker_order.rotate_left(position);
+            }
+        }
+        self
+    }
+}
+
+impl DebateFormat for FilteredFormat {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn display_name(&self) -> &str {
+        self.inner.display_name()
+    }
+
+    fn sections(&self) -> Vec<DebateSection> {
+        self.sections.clone()
+    }
+
+    fn max_participants(&self) -> usize {
+        self.inner.max_participants()
+    }
+
+    fn min_participants(&self) -> usize {
+        self.inner.min_participants()
+    }
+
+    // Sections are chosen when the filter is built, so the round count is
+    // fixed from then on; set it on the inner format first.
+    fn rounds(&self) -> Option<u32> {
+        self.inner.rounds()
+    }
+
+    fn system_prompt(&self, topic: &str, role_name: &str, opponent_name: &str) -> String {
+        self.inner.system_prompt(topic, role_name, opponent_name)
+    }
+
+    fn system_prompt_for_role(
+        &self,
+        topic: &str,
+        role_name: &str,
+        role: &ParticipantRole,
+        opponent_name: &str,
+    ) -> String {
+        self.inner
+            .system_prompt_for_role(topic, role_name, role, opponent_name)
     }
 }
 
 /// Get a debate format by name with specified rounds.
+///
+/// Formats with a fixed number of rounds ignore `rounds`; see
+/// [`DebateFormat::set_rounds`].
 pub fn get_format(name: &str, rounds: u32) -> Option<Box<dyn DebateFormat>> {
-    match name.to_lowercase().as_str() {
-        "presidential" => Some(Box::new(PresidentialDebateFormat::new(rounds))),
-        _ => None,
-    }
+    let mut format: Box<dyn DebateFormat> = match name.to_lowercase().as_str() {
+        "presidential" => Box::new(PresidentialDebateFormat::default()),
+        _ => return None,
+    };
+    format.set_rounds(rounds);
+    Some(format)
 }
 
 /// List all available debate format names.
@@ -186,6 +514,89 @@ pub fn available_formats() -> Vec<&'static str> {
 mod tests {
     use super::*;
 
+    fn section_names(format: &dyn DebateFormat) -> Vec<String> {
+        format.sections().into_iter().map(|s| s.name).collect()
+    }
+
+    #[test]
+    fn test_filtered_format_keeps_order() {
+        let names = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        // Requested out of order, returned in format order
+        let only = FilteredFormat::new(
+            Box::new(PresidentialDebateFormat::new(4)),
+            &names(&["closing statements", " Opening Statements"]),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            section_names(&only),
+            ["Opening Statements", "Closing Statements"]
+        );
+
+        let skip = FilteredFormat::new(
+            Box::new(PresidentialDebateFormat::new(4)),
+            &[],
+            &names(&["REBUTTALS"]),
+        )
+        .unwrap();
+        let mut expected = section_names(&PresidentialDebateFormat::new(4));
+        expected.retain(|n| n != "Rebuttals");
+        assert_eq!(section_names(&skip), expected);
+    }
+
+    #[test]
+    fn test_first_speaker_leads_every_section() {
+        let format = FilteredFormat::new(Box::new(PresidentialDebateFormat::new(6)), &[], &[])
+            .unwrap()
+            .with_first_speaker(1);
+
+        for section in format.sections() {
+            assert_eq!(section.speaker_order, [1, 0], "{}", section.name);
+        }
+    }
+
+    #[test]
+    fn test_closing_model_overrides_only_final_section() {
+        let format = FilteredFormat::new(Box::new(PresidentialDebateFormat::new(4)), &[], &[])
+            .unwrap()
+            .with_closing_model("strong-model");
+        let sections = format.sections();
+
+        let (last, rest) = sections.split_last().unwrap();
+        assert_eq!(last.name, "Closing Statements");
+        assert_eq!(last.model_override.as_deref(), Some("strong-model"));
+        assert!(rest.iter().all(|s| s.model_override.is_none()));
+
+        let models = ["strong-a".to_string(), "strong-b".to_string()];
+        let format = FilteredFormat::new(Box::new(PresidentialDebateFormat::new(4)), &[], &[])
+            .unwrap()
+            .with_closing_models(&models);
+        let sections = format.sections();
+        let (last, rest) = sections.split_last().unwrap();
+        assert_eq!(last.per_speaker_models[&1], "strong-b");
+        assert!(rest.iter().all(|s| s.per_speaker_models.is_empty()));
+    }
+
+    #[test]
+    fn test_filtered_format_rejects_unknown_or_empty() {
+        let unknown = FilteredFormat::new(
+            Box::new(PresidentialDebateFormat::new(4)),
+            &["Cross Examination".to_string()],
+            &[],
+        );
+        assert!(
+            matches!(unknown, Err(DebateError::ConfigError(msg)) if msg.contains("Cross Examination"))
+        );
+
+        let empty = FilteredFormat::new(
+            Box::new(PresidentialDebateFormat::new(4)),
+            &["Rebuttals".to_string()],
+            &["rebuttals".to_string()],
+        );
+        assert!(empty.is_err());
+    }
+
     #[test]
     fn test_presidential_format_minimum_rounds() {
         let format = PresidentialDebateFormat::new(4);
@@ -199,6 +610,85 @@ mod tests {
         assert_eq!(sections[3].name, "Closing Statements");
     }
 
+    #[test]
+    fn test_presidential_format_rounds_accessor() {
+        assert_eq!(PresidentialDebateFormat::new(10).rounds(), Some(10));
+        // Clamped to the minimum
+        assert_eq!(PresidentialDebateFormat::new(2).rounds(), Some(4));
+    }
+
+    #[test]
+    fn test_presidential_format_maximum_rounds() {
+        let format = PresidentialDebateFormat::new(100_000);
+        assert_eq!(
+            format.rounds(),
+            Some(PresidentialDebateFormat::DEFAULT_MAX_ROUNDS)
+        );
+        assert_eq!(format.sections().len(), 50);
+
+        let mut format = PresidentialDebateFormat::default().with_max_rounds(10);
+        format.set_rounds(100_000);
+        assert_eq!(format.rounds(), Some(10));
+        assert_eq!(format.sections().len(), 10);
+
+        // The cap does not depend on builder order
+        let format = PresidentialDebateFormat::new(80).with_max_rounds(100);
+        assert_eq!(format.rounds(), Some(80));
+    }
+
+    #[test]
+    fn test_get_format_honors_rounds() {
+        let format = get_format("presidential", 8).unwrap();
+        assert_eq!(format.rounds(), Some(8));
+        assert_eq!(format.sections().len(), 8);
+
+        let mut format = get_format("presidential", 2).unwrap();
+        assert_eq!(format.rounds(), Some(4));
+        format.set_rounds(5);
+        assert_eq!(format.rounds(), Some(5));
+    }
+
+    #[test]
+    fn test_fixed_round_format_ignores_rounds() {
+        /// A format that always runs one exchange.
+        struct SingleExchange;
+
+        impl DebateFormat for SingleExchange {
+            fn name(&self) -> &str {
+                "single"
+            }
+
+            fn display_name(&self) -> &str {
+                "Single Exchange"
+            }
+
+            fn sections(&self) -> Vec<DebateSection> {
+                PresidentialDebateFormat::new(4).sections()[..1].to_vec()
+            }
+
+            fn max_participants(&self) -> usize {
+                2
+            }
+
+            fn min_participants(&self) -> usize {
+                2
+            }
+
+            fn rounds(&self) -> Option<u32> {
+                Some(1)
+            }
+
+            fn system_prompt(&self, topic: &str, role_name: &str, _opponent: &str) -> String {
+                format!("{} on {}", role_name, topic)
+            }
+        }
+
+        let mut format: Box<dyn DebateFormat> = Box::new(SingleExchange);
+        format.set_rounds(10);
+        assert_eq!(format.rounds(), Some(1));
+        assert_eq!(format.sections().len(), 1);
+    }
+
     #[test]
     fn test_presidential_format_six_rounds() {
         let format = PresidentialDebateFormat::new(6);
@@ -225,6 +715,69 @@ mod tests {
         assert_eq!(sections[3].speaker_order, vec![0, 1]); // Round 3: A then B
     }
 
+    #[test]
+    fn test_presidential_format_default_section_tokens() {
+        let sections = PresidentialDebateFormat::new(4).sections();
+        let tokens: Vec<u32> = sections.iter().map(|s| s.max_tokens).collect();
+        assert_eq!(tokens, vec![300, 400, 400, 250]);
+    }
+
+    #[test]
+    fn test_presidential_format_custom_section_tokens() {
+        let format = PresidentialDebateFormat::new(5).with_section_tokens(100, 200, 300, 600);
+        let tokens: Vec<u32> = format.sections().iter().map(|s| s.max_tokens).collect();
+        assert_eq!(tokens, vec![100, 200, 200, 300, 600]);
+    }
+
+    #[test]
+    fn test_stance_comes_from_role_not_name() {
+        let format = PresidentialDebateFormat::new(4);
+
+        let against = format.system_prompt_for_role(
+            "Ban cars",
+            "Professor Pro (AGAINST)",
+            &ParticipantRole::Against,
+            "Alice",
+        );
+        assert!(against.contains("argue AGAINST the topic"));
+
+        let for_side = format.system_prompt_for_role(
+            "Ban cars",
+            "Alice (FOR)",
+            &ParticipantRole::For,
+            "Professor Pro",
+        );
+        assert!(for_side.contains("argue IN FAVOR OF the topic"));
+
+        // The name-only variant no longer matches "Pro" in a name
+        let by_name = format.system_prompt("Ban cars", "Professor Pro (AGAINST)", "Alice");
+        assert!(by_name.contains("argue AGAINST the topic"));
+    }
+
+    #[test]
+    fn test_speaker_prompt_uses_per_speaker_override() {
+        let mut section = PresidentialDebateFormat::new(4).sections().remove(0);
+        section
+            .per_speaker_prompts
+            .insert(1, "Attack the affirmative plan.".to_string());
+
+        assert!(
+            section
+                .speaker_prompt(0)
+                .ends_with("Please provide your opening statements.")
+        );
+        assert!(
+            section
+                .speaker_prompt(1)
+                .ends_with("\nAttack the affirmative plan.")
+        );
+        assert!(
+            section
+                .speaker_prompt(1)
+                .starts_with("[Opening Statements - ")
+        );
+    }
+
     #[test]
     fn test_get_format_presidential() {
         let format = get_format("presidential", 6);
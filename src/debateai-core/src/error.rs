@@ -1,5 +1,7 @@
 //! Error types for the debate system.
 
+use async_openai::error::OpenAIError;
+use std::error::Error as _;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,7 +14,10 @@ pub enum DebateError {
     },
 
     #[error("OpenAI API error: {0}")]
-    OpenAIError(#[from] async_openai::error::OpenAIError),
+    OpenAIError(#[from] OpenAIError),
+
+    #[error("Duplicate participant name: {0:?} is used by more than one participant")]
+    DuplicateParticipantName(String),
 
     #[error("Configuration error: {0}")]
     ConfigError(String),
@@ -20,9 +25,104 @@ pub enum DebateError {
     #[error("Unknown debate format: {0}")]
     UnknownFormat(String),
 
+    #[error("Empty response: {0}")]
+    EmptyResponse(String),
+
+    #[error("Refusal: {participant} refused to take a stance")]
+    Refusal { participant: String },
+
+    #[error("Invalid judge verdict: {0}")]
+    InvalidVerdict(String),
+
     #[error("TTS error: {0}")]
     TtsError(String),
 
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
+
+impl DebateError {
+    /// Full description of the error for diagnostics.
+    ///
+    /// Unlike `Display`, this includes the provider's error fields, the raw
+    /// response body when it could not be parsed, and the chain of sources.
+    pub fn verbose_detail(&self) -> String {
+        let mut detail = self.to_string();
+
+        if let DebateError::OpenAIError(e) = self {
+            match e {
+                OpenAIError::ApiError(api) => {
+                    detail.push_str(&format!("\n  message: {}", api.message));
+                    for (field, value) in [
+                        ("type", &api.r#type),
+                        ("param", &api.param),
+                        ("code", &api.code),
+                    ] {
+                        if let Some(value) = value {
+                            detail.push_str(&format!("\n  {}: {}", field, value));
+                        }
+                    }
+                    if let Ok(body) = serde_json::to_string_pretty(api) {
+                        detail.push_str(&format!("\n  body: {}", body));
+                    }
+                }
+                OpenAIError::JSONDeserialize(_, body) => {
+                    detail.push_str(&format!("\n  body: {}", body));
+                }
+                OpenAIError::Reqwest(req) => {
+                    if let Some(status) = req.status() {
+                        detail.push_str(&format!("\n  status: {}", status));
+                    }
+                    if let Some(url) = req.url() {
+                        detail.push_str(&format!("\n  url: {}", url));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut source = self.source();
+        while let Some(cause) = source {
+            detail.push_str(&format!("\n  caused by: {}", cause));
+            source = cause.source();
+        }
+
+        detail
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_openai::error::ApiError;
+
+    #[test]
+    fn test_verbose_detail_includes_api_error_body() {
+        let error = DebateError::from(OpenAIError::ApiError(ApiError {
+            message: "The model `gpt-5-nano` does not exist".to_string(),
+            r#type: Some("invalid_request_error".to_string()),
+            param: Some("model".to_string()),
+            code: Some("model_not_found".to_string()),
+        }));
+
+        let detail = error.verbose_detail();
+        assert!(detail.starts_with(&error.to_string()));
+        assert!(detail.contains("code: model_not_found"));
+        assert!(detail.contains("\"message\": \"The model `gpt-5-nano` does not exist\""));
+    }
+
+    #[test]
+    fn test_verbose_detail_includes_unparsed_body() {
+        let json_error = serde_json::from_str::<serde_json::Value>("<html>").unwrap_err();
+        let error = DebateError::from(OpenAIError::JSONDeserialize(
+            json_error,
+            "<html>502 Bad Gateway</html>".to_string(),
+        ));
+
+        assert!(
+            error
+                .verbose_detail()
+                .contains("body: <html>502 Bad Gateway</html>")
+        );
+    }
+}
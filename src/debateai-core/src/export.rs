@@ -0,0 +1,626 @@
+//! Transcript export formats.
+
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::Regex;
+
+use crate::error::DebateError;
+use crate::orchestrator::DebateMessage;
+use crate::participant::{AIParticipant, ParticipantRole};
+
+/// Metadata written as YAML front-matter at the top of a Markdown transcript.
+#[derive(Debug, Clone)]
+pub struct FrontMatter {
+    /// Name of the debate format (e.g. "presidential").
+    pub format: String,
+    /// ISO 8601 date of the debate (YYYY-MM-DD).
+    pub date: String,
+}
+
+/// Render a transcript as Markdown, optionally preceded by YAML front-matter
+/// for static site generators.
+pub fn render_markdown(
+    messages: &[DebateMessage],
+    participants: &[AIParticipant],
+    topic: &str,
+    front_matter: Option<&FrontMatter>,
+) -> String {
+    let mut output = String::new();
+
+    if let Some(meta) = front_matter {
+        output.push_str("---\n");
+        output.push_str(&format!("title: {}\n", yaml_string(topic)));
+        output.push_str(&format!("date: {}\n", meta.date));
+        output.push_str(&format!("format: {}\n", yaml_string(&meta.format)));
+        output.push_str("participants:\n");
+        for p in participants {
+            output.push_str(&format!("  - name: {}\n", yaml_string(&p.name)));
+            output.push_str(&format!(
+                "    role: {}\n",
+                yaml_string(p.role.display_name())
+            ));
+        }
+        output.push_str("---\n\n");
+    }
+
+    output.push_str(&format!("# {}\n\n", topic));
+    for p in participants {
+        output.push_str(&format!("- **{}** ({})\n", p.name, p.role.display_name()));
+    }
+
+    let mut current_section: Option<&str> = None;
+    for message in messages {
+        if current_section != Some(message.section.as_str()) {
+            output.push_str(&format!("\n## {}\n", message.section));
+            current_section = Some(&message.section);
+        }
+        output.push_str(&format!(
+            "\n**{}:** {}\n",
+            message.speaker_name,
+            message.content.trim()
+        ));
+    }
+
+    output
+}
+
+/// Inline stylesheet for [`render_html`], so the document needs no other files.
+const HTML_STYLE: &str =
+    "body { font-family: sans-serif; max-width: 50em; margin: 2em auto; line-height: 1.5; }
+.speaker { border-left: 4px solid #888; padding: 0.5em 1em; margin: 1em 0; }
+.speaker .name { font-weight: bold; }
+.role-for { border-color: #2e7d32; background: #f1f8f1; }
+.role-against { border-color: #c62828; background: #fbf1f1; }
+.role-neutral { border-color: #546e7a; background: #f3f5f6; }
+.role-custom { border-color: #6a1b9a; background: #f6f1f9; }
+";
+
+/// Render a transcript as a self-contained HTML document.
+///
+/// Each speaker block is colored by the speaker's role. All text is
+/// HTML-escaped.
+pub fn render_html(
+    messages: &[DebateMessage],
+    participants: &[AIParticipant],
+    topic: &str,
+) -> String {
+    let topic = html_escape(topic);
+    let mut output =
+        String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    output.push_str(&format!("<title>{}</title>\n", topic));
+    output.push_str(&format!(
+        "<style>\n{}</style>\n</head>\n<body>\n",
+        HTML_STYLE
+    ));
+    output.push_str(&format!("<h1>{}</h1>\n<ul>\n", topic));
+    for p in participants {
+        output.push_str(&format!(
+            "<li><strong>{}</strong> ({})</li>\n",
+            html_escape(&p.name),
+            html_escape(p.role.display_name())
+        ));
+    }
+    output.push_str("</ul>\n");
+
+    let mut current_section: Option<&str> = None;
+    for message in messages {
+        if current_section != Some(message.section.as_str()) {
+            output.push_str(&format!("<h2>{}</h2>\n", html_escape(&message.section)));
+            current_section = Some(&message.section);
+        }
+        let role_class = match message
+            .speaker_index
+            .and_then(|i| participants.get(i))
+            .map(|p| &p.role)
+        {
+            Some(ParticipantRole::For) => "role-for",
+            Some(ParticipantRole::Against) => "role-against",
+            Some(ParticipantRole::Neutral) | None => "role-neutral",
+            Some(ParticipantRole::Custom(_)) => "role-custom",
+        };
+        output.push_str(&format!(
+            "<div class=\"speaker {}\">\n<p class=\"name\">{}</p>\n",
+            role_class,
+            html_escape(&message.speaker_name)
+        ));
+        for paragraph in message.content.trim().split("\n\n") {
+            let paragraph = paragraph.trim();
+            if !paragraph.is_empty() {
+                output.push_str(&format!(
+                    "<p>{}</p>\n",
+                    html_escape(paragraph).replace('\n', "<br>\n")
+                ));
+            }
+        }
+        output.push_str("</div>\n");
+    }
+
+    output.push_str("</body>\n</html>\n");
+    output
+}
+
+/// Render a transcript as pretty-printed JSON.
+pub fn render_json(messages: &[DebateMessage]) -> Result<String, DebateError> {
+    serde_json::to_string_pretty(messages)
+        .map_err(|e| DebateError::ConfigError(format!("Failed to serialize transcript: {}", e)))
+}
+
+/// Parse a transcript written by [`render_json`].
+///
+/// Participants are numbered from 0 with none skipped, so a speaker index
+/// at or above the number of distinct speakers is rejected rather than
+/// trusted to size a participant list.
+pub fn parse_json(json: &str) -> Result<Vec<DebateMessage>, DebateError> {
+    let messages: Vec<DebateMessage> = serde_json::from_str(json)
+        .map_err(|e| DebateError::ConfigError(format!("Invalid transcript: {}", e)))?;
+
+    let speakers: HashSet<usize> = messages.iter().filter_map(|m| m.speaker_index).collect();
+    if let Some(message) = messages
+        .iter()
+        .find(|m| m.speaker_index.is_some_and(|i| i >= speakers.len()))
+    {
+        return Err(DebateError::ConfigError(format!(
+            "Invalid transcript: speaker_index {} ({}) is out of range for {} participant(s)",
+            message.speaker_index.unwrap_or_default(),
+            message.speaker_name,
+            speakers.len()
+        )));
+    }
+    Ok(messages)
+}
+
+/// Render a transcript as CSV (RFC 4180) with a header row.
+///
+/// Columns: section, speaker_index, speaker_name, word_count, content. The
+/// speaker_index is left empty for the announcer.
+pub fn render_csv(messages: &[DebateMessage]) -> String {
+    let mut output = String::from("section,speaker_index,speaker_name,word_count,content\r\n");
+
+    for message in messages {
+        let fields = [
+            csv_field(&message.section),
+            message
+                .speaker_index
+                .map(|i| i.to_string())
+                .unwrap_or_default(),
+            csv_field(&message.speaker_name),
+            message.content.split_whitespace().count().to_string(),
+            csv_field(&message.content),
+        ];
+        output.push_str(&fields.join(","));
+        output.push_str("\r\n");
+    }
+
+    output
+}
+
+/// Merge each run of adjacent messages by the same speaker into one message,
+/// joining their content with a blank line.
+///
+/// The merged message keeps the section of the first message in the run, so
+/// the later sections' headings are left out of a rendered transcript.
+pub fn merge_consecutive(messages: &[DebateMessage]) -> Vec<DebateMessage> {
+    let mut merged: Vec<DebateMessage> = Vec::with_capacity(messages.len());
+    for message in messages {
+        match merged.last_mut() {
+            Some(last) if last.speaker_index == message.speaker_index => {
+                last.content = format!("{}\n\n{}", last.content.trim_end(), message.content.trim());
+                last.raw_content = match (last.raw_content.take(), &message.raw_content) {
+                    (Some(first), Some(next)) => Some(format!("{}\n\n{}", first, next)),
+                    (first, next) => first.or_else(|| next.clone()),
+                };
+            }
+            _ => merged.push(message.clone()),
+        }
+    }
+    merged
+}
+
+/// Render chapter markers as one `HH:MM:SS.mmm Title` line per chapter
+/// (the simple chapters format read by podcast tools).
+///
+/// Each boundary is a chapter title and its start, in samples from the
+/// beginning of the audio.
+pub fn build_chapters(section_boundaries: &[(String, usize)], sample_rate: u32) -> String {
+    let mut output = String::new();
+    for (title, start) in section_boundaries {
+        let millis = (*start as u64 * 1000) / u64::from(sample_rate.max(1));
+        output.push_str(&format!(
+            "{:02}:{:02}:{:02}.{:03} {}\n",
+            millis / 3_600_000,
+            millis / 60_000 % 60,
+            millis / 1000 % 60,
+            millis % 1000,
+            title
+        ));
+    }
+    output
+}
+
+/// JSON Schema describing the output of [`render_json`], as pretty-printed JSON.
+pub fn transcript_schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(Vec<DebateMessage>))
+        .expect("schemas always serialize")
+}
+
+/// How redaction terms are matched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RedactionMode {
+    /// Match terms only as whole words ("Acme" leaves "Acmeville" alone).
+    #[default]
+    WholeWord,
+    /// Match terms anywhere, including inside other words.
+    Substring,
+}
+
+/// Replace every occurrence of `terms` in `text` with `replacement`.
+///
+/// Terms match case-insensitively; longer terms are tried first, so
+/// "Acme Corp" is replaced whole rather than leaving "Corp" behind.
+pub fn redact(text: &str, terms: &[String], replacement: &str, mode: RedactionMode) -> String {
+    match redaction_pattern(terms, mode) {
+        Some(pattern) => pattern
+            .replace_all(text, regex::NoExpand(replacement))
+            .into_owned(),
+        None => text.to_string(),
+    }
+}
+
+/// Redact the content and speaker names of a transcript.
+pub fn redact_transcript(
+    messages: &[DebateMessage],
+    terms: &[String],
+    replacement: &str,
+    mode: RedactionMode,
+) -> Vec<DebateMessage> {
+    let Some(pattern) = redaction_pattern(terms, mode) else {
+        return messages.to_vec();
+    };
+    let redact = |text: &str| {
+        pattern
+            .replace_all(text, regex::NoExpand(replacement))
+            .into_owned()
+    };
+
+    messages
+        .iter()
+        .map(|m| DebateMessage {
+            speaker_name: redact(&m.speaker_name),
+            content: redact(&m.content),
+            raw_content: m.raw_content.as_deref().map(redact),
+            ..m.clone()
+        })
+        .collect()
+}
+
+/// Build the case-insensitive pattern for the redaction terms, if any.
+fn redaction_pattern(terms: &[String], mode: RedactionMode) -> Option<Regex> {
+    let mut terms: Vec<&str> = terms
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    terms.sort_by_key(|t| std::cmp::Reverse(t.len()));
+
+    let alternatives: Vec<String> = terms.iter().map(|t| regex::escape(t)).collect();
+    let pattern = match mode {
+        RedactionMode::WholeWord => format!(r"(?i)\b(?:{})\b", alternatives.join("|")),
+        RedactionMode::Substring => format!(r"(?i)(?:{})", alternatives.join("|")),
+    };
+    Regex::new(&pattern).ok()
+}
+
+/// Escape text for use in HTML element content and attribute values.
+fn html_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quote a CSV field if it contains a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Quote a string for YAML. JSON strings are valid YAML double-quoted scalars.
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Today's date in UTC as YYYY-MM-DD.
+pub fn today_iso_date() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400) as i64;
+    iso_date_from_days(days)
+}
+
+/// Convert days since 1970-01-01 to a YYYY-MM-DD date (proleptic Gregorian).
+fn iso_date_from_days(days: i64) -> String {
+    // Howard Hinnant's civil_from_days algorithm
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> (Vec<DebateMessage>, Vec<AIParticipant>) {
+        let participants = vec![
+            AIParticipant::new("Alice", "model-a", ParticipantRole::For),
+            AIParticipant::new("Bob \"The Builder\"", "model-b", ParticipantRole::Against),
+        ];
+        let messages = vec![
+            DebateMessage {
+                section: "Opening Statements".to_string(),
+                speaker_index: Some(0),
+                speaker_name: "Alice".to_string(),
+                content: "We should.".to_string(),
+                raw_content: None,
+            },
+            DebateMessage {
+                section: "Opening Statements".to_string(),
+                speaker_index: Some(1),
+                speaker_name: "Bob \"The Builder\"".to_string(),
+                content: "We should not.".to_string(),
+                raw_content: None,
+            },
+        ];
+        (messages, participants)
+    }
+
+    #[test]
+    fn test_markdown_front_matter() {
+        let (messages, participants) = sample();
+        let meta = FrontMatter {
+            format: "presidential".to_string(),
+            date: "2025-03-01".to_string(),
+        };
+        let output = render_markdown(&messages, &participants, "Topic: cars?", Some(&meta));
+
+        let block = output
+            .strip_prefix("---\n")
+            .and_then(|rest| rest.split_once("\n---\n"))
+            .map(|(block, _)| block)
+            .expect("front-matter block");
+
+        // Every line is a `key: value` mapping entry; quoted values are valid
+        // double-quoted scalars.
+        let mut keys = Vec::new();
+        for line in block.lines() {
+            let (key, value) = line.split_once(':').expect("key: value line");
+            let value = value.trim();
+            if value.starts_with('"') {
+                serde_json::from_str::<String>(value).expect("valid quoted scalar");
+            }
+            keys.push(key.trim().trim_start_matches("- ").to_string());
+        }
+        assert_eq!(
+            keys,
+            [
+                "title",
+                "date",
+                "format",
+                "participants",
+                "name",
+                "role",
+                "name",
+                "role"
+            ]
+        );
+        assert!(block.contains("title: \"Topic: cars?\""));
+        assert!(block.contains("date: 2025-03-01"));
+        assert!(block.contains("name: \"Bob \\\"The Builder\\\"\""));
+        assert!(block.contains("role: \"AGAINST\""));
+    }
+
+    #[test]
+    fn test_markdown_without_front_matter() {
+        let (messages, participants) = sample();
+        let output = render_markdown(&messages, &participants, "Cars", None);
+
+        assert!(output.starts_with("# Cars\n"));
+        assert_eq!(output.matches("## Opening Statements").count(), 1);
+        assert!(output.contains("**Alice:** We should."));
+    }
+
+    #[test]
+    fn test_csv_escaping() {
+        let (mut messages, _) = sample();
+        messages[0].content = "Yes, \"really\".\nTwice.".to_string();
+        let output = render_csv(&messages);
+        let lines: Vec<&str> = output.split("\r\n").collect();
+
+        assert_eq!(
+            lines[0],
+            "section,speaker_index,speaker_name,word_count,content"
+        );
+        assert_eq!(
+            lines[1],
+            "Opening Statements,0,Alice,3,\"Yes, \"\"really\"\".\nTwice.\""
+        );
+        assert_eq!(
+            lines[2],
+            "Opening Statements,1,\"Bob \"\"The Builder\"\"\",3,We should not."
+        );
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let (mut messages, participants) = sample();
+        messages[0].content = "If a < b && b < c then <script>a < c</script>".to_string();
+        let output = render_html(&messages, &participants, "Cats & dogs");
+
+        assert!(output.contains("<h1>Cats &amp; dogs</h1>"));
+        assert!(output.contains(
+            "<p>If a &lt; b &amp;&amp; b &lt; c then &lt;script&gt;a &lt; c&lt;/script&gt;</p>"
+        ));
+        assert!(!output.contains("<script>"));
+        assert!(output.contains("<div class=\"speaker role-against\">\n<p class=\"name\">Bob &quot;The Builder&quot;</p>"));
+        assert_eq!(output.matches("<h2>Opening Statements</h2>").count(), 1);
+    }
+
+    #[test]
+    fn test_merge_consecutive_same_speaker() {
+        let (mut messages, _) = sample();
+        let mut rebuttal = messages[1].clone();
+        rebuttal.section = "Rebuttals".to_string();
+        rebuttal.content = "Still no.".to_string();
+        messages.push(rebuttal);
+        let mut closing = messages[0].clone();
+        closing.section = "Closing".to_string();
+        messages.push(closing);
+
+        let merged = merge_consecutive(&messages);
+        let speakers: Vec<Option<usize>> = merged.iter().map(|m| m.speaker_index).collect();
+        assert_eq!(speakers, [Some(0), Some(1), Some(0)]);
+        assert_eq!(merged[1].content, "We should not.\n\nStill no.");
+        assert_eq!(merged[1].section, "Opening Statements");
+        assert_eq!(merged[2].section, "Closing");
+        assert_eq!(merged[0].content, "We should.");
+    }
+
+    #[test]
+    fn test_build_chapters_timecodes() {
+        let boundaries = vec![
+            ("Introduction".to_string(), 0),
+            ("Opening Statements".to_string(), 36_000),
+            ("Closing".to_string(), 24_000 * 3_725 + 12_000),
+        ];
+        assert_eq!(
+            build_chapters(&boundaries, 24_000),
+            "00:00:00.000 Introduction\n\
+             00:00:01.500 Opening Statements\n\
+             01:02:05.500 Closing\n"
+        );
+    }
+
+    #[test]
+    fn test_transcript_schema_validates_json_transcript() {
+        let schema: serde_json::Value = serde_json::from_str(&transcript_schema()).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let (messages, _) = sample();
+        let transcript: serde_json::Value =
+            serde_json::from_str(&render_json(&messages).unwrap()).unwrap();
+        assert!(validator.is_valid(&transcript));
+        assert_eq!(
+            parse_json(&render_json(&messages).unwrap()).unwrap()[1].content,
+            messages[1].content
+        );
+
+        let missing_content = serde_json::json!([{
+            "section": "Opening Statements",
+            "speaker_index": 0,
+            "speaker_name": "Alice"
+        }]);
+        assert!(!validator.is_valid(&missing_content));
+    }
+
+    #[test]
+    fn test_parse_json_rejects_out_of_range_speaker() {
+        let (mut messages, _) = sample();
+        messages[1].speaker_index = Some(usize::MAX);
+        let err = parse_json(&render_json(&messages).unwrap()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("out of range for 2 participant(s)")
+        );
+    }
+
+    #[test]
+    fn test_announcer_has_no_speaker_index() {
+        let (mut messages, _) = sample();
+        messages.push(DebateMessage {
+            section: "Opening Statements".to_string(),
+            speaker_index: None,
+            speaker_name: "Announcer".to_string(),
+            content: "So far, so good.".to_string(),
+            raw_content: None,
+        });
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&messages).unwrap()).unwrap();
+        assert!(json[2]["speaker_index"].is_null());
+        assert_eq!(json[0]["speaker_index"], 0);
+        assert!(parse_json(&render_json(&messages).unwrap()).unwrap()[2].is_announcer());
+
+        let csv = render_csv(&messages);
+        assert_eq!(
+            csv.split("\r\n").nth(3),
+            Some("Opening Statements,,Announcer,4,\"So far, so good.\"")
+        );
+    }
+
+    #[test]
+    fn test_redact_whole_words() {
+        let terms = vec!["Acme".to_string(), "Acme Corp".to_string()];
+        assert_eq!(
+            redact(
+                "acme Corp sued Acme; Acmeville is fine.",
+                &terms,
+                "[REDACTED]",
+                RedactionMode::WholeWord
+            ),
+            "[REDACTED] sued [REDACTED]; Acmeville is fine."
+        );
+        assert_eq!(
+            redact("Acme $1", &[], "x", RedactionMode::WholeWord),
+            "Acme $1"
+        );
+    }
+
+    #[test]
+    fn test_redact_substrings() {
+        let terms = vec!["acme".to_string()];
+        assert_eq!(
+            redact("Acmeville and ACME", &terms, "$0", RedactionMode::Substring),
+            "$0ville and $0"
+        );
+
+        let (messages, _) = sample();
+        let redacted = redact_transcript(
+            &messages,
+            &["alice".to_string()],
+            "***",
+            RedactionMode::Substring,
+        );
+        assert_eq!(redacted[0].speaker_name, "***");
+        assert_eq!(redacted[1].content, messages[1].content);
+    }
+
+    #[test]
+    fn test_iso_date_from_days() {
+        assert_eq!(iso_date_from_days(0), "1970-01-01");
+        assert_eq!(iso_date_from_days(19_782), "2024-02-29");
+        assert_eq!(iso_date_from_days(20_513), "2026-03-01");
+    }
+}
@@ -0,0 +1,328 @@
+//! Judging: the criteria a debate is scored on, the prompt asking a model to
+//! score it, and how the reply's per-criterion scores combine into one.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::DebateError;
+
+/// Criteria used when none are configured, weighted equally.
+pub const DEFAULT_CRITERIA: &[&str] = &["persuasiveness", "evidence", "rebuttal", "clarity"];
+
+/// Highest score a judge may give for a criterion; scores run from 0.
+pub const MAX_CRITERION_SCORE: f32 = 10.0;
+
+/// Token budget for the judge's reply.
+pub const JUDGE_TOKENS: u32 = 1024;
+
+/// The criteria a judge scores each participant on, with their weights.
+///
+/// Weights are relative: `[("persuasiveness", 2.0), ("evidence", 1.0)]`
+/// counts persuasiveness for two thirds of the final score.
+///
+/// Read from the `[judge]` section of the config file, e.g.
+/// `criteria = [["persuasiveness", 2.0], ["evidence", 1.0]]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JudgeConfig {
+    /// Model that judges the debate; the first participant's model when unset.
+    pub model: Option<String>,
+    /// OpenAI-compatible API base URL the judge is asked at, with the
+    /// debate's API key; the first participant's endpoint when unset.
+    pub endpoint: Option<String>,
+    /// Criterion names and their weights, in the order shown to the judge.
+    pub criteria: Vec<(String, f32)>,
+}
+
+impl Default for JudgeConfig {
+    fn default() -> Self {
+        Self {
+            model: None,
+            endpoint: None,
+            criteria: DEFAULT_CRITERIA
+                .iter()
+                .map(|name| (name.to_string(), 1.0))
+                .collect(),
+        }
+    }
+}
+
+impl JudgeConfig {
+    /// Set the weight of a criterion, adding it if it is not already listed.
+    pub fn with_weight(mut self, criterion: impl Into<String>, weight: f32) -> Self {
+        let criterion = criterion.into();
+        match self
+            .criteria
+            .iter_mut()
+            .find(|(name, _)| *name == criterion)
+        {
+            Some((_, w)) => *w = weight,
+            None => self.criteria.push((criterion, weight)),
+        }
+        self
+    }
+
+    /// Check that there is at least one criterion, that every weight is
+    /// finite and not negative, and that the weights do not sum to zero.
+    pub fn validate(&self) -> Result<(), DebateError> {
+        if self.criteria.is_empty() {
+            return Err(DebateError::ConfigError(
+                "The judge needs at least one criterion".to_string(),
+            ));
+        }
+        if let Some((name, weight)) = self
+            .criteria
+            .iter()
+            .find(|(_, w)| !w.is_finite() || *w < 0.0)
+        {
+            return Err(DebateError::ConfigError(format!(
+                "Judge criterion '{}' has an invalid weight of {}",
+                name, weight
+            )));
+        }
+        if self.total_weight() == 0.0 {
+            return Err(DebateError::ConfigError(
+                "Judge criterion weights sum to zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The rubric section of a judge's prompt: each criterion with its share
+    /// of the final score, and the range to score it in.
+    pub fn rubric_prompt(&self) -> String {
+        let total = self.total_weight();
+        let mut prompt = format!(
+            "Score each participant from 0 to {} on each criterion below. \
+             The criteria count towards the final score as shown.\n",
+            MAX_CRITERION_SCORE
+        );
+        for (name, weight) in &self.criteria {
+            let share = if total > 0.0 {
+                weight / total * 100.0
+            } else {
+                0.0
+            };
+            prompt.push_str(&format!("- {} ({:.0}%)\n", name, share));
+        }
+        prompt
+    }
+
+    /// System prompt asking a model to judge the debate on `topic` between
+    /// `names` (which must be distinct), replying with the JSON object [`parse_verdict`](Self::parse_verdict) reads.
+    pub fn judge_prompt(&self, topic: &str, names: &[String]) -> String {
+        let example: serde_json::Map<String, serde_json::Value> = names
+            .iter()
+            .map(|name| {
+                let scores = self
+                    .criteria
+                    .iter()
+                    .map(|(criterion, _)| (criterion.clone(), 5.into()))
+                    .collect();
+                (name.clone(), serde_json::Value::Object(scores))
+            })
+            .collect();
+        format!(
+            "You are an impartial judge of a formal debate on \"{}\". \
+             Read the transcript and judge the arguments alone, not which side you agree with.\n\n\
+             {}\nReply with only a JSON object mapping each participant's name to their \
+             scores, in this shape:\n{}",
+            topic,
+            self.rubric_prompt(),
+            serde_json::Value::Object(example)
+        )
+    }
+
+    /// Read the judge's reply into a verdict for `names`, the participants'
+    /// names in participant order.
+    ///
+    /// Text around the JSON object (e.g. a code fence) is ignored. Every
+    /// participant must be scored on every criterion within range. The
+    /// reply is keyed by name, so the names must be distinct.
+    pub fn parse_verdict(
+        &self,
+        reply: &str,
+        names: &[String],
+    ) -> Result<JudgeVerdict, DebateError> {
+        if let Some((_, name)) = names
+            .iter()
+            .enumerate()
+            .find(|(i, name)| names[..*i].contains(name))
+        {
+            return Err(DebateError::DuplicateParticipantName(name.clone()));
+        }
+        let json = match (reply.find('{'), reply.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &reply[start..=end],
+            _ => {
+                return Err(DebateError::InvalidVerdict(
+                    "no JSON object in the reply".to_string(),
+                ));
+            }
+        };
+        let mut given: HashMap<String, HashMap<String, f32>> =
+            serde_json::from_str(json).map_err(|e| DebateError::InvalidVerdict(e.to_string()))?;
+
+        let mut scores = Vec::with_capacity(names.len());
+        for (index, name) in names.iter().enumerate() {
+            let participant = given
+                .remove(name)
+                .ok_or_else(|| DebateError::InvalidVerdict(format!("no scores for '{}'", name)))?;
+            for (criterion, _) in &self.criteria {
+                match participant.get(criterion) {
+                    Some(score) if (0.0..=MAX_CRITERION_SCORE).contains(score) => {}
+                    Some(score) => {
+                        return Err(DebateError::InvalidVerdict(format!(
+                            "{} scored {} for '{}', outside 0-{}",
+                            name, score, criterion, MAX_CRITERION_SCORE
+                        )));
+                    }
+                    None => {
+                        return Err(DebateError::InvalidVerdict(format!(
+                            "no '{}' score for '{}'",
+                            criterion, name
+                        )));
+                    }
+                }
+            }
+            scores.push(ParticipantScore {
+                index,
+                name: name.clone(),
+                total: self.weighted_score(&participant)?,
+                scores: participant,
+            });
+        }
+        Ok(JudgeVerdict { scores })
+    }
+
+    /// Combine per-criterion scores into one weighted score on the same
+    /// 0 to [`MAX_CRITERION_SCORE`] scale.
+    ///
+    /// Every configured criterion must have a score; scores for criteria not
+    /// in the rubric are ignored.
+    pub fn weighted_score(&self, scores: &HashMap<String, f32>) -> Result<f32, DebateError> {
+        self.validate()?;
+        let mut sum = 0.0;
+        for (name, weight) in &self.criteria {
+            let score = scores.get(name).ok_or_else(|| {
+                DebateError::ConfigError(format!("No score given for criterion '{}'", name))
+            })?;
+            sum += score * weight;
+        }
+        Ok(sum / self.total_weight())
+    }
+
+    fn total_weight(&self) -> f32 {
+        self.criteria.iter().map(|(_, w)| w).sum()
+    }
+}
+
+/// A judge's scores for one participant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticipantScore {
+    /// Index of the participant in the debate.
+    pub index: usize,
+    /// Participant name.
+    pub name: String,
+    /// Score for each criterion.
+    pub scores: HashMap<String, f32>,
+    /// Weighted score across the criteria.
+    pub total: f32,
+}
+
+/// A judge's scores for every participant, in participant order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JudgeVerdict {
+    pub scores: Vec<ParticipantScore>,
+}
+
+impl JudgeVerdict {
+    /// The participant with the highest weighted score, or `None` on a tie
+    /// for first.
+    pub fn winner(&self) -> Option<&ParticipantScore> {
+        let best = self
+            .scores
+            .iter()
+            .max_by(|a, b| a.total.total_cmp(&b.total))?;
+        let tied = self.scores.iter().filter(|s| s.total == best.total).count();
+        (tied == 1).then_some(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores(pairs: &[(&str, f32)]) -> HashMap<String, f32> {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_weighted_score() {
+        let given = scores(&[
+            ("persuasiveness", 9.0),
+            ("evidence", 3.0),
+            ("rebuttal", 6.0),
+            ("clarity", 6.0),
+        ]);
+
+        // Equal weights by default: a plain average
+        assert_eq!(JudgeConfig::default().weighted_score(&given).unwrap(), 6.0);
+
+        let judge = JudgeConfig {
+            criteria: vec![
+                ("persuasiveness".to_string(), 3.0),
+                ("evidence".to_string(), 1.0),
+            ],
+            ..Default::default()
+        };
+        // (9 * 3 + 3 * 1) / 4; the unweighted criteria are ignored
+        assert_eq!(judge.weighted_score(&given).unwrap(), 7.5);
+        assert!(
+            judge
+                .rubric_prompt()
+                .contains("- persuasiveness (75%)\n- evidence (25%)\n")
+        );
+
+        let missing = scores(&[("persuasiveness", 9.0)]);
+        assert!(judge.weighted_score(&missing).is_err());
+        let zero = JudgeConfig::default()
+            .with_weight("persuasiveness", 0.0)
+            .with_weight("evidence", 0.0)
+            .with_weight("rebuttal", 0.0)
+            .with_weight("clarity", 0.0);
+        assert!(zero.weighted_score(&given).is_err());
+        let infinite = JudgeConfig::default().with_weight("evidence", f32::INFINITY);
+        assert!(infinite.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        let judge = JudgeConfig::default()
+            .with_weight("persuasiveness", 3.0)
+            .with_weight("rebuttal", 0.0)
+            .with_weight("clarity", 0.0);
+        let names = ["Alice".to_string(), "Bob".to_string()];
+        let reply = "```json\n{\"Alice\": {\"persuasiveness\": 8, \"evidence\": 4, \
+                     \"rebuttal\": 1, \"clarity\": 1},\n\"Bob\": {\"persuasiveness\": 6, \
+                     \"evidence\": 10, \"rebuttal\": 9, \"clarity\": 9}}\n```";
+
+        let verdict = judge.parse_verdict(reply, &names).unwrap();
+        assert_eq!(verdict.scores[0].total, 7.0);
+        assert_eq!(verdict.scores[1].total, 7.0);
+        assert!(verdict.winner().is_none());
+
+        let verdict = JudgeConfig::default().parse_verdict(reply, &names).unwrap();
+        assert_eq!(verdict.winner().map(|s| s.name.as_str()), Some("Bob"));
+
+        assert!(judge.parse_verdict(reply, &["Carol".to_string()]).is_err());
+        let same = ["Bob".to_string(), "Bob".to_string()];
+        assert!(matches!(
+            judge.parse_verdict(reply, &same),
+            Err(DebateError::DuplicateParticipantName(_))
+        ));
+        let out_of_range = reply.replace("\"persuasiveness\": 8", "\"persuasiveness\": 11");
+        assert!(judge.parse_verdict(&out_of_range, &names).is_err());
+        assert!(judge.parse_verdict("Bob wins.", &names).is_err());
+    }
+}
@@ -1,19 +1,59 @@
 //! DebateAI Core Library
-//! 
+//!
 //! Provides the core debate orchestration logic, format definitions,
 //! AI participant management, and TTS output.
+//!
+//! TTS output lives behind the default `tts` feature. Text-only users can
+//! build without it; keep that configuration compiling with
+//! `cargo check -p debateai-core --no-default-features`.
 
+pub mod backend;
+pub mod builder;
+pub mod config;
 pub mod debate_format;
-pub mod participant;
-pub mod orchestrator;
 pub mod error;
-pub mod config;
+pub mod export;
+pub mod judge;
+pub mod manifest;
+pub mod orchestrator;
+pub mod participant;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "templating")]
+pub mod template;
+#[cfg(feature = "tts")]
 pub mod tts;
 
-pub use debate_format::{DebateFormat, DebateSection, PresidentialDebateFormat};
-pub use participant::{AIParticipant, ParticipantRole};
-pub use orchestrator::{DebateOrchestrator, DebateConfig, DebateMessage, DebateEvent};
+#[cfg(any(test, feature = "test-util"))]
+pub use backend::ScriptedBackend;
+pub use backend::{
+    CompletionBackend, CompletionRequest, CompletionResponse, MockBackend, OpenAIBackend,
+    PingReport, ping,
+};
+pub use builder::DebateBuilder;
+pub use config::{AudioConfig, Config, ModelAliases, Pronunciations, VoicesConfig, redact_api_key};
+pub use debate_format::{DebateFormat, DebateSection, FilteredFormat, PresidentialDebateFormat};
 pub use error::DebateError;
-pub use config::{Config, VoicesConfig};
-pub use tts::{DebateTts, combine_audio_segments, generate_output_filename, adjust_audio_speed};
-
+pub use export::{
+    FrontMatter, RedactionMode, build_chapters, merge_consecutive, parse_json, redact,
+    redact_transcript, render_csv, render_html, render_json, render_markdown, today_iso_date,
+    transcript_schema,
+};
+pub use judge::{JudgeConfig, JudgeVerdict, ParticipantScore};
+pub use manifest::{DebateManifest, SectionTokens};
+pub use orchestrator::{
+    DebateConfig, DebateEvent, DebateMessage, DebateOrchestrator, DebatePlan, MAX_TOPIC_CHARS,
+    MarkdownStripping, PlannedParticipant, PlannedSection, ResponseHook, SanitizeConfig,
+    SectionTranscript, group_by_section, normalize_topic, replay_transcript, run_debates,
+    run_orchestrators,
+};
+pub use participant::{AIParticipant, ParticipantRole};
+#[cfg(feature = "server")]
+pub use server::{ServerConfig, serve};
+#[cfg(feature = "tts")]
+pub use tts::{
+    AudioBudget, DebateTts, SPEECH_WORDS_PER_MINUTE, SegmentGaps, TtsTiming, WavStreamWriter,
+    adjust_audio_speed, check_voices, clean_for_tts, combine_audio_segments, detect_language,
+    estimate_speech_samples, estimate_speech_seconds, expand_for_tts, generate_output_filename,
+    generate_tone, mix_background, read_wav_mono, resample_linear, write_wav_streaming,
+};
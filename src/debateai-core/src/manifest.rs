@@ -0,0 +1,342 @@
+//! Debate manifests: the saved setup of a debate, used to re-run it.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::config::VoicesConfig;
+use crate::debate_format::{self, DebateFormat, FilteredFormat, PresidentialDebateFormat};
+use crate::error::DebateError;
+use crate::participant::AIParticipant;
+
+/// Everything needed to run a debate again: participants, format and voices.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DebateManifest {
+    /// The topic that was debated.
+    pub topic: String,
+    /// Name of the debate format (e.g. "presidential").
+    pub format: String,
+    /// Number of rounds the format was built with.
+    pub rounds: u32,
+    /// Cap on the number of rounds the format was built with.
+    #[serde(default = "default_max_rounds")]
+    pub max_rounds: u32,
+    /// Token budget for each kind of section (presidential format).
+    #[serde(default)]
+    pub section_tokens: SectionTokens,
+    /// Participant who spoke first in every section, if not the format's default.
+    #[serde(default)]
+    pub first_speaker: Option<usize>,
+    /// Models used for the closing section: one for everyone, or one per
+    /// participant. Empty when each kept their own.
+    #[serde(default)]
+    pub closing_models: Vec<String>,
+    /// The participants, in speaking order.
+    pub participants: Vec<AIParticipant>,
+    /// Voices used for audio output.
+    pub voices: VoicesConfig,
+}
+
+fn default_max_rounds() -> u32 {
+    PresidentialDebateFormat::DEFAULT_MAX_ROUNDS
+}
+
+/// Token budget for each kind of presidential section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SectionTokens {
+    pub opening: u32,
+    pub main: u32,
+    pub rebuttal: u32,
+    pub closing: u32,
+}
+
+impl Default for SectionTokens {
+    fn default() -> Self {
+        Self {
+            opening: PresidentialDebateFormat::DEFAULT_OPENING_TOKENS,
+            main: PresidentialDebateFormat::DEFAULT_MAIN_TOKENS,
+            rebuttal: PresidentialDebateFormat::DEFAULT_REBUTTAL_TOKENS,
+            closing: PresidentialDebateFormat::DEFAULT_CLOSING_TOKENS,
+        }
+    }
+}
+
+impl DebateManifest {
+    /// Create a manifest for a debate.
+    pub fn new(
+        topic: impl Into<String>,
+        format: &dyn DebateFormat,
+        rounds: u32,
+        participants: Vec<AIParticipant>,
+        voices: VoicesConfig,
+    ) -> Self {
+        Self {
+            topic: topic.into(),
+            format: format.name().to_string(),
+            rounds,
+            max_rounds: default_max_rounds(),
+            section_tokens: SectionTokens::default(),
+            first_speaker: None,
+            closing_models: Vec::new(),
+            participants,
+            voices,
+        }
+    }
+
+    /// Record the cap on the number of rounds the format was built with.
+    pub fn with_max_rounds(mut self, max_rounds: u32) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    /// Record the token budget for each kind of section.
+    pub fn with_section_tokens(mut self, tokens: SectionTokens) -> Self {
+        self.section_tokens = tokens;
+        self
+    }
+
+    /// Record the participant who spoke first in every section.
+    pub fn with_first_speaker(mut self, index: usize) -> Self {
+        self.first_speaker = Some(index);
+        self
+    }
+
+    /// Record the models used for the closing section.
+    pub fn with_closing_models(mut self, models: Vec<String>) -> Self {
+        self.closing_models = models;
+        self
+    }
+
+    /// Load a manifest from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, DebateError> {
+        let content = fs::read_to_string(path.as_ref())
+            .map_err(|e| DebateError::ConfigError(format!("Failed to read manifest: {}", e)))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| DebateError::ConfigError(format!("Failed to parse manifest: {}", e)))
+    }
+
+    /// Save the manifest as pretty-printed JSON.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), DebateError> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            DebateError::ConfigError(format!("Failed to serialize manifest: {}", e))
+        })?;
+        fs::write(path.as_ref(), content)?;
+        Ok(())
+    }
+
+    /// JSON Schema describing the manifest file, as pretty-printed JSON.
+    pub fn json_schema() -> String {
+        serde_json::to_string_pretty(&schemars::schema_for!(DebateManifest))
+            .expect("schemas always serialize")
+    }
+
+    /// Hide which model each participant used, for sharing results blind.
+    ///
+    /// Each distinct model becomes "Model 1", "Model 2", ... in order of
+    /// first appearance, and per-participant API base URLs (which often
+    /// name the provider) are dropped. Names, roles and personas are kept.
+    pub fn anonymize_models(mut self) -> Self {
+        let mut seen: Vec<String> = Vec::new();
+        for participant in &mut self.participants {
+            let index = match seen.iter().position(|m| *m == participant.model) {
+                Some(index) => index,
+                None => {
+                    seen.push(participant.model.clone());
+                    seen.len() - 1
+                }
+            };
+            participant.model = format!("Model {}", index + 1);
+            participant.api_base = None;
+        }
+        self
+    }
+
+    /// Build the debate format this manifest was saved with.
+    pub fn build_format(&self) -> Result<Box<dyn DebateFormat>, DebateError> {
+        let format: Box<dyn DebateFormat> = if self.format.eq_ignore_ascii_case("presidential") {
+            let tokens = self.section_tokens;
+            Box::new(
+                PresidentialDebateFormat::new(self.rounds)
+                    .with_max_rounds(self.max_rounds)
+                    .with_section_tokens(
+                        tokens.opening,
+                        tokens.main,
+                        tokens.rebuttal,
+                        tokens.closing,
+                    ),
+            )
+        } else {
+            debate_format::get_format(&self.format, self.rounds)
+                .ok_or_else(|| DebateError::UnknownFormat(self.format.clone()))?
+        };
+        if self.first_speaker.is_none() && self.closing_models.is_empty() {
+            return Ok(format);
+        }
+
+        let mut filtered = FilteredFormat::new(format, &[], &[])?;
+        if let Some(first) = self.first_speaker {
+            filtered = filtered.with_first_speaker(first);
+        }
+        filtered = match self.closing_models.as_slice() {
+            [] => filtered,
+            [model] => filtered.with_closing_model(model),
+            models => filtered.with_closing_models(models),
+        };
+        Ok(Box::new(filtered))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::participant::ParticipantRole;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let participants = vec![
+            AIParticipant::new("Alice", "model-a", ParticipantRole::For)
+                .with_voice("af_heart")
+                .with_persona("a labor economist"),
+            AIParticipant::new("Bob", "model-b", ParticipantRole::Against).with_voice("bm_george"),
+        ];
+        let manifest = DebateManifest::new(
+            "Old topic",
+            &PresidentialDebateFormat::new(60),
+            60,
+            participants,
+            VoicesConfig::default(),
+        )
+        .with_max_rounds(80);
+
+        let path = std::env::temp_dir().join(format!(
+            "debateai-manifest-test-{}.json",
+            std::process::id()
+        ));
+        manifest.save(&path).unwrap();
+        let loaded = DebateManifest::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.format, "presidential");
+        assert_eq!(loaded.build_format().unwrap().name(), "presidential");
+        assert_eq!(loaded.rounds, 60);
+        assert_eq!(loaded.max_rounds, 80);
+        // Above the default cap, but within the saved one
+        assert_eq!(loaded.build_format().unwrap().rounds(), Some(60));
+
+        let names: Vec<_> = loaded
+            .participants
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, ["Alice", "Bob"]);
+        assert_eq!(loaded.participants[0].model, "model-a");
+        assert_eq!(loaded.participants[0].role, ParticipantRole::For);
+        assert_eq!(loaded.participants[0].voice_id.as_deref(), Some("af_heart"));
+        assert_eq!(
+            loaded.participants[0].persona.as_deref(),
+            Some("a labor economist")
+        );
+        assert_eq!(loaded.participants[1].role, ParticipantRole::Against);
+    }
+
+    #[test]
+    fn test_manifest_keeps_format_options() {
+        let tokens = SectionTokens {
+            opening: 100,
+            main: 200,
+            rebuttal: 300,
+            closing: 150,
+        };
+        let manifest = DebateManifest::new(
+            "Topic",
+            &PresidentialDebateFormat::new(4),
+            4,
+            Vec::new(),
+            VoicesConfig::default(),
+        )
+        .with_section_tokens(tokens)
+        .with_first_speaker(1)
+        .with_closing_models(vec!["strong-a".to_string(), "strong-b".to_string()]);
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        let loaded: DebateManifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.section_tokens, tokens);
+        assert_eq!(loaded.first_speaker, Some(1));
+        assert_eq!(loaded.closing_models, ["strong-a", "strong-b"]);
+
+        let sections = loaded.build_format().unwrap().sections();
+        let budgets: Vec<u32> = sections.iter().map(|s| s.max_tokens).collect();
+        assert_eq!(budgets, [100, 200, 300, 150]);
+        assert!(sections.iter().all(|s| s.speaker_order[0] == 1));
+        assert_eq!(sections[3].per_speaker_models[&0], "strong-a");
+
+        // Manifests saved before these options existed get the defaults
+        let mut old: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let fields = old.as_object_mut().unwrap();
+        for key in [
+            "max_rounds",
+            "section_tokens",
+            "first_speaker",
+            "closing_models",
+        ] {
+            fields.remove(key);
+        }
+        let old: DebateManifest = serde_json::from_value(old).unwrap();
+        assert_eq!(old.max_rounds, PresidentialDebateFormat::DEFAULT_MAX_ROUNDS);
+        assert_eq!(old.section_tokens, SectionTokens::default());
+        assert_eq!(old.first_speaker, None);
+        assert!(old.closing_models.is_empty());
+    }
+
+    #[test]
+    fn test_anonymize_models_keeps_names() {
+        let participants = vec![
+            AIParticipant::new("Alice", "gpt-4o", ParticipantRole::For)
+                .with_endpoint("https://api.example.com/v1"),
+            AIParticipant::new("Bob", "llama3:8b", ParticipantRole::Against),
+            AIParticipant::new("Carol", "gpt-4o", ParticipantRole::For),
+        ];
+        let manifest = DebateManifest::new(
+            "Topic",
+            &PresidentialDebateFormat::new(4),
+            4,
+            participants,
+            VoicesConfig::default(),
+        )
+        .anonymize_models();
+
+        let summary: Vec<_> = manifest
+            .participants
+            .iter()
+            .map(|p| (p.name.as_str(), p.model.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Alice", "Model 1"),
+                ("Bob", "Model 2"),
+                ("Carol", "Model 1")
+            ]
+        );
+        assert_eq!(manifest.participants[0].api_base, None);
+    }
+
+    #[test]
+    fn test_unknown_format_is_an_error() {
+        let mut manifest = DebateManifest::new(
+            "Topic",
+            &PresidentialDebateFormat::new(4),
+            4,
+            Vec::new(),
+            VoicesConfig::default(),
+        );
+        manifest.format = "oxford".to_string();
+
+        assert!(matches!(
+            manifest.build_format(),
+            Err(DebateError::UnknownFormat(name)) if name == "oxford"
+        ));
+    }
+}
@@ -2,18 +2,25 @@
 //!
 //! Manages the debate flow, API calls, and message history.
 
+use crate::backend::{CompletionBackend, CompletionRequest, OpenAIBackend};
+use crate::config::render_moderator_prompt;
 use crate::debate_format::{DebateFormat, DebateSection};
 use crate::error::DebateError;
+use crate::judge::{JUDGE_TOKENS, JudgeConfig, JudgeVerdict};
 use crate::participant::AIParticipant;
 
-use async_openai::Client;
-use async_openai::config::OpenAIConfig;
 use async_openai::types::chat::{
-    ChatCompletionRequestAssistantMessage, ChatCompletionRequestMessage,
-    ChatCompletionRequestSystemMessage, ChatCompletionRequestUserMessage,
-    CreateChatCompletionRequestArgs,
+    ChatCompletionRequestMessage, ChatCompletionRequestSystemMessage,
+    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestUserMessage,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::Semaphore;
 
 /// Configuration for running a debate.
 #[derive(Debug, Clone)]
@@ -24,6 +31,179 @@ pub struct DebateConfig {
     pub api_base: String,
     /// API key for authentication.
     pub api_key: String,
+    /// When a response hits the token limit mid-sentence, request one short
+    /// continuation to finish it.
+    pub finish_truncated: bool,
+    /// How many times a response cut off by the token limit is continued
+    /// with a further request, the parts joined into one message.
+    pub max_continuations: u32,
+    /// Similarity (0.0-1.0) above which a response counts as repeating one of
+    /// the speaker's recent responses. `None` disables the check.
+    pub repetition_threshold: Option<f32>,
+    /// Fail with [`DebateError::Refusal`] when a speaker refuses to take a
+    /// stance, instead of re-prompting them once.
+    pub fail_fast_on_refusal: bool,
+    /// Maximum characters of context documents injected per participant.
+    pub context_char_budget: usize,
+    /// Documents given identically to every participant, directly after the
+    /// system prompt. Like the system prompt, they are part of each history's
+    /// fixed prefix and are never removed from it.
+    pub shared_context: Vec<String>,
+    /// Fail instead of skipping when a section refers to a speaker that
+    /// does not exist.
+    pub strict: bool,
+    /// Emit a [`DebateEvent::PromptDump`] before every completion request.
+    pub debug_prompts: bool,
+    /// How responses are cleaned up before they are recorded.
+    pub sanitize: SanitizeConfig,
+    /// Send each participant a throwaway request before the debate starts,
+    /// for backends that respond poorly on their first call.
+    pub warmup: bool,
+    /// Keep each response as the model sent it in
+    /// [`DebateMessage::raw_content`], for debugging sanitizing.
+    pub keep_raw: bool,
+    /// Have the announcer recap the debate at its halfway point.
+    pub halftime_recap: bool,
+    /// System prompt for a moderator who opens each section with a question
+    /// for the participants. Supports `{topic}`, `{section_name}` and
+    /// `{section_description}`. `None` leaves sections without one.
+    pub moderator_prompt: Option<String>,
+    /// Ask the judge for a strict JSON reply, on endpoints that support it.
+    pub json_mode: bool,
+    /// House rules appended to every participant's system prompt.
+    pub rules: Option<String>,
+    /// Date the debate takes place on (e.g. "2024-06-01"). Replaces `{date}`
+    /// in system prompts and is stated at the end of each one.
+    pub debate_date: Option<String>,
+    /// Retries after a failed API request.
+    pub api_retries: u32,
+    /// Retries after an empty (or near-empty) response.
+    ///
+    /// Each empty-response retry is a full request with its own API retries,
+    /// so a turn makes at most `(api_retries + 1) * (empty_retries + 1)`
+    /// requests.
+    pub empty_retries: u32,
+    /// Pause before each speaker starts, to pace live output.
+    pub turn_delay: Option<Duration>,
+    /// Most recent messages kept in each participant's history besides its
+    /// pinned ones: the system prompt, context documents and the messages of
+    /// any [`pinned`](DebateSection::pinned) section. `None` keeps the whole
+    /// history.
+    pub history_window: Option<usize>,
+    /// How a statement is shown to the other participants. `{name}` is
+    /// replaced with the speaker's name and `{content}` with the statement.
+    pub opponent_message_template: String,
+}
+
+/// How responses are cleaned up before they are recorded.
+///
+/// The default is the [`lenient`](Self::lenient) preset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizeConfig {
+    /// Tags removed together with their content (e.g. `<thinking>`).
+    pub strip_tags: Vec<&'static str>,
+    /// Remove any other markup-shaped tags, keeping their content.
+    pub strip_markup: bool,
+    /// How markdown emphasis is removed.
+    pub markdown_stripping: MarkdownStripping,
+    /// Drop the fence lines around code blocks, keeping their content,
+    /// so TTS does not read out the backticks.
+    pub unwrap_code_fences: bool,
+    /// Remove "As an AI language model..." style disclaimer sentences.
+    pub strip_disclaimers: bool,
+    /// Collapse runs of whitespace, including newlines, to single spaces.
+    pub collapse_whitespace: bool,
+}
+
+/// Reasoning and scratchpad tags some models emit around hidden thoughts.
+const REASONING_TAGS: &[&str] = &[
+    "thinking",
+    "think",
+    "reflection",
+    "reflect",
+    "internal",
+    "reasoning",
+    "thought",
+    "scratch",
+    "scratchpad",
+    "plan",
+    "analysis",
+    "analyze",
+    "consider",
+    "pondering",
+    "deliberation",
+];
+
+/// Meta-commentary tags the strict preset strips on top of [`REASONING_TAGS`].
+const META_TAGS: &[&str] = &["note", "meta", "aside", "draft", "critique"];
+
+impl SanitizeConfig {
+    /// Names accepted by [`SanitizeConfig::preset`].
+    pub const PRESETS: &'static [&'static str] = &["strict", "lenient", "none"];
+
+    /// Strip reasoning and meta-commentary tags, every asterisk and AI
+    /// disclaimers, for providers that pad their answers.
+    pub fn strict() -> Self {
+        Self {
+            strip_tags: REASONING_TAGS.iter().chain(META_TAGS).copied().collect(),
+            markdown_stripping: MarkdownStripping::Aggressive,
+            strip_disclaimers: true,
+            ..Self::lenient()
+        }
+    }
+
+    /// Strip reasoning tags, markup and paired emphasis only.
+    pub fn lenient() -> Self {
+        Self {
+            strip_tags: REASONING_TAGS.to_vec(),
+            strip_markup: true,
+            markdown_stripping: MarkdownStripping::Conservative,
+            unwrap_code_fences: true,
+            strip_disclaimers: false,
+            collapse_whitespace: true,
+        }
+    }
+
+    /// Record responses exactly as the model returned them.
+    pub fn none() -> Self {
+        Self {
+            strip_tags: Vec::new(),
+            strip_markup: false,
+            markdown_stripping: MarkdownStripping::Keep,
+            unwrap_code_fences: false,
+            strip_disclaimers: false,
+            collapse_whitespace: false,
+        }
+    }
+
+    /// Look up a preset by name (see [`SanitizeConfig::PRESETS`]).
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "strict" => Some(Self::strict()),
+            "lenient" => Some(Self::lenient()),
+            "none" => Some(Self::none()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for SanitizeConfig {
+    fn default() -> Self {
+        Self::lenient()
+    }
+}
+
+/// How asterisks are removed from responses before they are recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MarkdownStripping {
+    /// Remove only paired emphasis (`*word*`, `**word**`), keeping
+    /// standalone asterisks such as in "3 * 4".
+    #[default]
+    Conservative,
+    /// Remove every asterisk.
+    Aggressive,
+    /// Leave asterisks alone.
+    Keep,
 }
 
 impl DebateConfig {
@@ -36,28 +216,398 @@ impl DebateConfig {
             topic: topic.into(),
             api_base: api_base.into(),
             api_key: api_key.into(),
+            finish_truncated: false,
+            max_continuations: 0,
+            repetition_threshold: None,
+            fail_fast_on_refusal: false,
+            context_char_budget: Self::DEFAULT_CONTEXT_CHAR_BUDGET,
+            shared_context: Vec::new(),
+            strict: false,
+            debug_prompts: false,
+            sanitize: SanitizeConfig::default(),
+            warmup: false,
+            keep_raw: false,
+            halftime_recap: false,
+            moderator_prompt: None,
+            json_mode: false,
+            rules: None,
+            debate_date: None,
+            api_retries: OpenAIBackend::DEFAULT_RETRIES,
+            empty_retries: Self::DEFAULT_EMPTY_RETRIES,
+            turn_delay: None,
+            history_window: None,
+            opponent_message_template: Self::DEFAULT_OPPONENT_MESSAGE_TEMPLATE.to_string(),
         }
     }
+
+    /// Default character budget for a participant's context documents.
+    pub const DEFAULT_CONTEXT_CHAR_BUDGET: usize = 8000;
+
+    /// Default number of retries after an empty response.
+    pub const DEFAULT_EMPTY_RETRIES: u32 = 2;
+
+    /// Default template for statements shown to the other participants.
+    pub const DEFAULT_OPPONENT_MESSAGE_TEMPLATE: &'static str = "[Opponent {name} said]: {content}";
+
+    /// Finish responses cut off by the token limit with a short continuation.
+    pub fn with_finish_truncated(mut self, enabled: bool) -> Self {
+        self.finish_truncated = enabled;
+        self
+    }
+
+    /// Continue responses cut off by the token limit up to `max` times.
+    ///
+    /// Runs before [`with_finish_truncated`](Self::with_finish_truncated),
+    /// which then only applies if the last continuation is also cut off.
+    pub fn with_max_continuations(mut self, max: u32) -> Self {
+        self.max_continuations = max;
+        self
+    }
+
+    /// Re-prompt once when a response is at least this similar to one of
+    /// the speaker's recent responses.
+    pub fn with_repetition_threshold(mut self, threshold: f32) -> Self {
+        self.repetition_threshold = Some(threshold);
+        self
+    }
+
+    /// Abort the debate as soon as a speaker refuses to take a stance.
+    pub fn with_fail_fast_on_refusal(mut self, enabled: bool) -> Self {
+        self.fail_fast_on_refusal = enabled;
+        self
+    }
+
+    /// Limit how many characters of context documents each participant receives.
+    pub fn with_context_char_budget(mut self, budget: usize) -> Self {
+        self.context_char_budget = budget;
+        self
+    }
+
+    /// Give every participant the same source documents.
+    pub fn with_shared_context(mut self, docs: Vec<String>) -> Self {
+        self.shared_context = docs;
+        self
+    }
+
+    /// Turn conditions that are normally skipped into errors.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Emit the full message history before every completion request.
+    pub fn with_debug_prompts(mut self, enabled: bool) -> Self {
+        self.debug_prompts = enabled;
+        self
+    }
+
+    /// Replace all response cleanup settings, e.g. with a preset.
+    pub fn with_sanitize(mut self, sanitize: SanitizeConfig) -> Self {
+        self.sanitize = sanitize;
+        self
+    }
+
+    /// Choose how markdown emphasis is removed from responses.
+    pub fn with_markdown_stripping(mut self, mode: MarkdownStripping) -> Self {
+        self.sanitize.markdown_stripping = mode;
+        self
+    }
+
+    /// Choose whether fenced code blocks are unwrapped (fence lines removed,
+    /// content kept) in responses.
+    pub fn with_code_fence_unwrapping(mut self, enabled: bool) -> Self {
+        self.sanitize.unwrap_code_fences = enabled;
+        self
+    }
+
+    /// Warm up each participant's backend before the debate starts.
+    pub fn with_warmup(mut self, enabled: bool) -> Self {
+        self.warmup = enabled;
+        self
+    }
+
+    /// Append the same house rules (e.g. "No ad hominem.") to every
+    /// participant's system prompt.
+    pub fn with_rules(mut self, rules: impl Into<String>) -> Self {
+        self.rules = Some(rules.into());
+        self
+    }
+
+    /// Ground the debate in a date, so models discuss events as of that day
+    /// rather than deflecting about current events.
+    pub fn with_debate_date(mut self, date: impl Into<String>) -> Self {
+        self.debate_date = Some(date.into());
+        self
+    }
+
+    /// Keep the unsanitized response alongside each transcript message.
+    pub fn with_keep_raw(mut self, enabled: bool) -> Self {
+        self.keep_raw = enabled;
+        self
+    }
+
+    /// Add an announcer recap of the debate so far after the first half of
+    /// the sections. The first participant's model writes it.
+    pub fn with_halftime_recap(mut self, enabled: bool) -> Self {
+        self.halftime_recap = enabled;
+        self
+    }
+
+    /// Open each section with a moderator question written from `prompt`
+    /// by the first participant's model (see
+    /// [`PromptsConfig::moderator_prompt`](crate::config::PromptsConfig::moderator_prompt)).
+    pub fn with_moderator_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.moderator_prompt = Some(prompt.into());
+        self
+    }
+
+    /// Request JSON object mode (`response_format`) for the judge, so its
+    /// scores parse reliably. Endpoints that reject the parameter are asked
+    /// again without it.
+    pub fn with_json_mode(mut self, enabled: bool) -> Self {
+        self.json_mode = enabled;
+        self
+    }
+
+    /// Set how many times a failed API request is retried.
+    pub fn with_api_retries(mut self, retries: u32) -> Self {
+        self.api_retries = retries;
+        self
+    }
+
+    /// Set how many times an empty response is retried.
+    pub fn with_empty_retries(mut self, retries: u32) -> Self {
+        self.empty_retries = retries;
+        self
+    }
+
+    /// Pause before each speaker starts, simulating a debate's natural pacing.
+    pub fn with_turn_delay(mut self, delay: Duration) -> Self {
+        self.turn_delay = Some(delay);
+        self
+    }
+
+    /// Trim each participant's history to its pinned messages plus the last
+    /// `messages` other messages before every request.
+    pub fn with_history_window(mut self, messages: usize) -> Self {
+        self.history_window = Some(messages);
+        self
+    }
+
+    /// Change how a statement is shown to the other participants, e.g.
+    /// `"[Moderator {name}]: {content}"`.
+    pub fn with_opponent_message_template(mut self, template: impl Into<String>) -> Self {
+        self.opponent_message_template = template.into();
+        self
+    }
 }
 
+/// Token budget for finishing a truncated sentence.
+const FINISH_SENTENCE_TOKENS: u32 = 60;
+
+/// Prompt asking a speaker to finish a response cut off by the token limit.
+const FINISH_SENTENCE_PROMPT: &str = "You were cut off mid-sentence. \
+Finish your last sentence in as few words as possible. Do not repeat anything already said.";
+
+/// Prompt asking a speaker to continue a response cut off by the token limit.
+const CONTINUE_PROMPT: &str = "You were cut off. Continue exactly where you left off. \
+Do not repeat anything already said.";
+
+/// Token budget for a warm-up request.
+const WARMUP_TOKENS: u32 = 5;
+
+/// Throwaway prompt sent to warm up a backend.
+const WARMUP_PROMPT: &str = "Reply with OK.";
+
+/// Token budget for the halftime recap.
+const RECAP_TOKENS: u32 = 250;
+
+/// System prompt for the halftime recap. `{topic}` is replaced with the topic.
+const RECAP_PROMPT: &str = "You are the announcer of a formal debate on \"{topic}\". \
+The debate is at its halfway point. In three or four sentences, neutrally summarize it so far \
+for the audience: each side's main arguments and where they clash. Do not pick a winner.";
+
+/// Token budget for a moderator question.
+const MODERATOR_TOKENS: u32 = 150;
+
+/// Asks the moderator for a section's question once the debate has started.
+const MODERATOR_REQUEST: &str = "Ask your question for this section.";
+
+/// How a moderator question is shown to the participants.
+const MODERATOR_MESSAGE_TEMPLATE: &str = "[Moderator asks]: {content}";
+
+/// Speaker name of transcript entries written by the moderator.
+pub const MODERATOR_NAME: &str = "Moderator";
+
+/// Speaker name of transcript entries spoken by the announcer.
+pub const ANNOUNCER_NAME: &str = "Announcer";
+
+/// Header for a participant's own context documents.
+const CONTEXT_DOCS_HEADER: &str =
+    "REFERENCE MATERIAL: Ground your arguments in the following documents where relevant.";
+
+/// Header for documents shared by all participants.
+const SHARED_CONTEXT_HEADER: &str = "SHARED EVIDENCE: Every participant has been given the \
+following documents. Either side may cite them.";
+
+/// Number of the speaker's previous responses checked for repetition.
+const REPETITION_LOOKBACK: usize = 3;
+
+/// Reminder sent when a response repeats an earlier one.
+const REPETITION_REMINDER: &str = "Your last response repeated an argument you have already made. \
+Do not restate earlier points; bring a new argument or directly answer your opponent's latest statement.";
+
+/// Phrases (lowercase) that mark a response as refusing to argue the assigned
+/// side when they appear in its first sentence.
+const REFUSAL_PHRASES: &[&str] = &[
+    "i can't take a side",
+    "i cannot take a side",
+    "i can't take sides",
+    "i cannot take sides",
+    "i don't take sides",
+    "i can't argue either side",
+    "i cannot argue either side",
+    "i can't argue this position",
+    "i cannot argue this position",
+    "i'm not able to argue either side",
+    "i'm not able to argue this position",
+    "as an ai, i don't have personal opinions",
+    "as an ai, i do not have personal opinions",
+    "as an ai language model, i can't",
+    "as an ai language model, i cannot",
+    "i can't participate in this debate",
+    "i cannot participate in this debate",
+];
+
+/// Reminder sent when a speaker refuses to take a stance.
+const REFUSAL_REMINDER: &str = "This is a structured debate exercise. You must argue your \
+assigned position persuasively. Do not decline or present both sides; give your argument now.";
+
+/// Maximum characters of each opponent turn quoted in an opponent digest.
+const DIGEST_TURN_CHARS: usize = 200;
+
 /// A message in the debate transcript.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DebateMessage {
     /// Section name when this was spoken.
     pub section: String,
-    /// Index of the speaker (into participants array).
-    pub speaker_index: usize,
+    /// Index of the speaker (into participants array). `None` when the
+    /// announcer rather than a participant spoke, as in the halftime recap.
+    pub speaker_index: Option<usize>,
     /// Speaker's name.
     pub speaker_name: String,
     /// The content of the message.
     pub content: String,
+    /// The response as the model sent it, before sanitizing. Only kept when
+    /// [`DebateConfig::keep_raw`] is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_content: Option<String>,
+}
+
+impl DebateMessage {
+    /// Whether the announcer, rather than a participant, spoke this message.
+    pub fn is_announcer(&self) -> bool {
+        self.speaker_index.is_none()
+    }
+}
+
+/// The messages spoken in one debate section, with the section's details.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SectionTranscript {
+    /// Section name.
+    pub name: String,
+    /// Section description.
+    pub description: String,
+    /// Position of the section in the format (0-based).
+    pub order: usize,
+    /// Messages spoken in this section, in order.
+    pub messages: Vec<DebateMessage>,
+}
+
+/// The agenda of a debate, available before it runs (e.g. for UIs).
+#[derive(Debug, Clone, Serialize)]
+pub struct DebatePlan {
+    /// The topic being debated.
+    pub topic: String,
+    /// Display name of the debate format.
+    pub format: String,
+    /// Participants in speaking-index order.
+    pub participants: Vec<PlannedParticipant>,
+    /// Sections in the order they will run.
+    pub sections: Vec<PlannedSection>,
+}
+
+/// A participant as listed in a [`DebatePlan`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedParticipant {
+    pub name: String,
+    /// Role display name (e.g. "FOR").
+    pub role: String,
+    pub model: String,
+}
+
+/// A section as listed in a [`DebatePlan`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedSection {
+    pub name: String,
+    pub description: String,
+    /// Names of the speakers, in speaking order. Speakers the section
+    /// refers to that do not exist are left out, as they are when running.
+    pub speakers: Vec<String>,
+}
+
+/// Group a transcript into its sections.
+///
+/// Consecutive messages with the same section name form one group. Each
+/// group takes its description and order from the matching section in
+/// `sections`, searched in order so repeated section names stay distinct.
+pub fn group_by_section(
+    messages: &[DebateMessage],
+    sections: &[DebateSection],
+) -> Vec<SectionTranscript> {
+    let mut groups: Vec<SectionTranscript> = Vec::new();
+    let mut next_section = 0;
+
+    for message in messages {
+        if groups.last().is_none_or(|g| g.name != message.section) {
+            let found = sections[next_section..]
+                .iter()
+                .position(|s| s.name == message.section)
+                .map(|offset| next_section + offset);
+            let (order, description) = match found {
+                Some(order) => {
+                    next_section = order + 1;
+                    (order, sections[order].description.clone())
+                }
+                None => (groups.len(), String::new()),
+            };
+            groups.push(SectionTranscript {
+                name: message.section.clone(),
+                description,
+                order,
+                messages: Vec::new(),
+            });
+        }
+        if let Some(group) = groups.last_mut() {
+            group.messages.push(message.clone());
+        }
+    }
+
+    groups
 }
 
 /// Callback for debate events.
 pub type DebateCallback = Box<dyn Fn(DebateEvent) + Send + Sync>;
 
+/// Transform applied to every response before it is recorded.
+pub type ResponseHook = Box<dyn Fn(&str) -> String + Send + Sync>;
+
 /// Events emitted during a debate.
-#[derive(Debug, Clone)]
+///
+/// Serializes as an object tagged with its snake_case `type`,
+/// e.g. `{"type":"speaker_start","name":"Alice","role":"FOR"}`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum DebateEvent {
     /// A new section is starting.
     SectionStart { name: String, description: String },
@@ -65,21 +615,73 @@ pub enum DebateEvent {
     SpeakerStart { name: String, role: String },
     /// A participant has finished speaking.
     SpeakerMessage { name: String, content: String },
+    /// The exact messages about to be sent for a participant, as JSON.
+    /// Only emitted when [`DebateConfig::debug_prompts`] is enabled.
+    PromptDump {
+        speaker_index: usize,
+        messages_json: String,
+    },
     /// The debate has concluded.
     DebateEnd,
 }
 
+/// Feed a saved transcript through `callback` as if the debate were running.
+///
+/// Emits a `SectionStart` whenever the section changes, a `SpeakerStart` and
+/// `SpeakerMessage` per message, then `DebateEnd`. Transcripts do not record
+/// section descriptions, so those are empty; roles come from `participants`
+/// by speaker index.
+pub fn replay_transcript(
+    messages: &[DebateMessage],
+    participants: &[AIParticipant],
+    callback: &DebateCallback,
+) {
+    let mut current_section: Option<&str> = None;
+    for message in messages {
+        if current_section != Some(message.section.as_str()) {
+            callback(DebateEvent::SectionStart {
+                name: message.section.clone(),
+                description: String::new(),
+            });
+            current_section = Some(&message.section);
+        }
+        let role = message
+            .speaker_index
+            .and_then(|i| participants.get(i))
+            .map(|p| p.role.display_name().to_string())
+            .unwrap_or_default();
+        callback(DebateEvent::SpeakerStart {
+            name: message.speaker_name.clone(),
+            role,
+        });
+        callback(DebateEvent::SpeakerMessage {
+            name: message.speaker_name.clone(),
+            content: message.content.clone(),
+        });
+    }
+    callback(DebateEvent::DebateEnd);
+}
+
 /// Orchestrates the debate between AI participants.
 pub struct DebateOrchestrator {
     config: DebateConfig,
     participants: Vec<AIParticipant>,
     format: Box<dyn DebateFormat>,
+    /// Backend used for chat completions, per participant.
+    backends: Vec<Arc<dyn CompletionBackend>>,
     /// Message history per participant (for context).
     histories: Vec<Vec<ChatCompletionRequestMessage>>,
+    /// Ranges of each history that trimming never removes: the fixed prefix
+    /// and the messages of every pinned section.
+    pinned: Vec<Vec<Range<usize>>>,
     /// Full debate transcript.
     transcript: Vec<DebateMessage>,
     /// Event callback.
     callback: Option<DebateCallback>,
+    /// Post-processing applied to responses after sanitizing.
+    response_hook: Option<ResponseHook>,
+    /// Set (e.g. from a callback) to stop the debate before the next turn.
+    cancel: Option<Arc<AtomicBool>>,
 }
 
 impl DebateOrchestrator {
@@ -101,7 +703,21 @@ impl DebateOrchestrator {
             });
         }
 
-        let histories = participants
+        // Names are used to attribute opponent messages and transcript lines,
+        // so they must be unique.
+        for (i, p) in participants.iter().enumerate() {
+            if participants[..i].iter().any(|other| other.name == p.name) {
+                return Err(DebateError::DuplicateParticipantName(p.name.clone()));
+            }
+        }
+
+        let shared_context = format_context_docs(
+            &config.shared_context,
+            config.context_char_budget,
+            SHARED_CONTEXT_HEADER,
+        );
+
+        let histories: Vec<_> = participants
             .iter()
             .enumerate()
             .map(|(i, p)| {
@@ -111,26 +727,98 @@ impl DebateOrchestrator {
                     .map(|op| op.name.as_str())
                     .unwrap_or("Opponent");
 
-                let system_prompt = p.custom_system_prompt.clone().unwrap_or_else(|| {
-                    format.system_prompt(&config.topic, &p.display_name_with_role(), opponent_name)
+                let mut system_prompt = p.custom_system_prompt.clone().unwrap_or_else(|| {
+                    format.system_prompt_for_role(
+                        &config.topic,
+                        &p.display_name_with_role(),
+                        &p.role,
+                        opponent_name,
+                    )
                 });
 
-                vec![ChatCompletionRequestMessage::System(
+                if let Some(persona) = &p.persona {
+                    system_prompt.push_str(&format!(
+                        "\n\nYOUR PERSONA: You are {}. Argue from this perspective throughout.",
+                        persona
+                    ));
+                }
+
+                if let Some(rules) = config
+                    .rules
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|r| !r.is_empty())
+                {
+                    system_prompt.push_str(&format!("\n\nDEBATE RULES:\n{}", rules));
+                }
+
+                if let Some(date) = &config.debate_date {
+                    system_prompt = system_prompt.replace("{date}", date);
+                    system_prompt.push_str(&format!(
+                        "\n\nTODAY'S DATE: {}. Treat events up to this date as current.",
+                        date
+                    ));
+                }
+
+                let mut history = vec![ChatCompletionRequestMessage::System(
                     ChatCompletionRequestSystemMessage {
                         content: system_prompt.into(),
                         name: None,
                     },
-                )]
+                )];
+
+                let own_context = format_context_docs(
+                    &p.context_docs,
+                    config.context_char_budget,
+                    CONTEXT_DOCS_HEADER,
+                );
+                for context in shared_context.iter().chain(&own_context) {
+                    history.push(ChatCompletionRequestMessage::User(
+                        ChatCompletionRequestUserMessage {
+                            content: context.clone().into(),
+                            name: None,
+                        },
+                    ));
+                }
+
+                history
             })
             .collect();
 
+        // Participants without endpoint or retry overrides share one client
+        let default_backend: Arc<dyn CompletionBackend> = Arc::new(
+            OpenAIBackend::new(&config.api_base, &config.api_key)?.with_retries(config.api_retries),
+        );
+        let backends = participants
+            .iter()
+            .map(|p| {
+                let endpoint = endpoint_override(&config, p);
+                if endpoint.is_none() && p.max_api_retries.is_none() {
+                    return Ok(default_backend.clone());
+                }
+                let (api_base, api_key) = endpoint.unwrap_or((&config.api_base, &config.api_key));
+                let retries = p.max_api_retries.unwrap_or(config.api_retries);
+                Ok(
+                    Arc::new(OpenAIBackend::new(api_base, api_key)?.with_retries(retries))
+                        as Arc<_>,
+                )
+            })
+            .collect::<Result<Vec<_>, DebateError>>()?;
+
         Ok(Self {
             config,
             participants,
             format,
+            backends,
+            pinned: histories
+                .iter()
+                .map(|h| std::iter::once(0..h.len()).collect())
+                .collect(),
             histories,
             transcript: Vec::new(),
             callback: None,
+            response_hook: None,
+            cancel: None,
         })
     }
 
@@ -140,30 +828,319 @@ impl DebateOrchestrator {
         self
     }
 
+    /// Stop the debate once `flag` is set.
+    ///
+    /// The flag is checked between turns, so the turn in progress finishes
+    /// and [`run`](Self::run) returns the transcript so far. A frontend can
+    /// hand a clone of the flag to its callback to stop on a given event.
+    pub fn with_cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    /// Transform every response (e.g. filtering or redaction) after it is
+    /// sanitized and before it is recorded or emitted.
+    pub fn with_response_hook(mut self, hook: ResponseHook) -> Self {
+        self.response_hook = Some(hook);
+        self
+    }
+
+    /// Use a different completion backend for all participants
+    /// (e.g. a mock for offline runs).
+    pub fn with_backend(mut self, backend: Arc<dyn CompletionBackend>) -> Self {
+        self.backends = vec![backend; self.participants.len()];
+        self
+    }
+
     /// Run the full debate.
     pub async fn run(&mut self) -> Result<Vec<DebateMessage>, DebateError> {
+        if self.config.warmup {
+            self.warm_up().await?;
+        }
+
         let sections = self.format.sections();
+        let halftime = sections.len() / 2;
 
-        for section in sections {
-            self.run_section(&section).await?;
+        for (i, section) in sections.iter().enumerate() {
+            // Pin sections such as the opening statements so trimming never
+            // drops them. The range stays open while the section runs and is
+            // closed at the section's end.
+            if section.pinned {
+                for (pinned, history) in self.pinned.iter_mut().zip(&self.histories) {
+                    pinned.push(history.len()..usize::MAX);
+                }
+            }
+            let result = self.run_section(section).await;
+            if section.pinned {
+                for (pinned, history) in self.pinned.iter_mut().zip(&self.histories) {
+                    if let Some(range) = pinned.last_mut() {
+                        range.end = history.len();
+                    }
+                }
+            }
+            result?;
+            if self.is_cancelled() {
+                break;
+            }
+            if self.config.halftime_recap && i + 1 == halftime {
+                self.halftime_recap(section).await;
+            }
         }
 
         self.emit_event(DebateEvent::DebateEnd);
         Ok(self.transcript.clone())
     }
 
+    /// Whether the cancel flag has been set.
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+
+    /// Run the full debate, returning the transcript grouped by section.
+    pub async fn run_grouped(&mut self) -> Result<Vec<SectionTranscript>, DebateError> {
+        let transcript = self.run().await?;
+        Ok(group_by_section(&transcript, &self.format.sections()))
+    }
+
+    /// Send each participant's backend a throwaway request, discarding the reply.
+    ///
+    /// The request is built from scratch, so nothing is added to the
+    /// participant's history or the transcript.
+    async fn warm_up(&self) -> Result<(), DebateError> {
+        for (i, participant) in self.participants.iter().enumerate() {
+            let request = CompletionRequest {
+                model: participant.model.clone(),
+                messages: vec![ChatCompletionRequestMessage::User(
+                    ChatCompletionRequestUserMessage {
+                        content: WARMUP_PROMPT.into(),
+                        name: None,
+                    },
+                )],
+                max_tokens: WARMUP_TOKENS,
+                participant_index: Some(i),
+                json_mode: false,
+            };
+            self.backends[i].complete(request).await?;
+        }
+        Ok(())
+    }
+
+    /// Have the first participant's model summarize the debate so far, and
+    /// add the summary to the transcript as an announcer entry at the end of
+    /// `section`.
+    ///
+    /// The recap is not added to any participant's history. A failed recap
+    /// is reported and skipped rather than ending the debate.
+    async fn halftime_recap(&mut self, section: &DebateSection) {
+        let turns = self.participant_turns();
+        let request = CompletionRequest {
+            model: self.participants[0].model.clone(),
+            messages: vec![
+                ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
+                    content: RECAP_PROMPT.replace("{topic}", &self.config.topic).into(),
+                    name: None,
+                }),
+                ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+                    content: turns.into(),
+                    name: None,
+                }),
+            ],
+            max_tokens: RECAP_TOKENS,
+            participant_index: None,
+            json_mode: false,
+        };
+
+        let response = match self.backends[0].complete(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                eprintln!("  [Recap] Halftime recap failed, skipping: {}", e);
+                return;
+            }
+        };
+        let recap = sanitize_response(&response.content, &self.config.sanitize);
+        if recap.trim().is_empty() {
+            eprintln!("  [Recap] Halftime recap was empty, skipping");
+            return;
+        }
+
+        self.emit_event(DebateEvent::SpeakerStart {
+            name: ANNOUNCER_NAME.to_string(),
+            role: "HALFTIME RECAP".to_string(),
+        });
+        self.emit_event(DebateEvent::SpeakerMessage {
+            name: ANNOUNCER_NAME.to_string(),
+            content: recap.clone(),
+        });
+        self.transcript.push(DebateMessage {
+            section: section.name.clone(),
+            speaker_index: None,
+            speaker_name: ANNOUNCER_NAME.to_string(),
+            content: recap,
+            raw_content: self.config.keep_raw.then_some(response.content),
+        });
+    }
+
+    /// Have the first participant's model write a question opening `section`,
+    /// and add it to the transcript and to every participant's history.
+    ///
+    /// The moderator sees the participants' turns so far. A failed question
+    /// is reported and skipped rather than ending the debate.
+    async fn moderator_question(&mut self, prompt: &str, section: &DebateSection) {
+        let turns = self.participant_turns();
+        let request = CompletionRequest {
+            model: self.participants[0].model.clone(),
+            messages: vec![
+                ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
+                    content: render_moderator_prompt(
+                        prompt,
+                        &self.config.topic,
+                        &section.name,
+                        &section.description,
+                    )
+                    .into(),
+                    name: None,
+                }),
+                ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+                    content: format!("{}{}", turns, MODERATOR_REQUEST).into(),
+                    name: None,
+                }),
+            ],
+            max_tokens: MODERATOR_TOKENS,
+            participant_index: None,
+            json_mode: false,
+        };
+
+        let response = match self.backends[0].complete(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                eprintln!("  [Moderator] Question failed, skipping: {}", e);
+                return;
+            }
+        };
+        let question = sanitize_response(&response.content, &self.config.sanitize);
+        if question.trim().is_empty() {
+            eprintln!("  [Moderator] Question was empty, skipping");
+            return;
+        }
+
+        self.emit_event(DebateEvent::SpeakerStart {
+            name: MODERATOR_NAME.to_string(),
+            role: "MODERATOR".to_string(),
+        });
+        self.emit_event(DebateEvent::SpeakerMessage {
+            name: MODERATOR_NAME.to_string(),
+            content: question.clone(),
+        });
+
+        let shown = MODERATOR_MESSAGE_TEMPLATE.replace("{content}", &question);
+        for history in &mut self.histories {
+            history.push(ChatCompletionRequestMessage::User(
+                ChatCompletionRequestUserMessage {
+                    content: shown.clone().into(),
+                    name: Some(MODERATOR_NAME.to_string()),
+                },
+            ));
+        }
+        self.transcript.push(DebateMessage {
+            section: section.name.clone(),
+            speaker_index: None,
+            speaker_name: MODERATOR_NAME.to_string(),
+            content: question,
+            raw_content: self.config.keep_raw.then_some(response.content),
+        });
+    }
+
+    /// Have a model score the debate so far on `judge`'s criteria.
+    ///
+    /// The judge sees the participants' turns (not announcer entries) and
+    /// nothing is added to the transcript or any history. It is asked at
+    /// [`JudgeConfig::endpoint`] if set, otherwise through the first
+    /// participant's backend.
+    pub async fn judge(&self, judge: &JudgeConfig) -> Result<JudgeVerdict, DebateError> {
+        judge.validate()?;
+        let backend: Arc<dyn CompletionBackend> = match &judge.endpoint {
+            Some(api_base) => Arc::new(
+                OpenAIBackend::new(api_base, &self.config.api_key)?
+                    .with_retries(self.config.api_retries),
+            ),
+            None => self.backends[0].clone(),
+        };
+        let names: Vec<String> = self.participants.iter().map(|p| p.name.clone()).collect();
+        let response = backend.complete(self.judge_request(judge, &names)).await?;
+        judge.parse_verdict(&response.content, &names)
+    }
+
+    /// The request sent by [`judge`](Self::judge).
+    fn judge_request(&self, judge: &JudgeConfig, names: &[String]) -> CompletionRequest {
+        CompletionRequest {
+            model: judge
+                .model
+                .clone()
+                .unwrap_or_else(|| self.participants[0].model.clone()),
+            messages: vec![
+                ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
+                    content: judge.judge_prompt(&self.config.topic, names).into(),
+                    name: None,
+                }),
+                ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+                    content: self.participant_turns().into(),
+                    name: None,
+                }),
+            ],
+            max_tokens: JUDGE_TOKENS,
+            participant_index: None,
+            json_mode: self.config.json_mode,
+        }
+    }
+
+    /// The participants' turns so far as plain text, one per paragraph.
+    fn participant_turns(&self) -> String {
+        self.transcript
+            .iter()
+            .filter(|m| !m.is_announcer())
+            .map(|m| {
+                format!(
+                    "{} ({}): {}\n\n",
+                    m.speaker_name,
+                    m.section,
+                    m.content.trim()
+                )
+            })
+            .collect()
+    }
+
     /// Run a single debate section.
     async fn run_section(&mut self, section: &DebateSection) -> Result<(), DebateError> {
         self.emit_event(DebateEvent::SectionStart {
             name: section.name.clone(),
             description: section.description.clone(),
         });
+        if let Some(prompt) = self.config.moderator_prompt.clone() {
+            self.moderator_question(&prompt, section).await;
+        }
 
         for &speaker_idx in &section.speaker_order {
+            if self.is_cancelled() {
+                return Ok(());
+            }
             if speaker_idx >= self.participants.len() {
+                if self.config.strict {
+                    return Err(DebateError::ConfigError(format!(
+                        "Section '{}' refers to speaker {} but there are only {} participants",
+                        section.name,
+                        speaker_idx,
+                        self.participants.len()
+                    )));
+                }
                 continue;
             }
 
+            if let Some(delay) = self.config.turn_delay {
+                tokio::time::sleep(delay).await;
+            }
+
             let participant = &self.participants[speaker_idx];
             self.emit_event(DebateEvent::SpeakerStart {
                 name: participant.name.clone(),
@@ -171,12 +1148,12 @@ impl DebateOrchestrator {
             });
 
             // Build the prompt for this turn
-            let section_prompt = format!(
-                "[{} - {}]\nPlease provide your {}.",
-                section.name,
-                section.description,
-                section.name.to_lowercase()
-            );
+            let mut section_prompt = section.speaker_prompt(speaker_idx);
+            if section.summarize_opponent
+                && let Some(digest) = opponent_digest(&self.transcript, speaker_idx)
+            {
+                section_prompt.push_str(&digest);
+            }
 
             // Add section prompt to this participant's history
             self.histories[speaker_idx].push(ChatCompletionRequestMessage::User(
@@ -186,46 +1163,62 @@ impl DebateOrchestrator {
                 },
             ));
 
-            // Get response from the AI with retry logic for empty responses
-            let max_empty_retries = 3;
-            let mut sanitized_response = String::new();
-
-            for attempt in 0..max_empty_retries {
-                let response = self.get_completion(speaker_idx, section.max_tokens).await?;
-                sanitized_response = sanitize_response(&response);
+            if let Some(window) = self.config.history_window {
+                trim_history(
+                    &mut self.histories[speaker_idx],
+                    &mut self.pinned[speaker_idx],
+                    window,
+                );
+            }
+            let (mut sanitized_response, mut raw_response) =
+                self.get_response(speaker_idx, section).await?;
 
-                // Check if response is non-empty (has meaningful content)
-                if !sanitized_response.trim().is_empty() && sanitized_response.trim().len() > 10 {
-                    break;
-                }
-
-                // Log retry attempt (response was empty or too short)
-                if attempt < max_empty_retries - 1 {
-                    eprintln!(
-                        "  [Retry {}/{}] Empty response from {}, retrying...",
-                        attempt + 1,
-                        max_empty_retries,
-                        participant.name
-                    );
-                    // Brief delay before retry
-                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            // Re-prompt once (or give up) if the speaker refuses to take a stance
+            if is_refusal(&sanitized_response) {
+                if self.config.fail_fast_on_refusal {
+                    return Err(DebateError::Refusal {
+                        participant: participant.name.clone(),
+                    });
                 }
+                eprintln!(
+                    "  [Refusal] {} refused to take a stance, re-prompting...",
+                    participant.name
+                );
+                self.histories[speaker_idx].push(ChatCompletionRequestMessage::User(
+                    ChatCompletionRequestUserMessage {
+                        content: REFUSAL_REMINDER.into(),
+                        name: None,
+                    },
+                ));
+                (sanitized_response, raw_response) =
+                    self.get_response(speaker_idx, section).await?;
             }
 
-            // If still empty after retries, return an error
-            if sanitized_response.trim().is_empty() || sanitized_response.trim().len() <= 10 {
-                return Err(DebateError::ConfigError(format!(
-                    "AI participant '{}' returned empty response after {} retries. Debate cannot continue.",
-                    participant.name, max_empty_retries
-                )));
+            // Re-prompt once if the speaker is repeating themselves
+            if let Some(threshold) = self.config.repetition_threshold
+                && self.is_repetitive(speaker_idx, &sanitized_response, threshold)
+            {
+                eprintln!(
+                    "  [Repetition] {} repeated an earlier argument, re-prompting...",
+                    participant.name
+                );
+                self.histories[speaker_idx].push(ChatCompletionRequestMessage::User(
+                    ChatCompletionRequestUserMessage {
+                        content: REPETITION_REMINDER.into(),
+                        name: None,
+                    },
+                ));
+                (sanitized_response, raw_response) =
+                    self.get_response(speaker_idx, section).await?;
             }
 
             // Record the message
             let message = DebateMessage {
                 section: section.name.clone(),
-                speaker_index: speaker_idx,
+                speaker_index: Some(speaker_idx),
                 speaker_name: participant.name.clone(),
                 content: sanitized_response.clone(),
+                raw_content: self.config.keep_raw.then_some(raw_response),
             };
             self.transcript.push(message);
 
@@ -236,27 +1229,23 @@ impl DebateOrchestrator {
 
             // Add assistant response to speaker's history
             self.histories[speaker_idx].push(ChatCompletionRequestMessage::Assistant(
-                ChatCompletionRequestAssistantMessage {
-                    content: Some(sanitized_response.clone().into()),
-                    name: None,
-                    tool_calls: None,
-                    refusal: None,
-                    audio: None,
-                    function_call: None,
-                },
+                sanitized_response.clone().into(),
             ));
 
             // Add opponent's statement to all other participants' histories
+            let opponent_msg = self
+                .config
+                .opponent_message_template
+                .replace("{name}", &self.participants[speaker_idx].name)
+                .replace("{content}", &sanitized_response);
             for (i, history) in self.histories.iter_mut().enumerate() {
                 if i != speaker_idx {
-                    let opponent_msg = format!(
-                        "[Opponent {} said]: {}",
-                        self.participants[speaker_idx].name, sanitized_response
-                    );
+                    // Attribute the message to its speaker so models don't
+                    // mistake it for their own instructions
                     history.push(ChatCompletionRequestMessage::User(
                         ChatCompletionRequestUserMessage {
-                            content: opponent_msg.into(),
-                            name: None,
+                            content: opponent_msg.clone().into(),
+                            name: Some(message_author_name(&self.participants[speaker_idx].name)),
                         },
                     ));
                 }
@@ -266,71 +1255,182 @@ impl DebateOrchestrator {
         Ok(())
     }
 
+    /// Get a sanitized, non-empty response for a turn in `section`,
+    /// retrying on empty replies.
+    ///
+    /// Returns the sanitized response and the raw response it came from.
+    async fn get_response(
+        &self,
+        speaker_idx: usize,
+        section: &DebateSection,
+    ) -> Result<(String, String), DebateError> {
+        let participant = &self.participants[speaker_idx];
+        let model = section
+            .per_speaker_models
+            .get(&speaker_idx)
+            .or(section.model_override.as_ref())
+            .unwrap_or(&participant.model);
+        let empty_retries = participant
+            .max_empty_retries
+            .unwrap_or(self.config.empty_retries);
+        let max_attempts = empty_retries + 1;
+        let mut sanitized_response = String::new();
+        let mut response = String::new();
+
+        // A zero budget can only produce empty responses; don't retry them
+        if section.max_tokens == 0 {
+            return Err(DebateError::ConfigError(format!(
+                "Section '{}' has a token budget of 0",
+                section.name
+            )));
+        }
+
+        for attempt in 0..max_attempts {
+            response = self
+                .get_completion(speaker_idx, model, section.max_tokens)
+                .await?;
+            sanitized_response = sanitize_response(&response, &self.config.sanitize);
+
+            // Check if response is non-empty (has meaningful content)
+            if !sanitized_response.trim().is_empty() && sanitized_response.trim().len() > 10 {
+                break;
+            }
+
+            // Log retry attempt (response was empty or too short)
+            if attempt < max_attempts - 1 {
+                eprintln!(
+                    "  [Retry {}/{}] Empty response from {}, retrying...",
+                    attempt + 1,
+                    empty_retries,
+                    participant.name
+                );
+                // Brief delay before retry
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+        }
+
+        // If still empty after retries, return an error
+        if sanitized_response.trim().is_empty() || sanitized_response.trim().len() <= 10 {
+            return Err(DebateError::EmptyResponse(format!(
+                "AI participant '{}' returned empty response after {} attempts. Debate cannot continue.",
+                participant.name, max_attempts
+            )));
+        }
+
+        let sanitized_response = match &self.response_hook {
+            Some(hook) => hook(&sanitized_response),
+            None => sanitized_response,
+        };
+        Ok((sanitized_response, response))
+    }
+
+    /// Check whether a response is too similar to the speaker's recent ones.
+    fn is_repetitive(&self, speaker_idx: usize, response: &str, threshold: f32) -> bool {
+        self.transcript
+            .iter()
+            .rev()
+            .filter(|m| m.speaker_index == Some(speaker_idx))
+            .take(REPETITION_LOOKBACK)
+            .any(|m| response_similarity(&m.content, response) >= threshold)
+    }
+
     /// Get a completion from the AI for a specific participant.
-    /// Includes retry logic with exponential backoff for resilience.
+    ///
+    /// If enabled, a response cut off by the token limit is continued with
+    /// further requests, and then gets one short request so it ends on a
+    /// complete sentence.
     async fn get_completion(
         &self,
         participant_idx: usize,
+        model: &str,
         max_tokens: u32,
     ) -> Result<String, DebateError> {
-        let participant = &self.participants[participant_idx];
-        let history = &self.histories[participant_idx];
-
-        // Create custom HTTP client that skips SSL verification with timeout
-        let http_client = reqwest::Client::builder()
-            .danger_accept_invalid_certs(true)
-            .timeout(std::time::Duration::from_secs(120))
-            .connect_timeout(std::time::Duration::from_secs(30))
-            .build()
-            .map_err(|e| {
-                DebateError::ConfigError(format!("Failed to create HTTP client: {}", e))
-            })?;
-
-        let config = OpenAIConfig::new()
-            .with_api_key(&self.config.api_key)
-            .with_api_base(&self.config.api_base);
-
-        let client = Client::with_config(config).with_http_client(http_client);
-
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(&participant.model)
-            .max_completion_tokens(max_tokens)
-            .messages(history.clone())
-            .build()?;
-
-        // Retry logic with exponential backoff
-        let max_retries = 3;
-        let mut last_error = None;
-
-        for attempt in 0..max_retries {
-            if attempt > 0 {
-                // Exponential backoff: 1s, 2s, 4s
-                let delay = std::time::Duration::from_secs(1 << attempt);
-                tokio::time::sleep(delay).await;
-            }
+        let request = CompletionRequest {
+            model: model.to_string(),
+            messages: self.histories[participant_idx].clone(),
+            max_tokens,
+            participant_index: Some(participant_idx),
+            json_mode: false,
+        };
 
-            match client.chat().create(request.clone()).await {
-                Ok(response) => {
-                    let content = response
-                        .choices
-                        .first()
-                        .and_then(|c| c.message.content.clone())
-                        .unwrap_or_default();
-                    return Ok(content);
-                }
-                Err(e) => {
-                    last_error = Some(e);
-                    // Only retry on transient errors
-                    if attempt < max_retries - 1 {
-                        continue;
-                    }
-                }
-            }
+        let backend = &self.backends[participant_idx];
+        self.dump_prompt(participant_idx, &request.messages);
+        let response = backend.complete(request.clone()).await?;
+        let mut content = response.content.clone();
+        let mut last = response;
+
+        // Feed each part back as the speaker's own words and ask for more
+        let mut messages = request.messages;
+        let mut continuations = 0;
+        while last.is_truncated() && continuations < self.config.max_continuations {
+            messages.push(ChatCompletionRequestMessage::Assistant(
+                last.content.clone().into(),
+            ));
+            messages.push(ChatCompletionRequestMessage::User(
+                ChatCompletionRequestUserMessage {
+                    content: CONTINUE_PROMPT.into(),
+                    name: None,
+                },
+            ));
+
+            self.dump_prompt(participant_idx, &messages);
+            last = backend
+                .complete(CompletionRequest {
+                    model: request.model.clone(),
+                    messages: messages.clone(),
+                    max_tokens,
+                    participant_index: request.participant_index,
+                    json_mode: request.json_mode,
+                })
+                .await?;
+            content = format!("{} {}", content.trim_end(), last.content.trim());
+            continuations += 1;
+        }
+
+        if !(self.config.finish_truncated && last.is_truncated()) {
+            return Ok(content);
+        }
+
+        messages.push(ChatCompletionRequestMessage::Assistant(
+            last.content.clone().into(),
+        ));
+        messages.push(ChatCompletionRequestMessage::User(
+            ChatCompletionRequestUserMessage {
+                content: FINISH_SENTENCE_PROMPT.into(),
+                name: None,
+            },
+        ));
+
+        self.dump_prompt(participant_idx, &messages);
+        let continuation = backend
+            .complete(CompletionRequest {
+                model: request.model,
+                messages,
+                max_tokens: FINISH_SENTENCE_TOKENS,
+                participant_index: request.participant_index,
+                json_mode: request.json_mode,
+            })
+            .await?;
+
+        Ok(format!(
+            "{} {}",
+            content.trim_end(),
+            continuation.content.trim()
+        ))
+    }
+
+    /// Emit the messages about to be sent, if prompt debugging is enabled.
+    fn dump_prompt(&self, speaker_index: usize, messages: &[ChatCompletionRequestMessage]) {
+        if !self.config.debug_prompts || self.callback.is_none() {
+            return;
         }
 
-        Err(last_error.map(DebateError::from).unwrap_or_else(|| {
-            DebateError::ConfigError("Unknown API error after retries".to_string())
-        }))
+        let messages_json = serde_json::to_string_pretty(messages)
+            .unwrap_or_else(|e| format!("<failed to serialize messages: {}>", e));
+        self.emit_event(DebateEvent::PromptDump {
+            speaker_index,
+            messages_json,
+        });
     }
 
     /// Emit an event if a callback is registered.
@@ -345,56 +1445,408 @@ impl DebateOrchestrator {
         &self.transcript
     }
 
+    /// The topic, participants and sections of the debate, without running it.
+    pub fn plan(&self) -> DebatePlan {
+        DebatePlan {
+            topic: self.config.topic.clone(),
+            format: self.format.display_name().to_string(),
+            participants: self
+                .participants
+                .iter()
+                .map(|p| PlannedParticipant {
+                    name: p.name.clone(),
+                    role: p.role.display_name().to_string(),
+                    model: p.model.clone(),
+                })
+                .collect(),
+            sections: self
+                .format
+                .sections()
+                .into_iter()
+                .map(|section| PlannedSection {
+                    speakers: section
+                        .speaker_order
+                        .iter()
+                        .filter_map(|&i| self.participants.get(i))
+                        .map(|p| p.name.clone())
+                        .collect(),
+                    name: section.name,
+                    description: section.description,
+                })
+                .collect(),
+        }
+    }
+
     /// Get participants.
     pub fn participants(&self) -> &[AIParticipant] {
         &self.participants
     }
+
+    /// Get the debate configuration.
+    pub fn config(&self) -> &DebateConfig {
+        &self.config
+    }
+
+    /// The system prompt each participant starts with, in participant order.
+    pub fn system_prompts(&self) -> Vec<String> {
+        self.histories
+            .iter()
+            .map(|history| match history.first() {
+                Some(ChatCompletionRequestMessage::System(message)) => match &message.content {
+                    ChatCompletionRequestSystemMessageContent::Text(text) => text.clone(),
+                    _ => String::new(),
+                },
+                _ => String::new(),
+            })
+            .collect()
+    }
+}
+
+/// Run several independent debates concurrently.
+///
+/// At most `concurrency` debates run at once. Results are returned in the
+/// same order as the input, one per debate.
+pub async fn run_debates(
+    debates: Vec<(DebateConfig, Vec<AIParticipant>, Box<dyn DebateFormat>)>,
+    concurrency: usize,
+) -> Vec<Result<Vec<DebateMessage>, DebateError>> {
+    let orchestrators = debates
+        .into_iter()
+        .map(|(config, participants, format)| DebateOrchestrator::new(config, participants, format))
+        .collect();
+
+    run_all(orchestrators, concurrency).await
+}
+
+/// Run several already-constructed orchestrators concurrently.
+///
+/// Like [`run_debates`], but allows each orchestrator to carry its own
+/// backend and callback.
+pub async fn run_orchestrators(
+    orchestrators: Vec<DebateOrchestrator>,
+    concurrency: usize,
+) -> Vec<Result<Vec<DebateMessage>, DebateError>> {
+    run_all(orchestrators.into_iter().map(Ok).collect(), concurrency).await
+}
+
+/// Spawn each orchestrator on a task, bounded by a semaphore.
+async fn run_all(
+    orchestrators: Vec<Result<DebateOrchestrator, DebateError>>,
+    concurrency: usize,
+) -> Vec<Result<Vec<DebateMessage>, DebateError>> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+
+    let tasks: Vec<_> = orchestrators
+        .into_iter()
+        .map(|orchestrator| {
+            orchestrator.map(|mut orchestrator| {
+                let semaphore = semaphore.clone();
+                tokio::spawn(async move {
+                    let _permit = semaphore
+                        .acquire_owned()
+                        .await
+                        .expect("semaphore is never closed");
+                    orchestrator.run().await
+                })
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        results.push(match task {
+            Ok(handle) => match handle.await {
+                Ok(result) => result,
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            },
+            Err(e) => Err(e),
+        });
+    }
+
+    results
+}
+
+/// Longest topic, in characters, used in prompts and filenames.
+pub const MAX_TOPIC_CHARS: usize = 300;
+
+/// Clean up a debate topic: trim it, collapse internal whitespace, and
+/// truncate it to [`MAX_TOPIC_CHARS`] at a word boundary.
+///
+/// Empty or whitespace-only topics are an error.
+pub fn normalize_topic(topic: &str) -> Result<String, DebateError> {
+    let topic = topic.split_whitespace().collect::<Vec<_>>().join(" ");
+    if topic.is_empty() {
+        return Err(DebateError::ConfigError(
+            "The debate topic cannot be empty".to_string(),
+        ));
+    }
+
+    if topic.chars().count() <= MAX_TOPIC_CHARS {
+        return Ok(topic);
+    }
+
+    let cut = topic
+        .char_indices()
+        .nth(MAX_TOPIC_CHARS)
+        .map_or(topic.len(), |(i, _)| i);
+    let truncated = match topic[..cut].rfind(' ') {
+        Some(space) => &topic[..space],
+        None => &topic[..cut],
+    };
+    Ok(format!("{}...", truncated))
+}
+
+/// Convert a participant name into a valid chat message `name` field.
+///
+/// The API only accepts letters, digits, underscores and hyphens (up to 64
+/// characters), so anything else is replaced with an underscore.
+fn message_author_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(64)
+        .collect()
+}
+
+/// A bulleted digest of every earlier turn not spoken by `speaker_idx`,
+/// so a closing can address the opponent's actual arguments.
+///
+/// Each turn is reduced to its first sentence, capped at
+/// [`DIGEST_TURN_CHARS`]. `None` if the opponents have not spoken yet.
+fn opponent_digest(transcript: &[DebateMessage], speaker_idx: usize) -> Option<String> {
+    let bullets: Vec<String> = transcript
+        .iter()
+        .filter(|m| m.speaker_index.is_some_and(|i| i != speaker_idx))
+        .map(|m| {
+            let content = m.content.trim();
+            let first_sentence = content
+                .find(['.', '!', '?'])
+                .map_or(content, |end| &content[..=end]);
+            let mut summary: String = first_sentence.chars().take(DIGEST_TURN_CHARS).collect();
+            if summary.len() < first_sentence.len() {
+                summary.push_str("...");
+            }
+            format!("- {} ({}): {}", m.speaker_name, m.section, summary)
+        })
+        .collect();
+
+    if bullets.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "\n\nYour opponent's arguments so far:\n{}\nAddress these points directly in your summation.",
+        bullets.join("\n")
+    ))
+}
+
+/// The API base and key for a participant with endpoint overrides, with any
+/// part not overridden taken from the debate config. `None` if the
+/// participant uses the debate defaults.
+fn endpoint_override<'a>(
+    config: &'a DebateConfig,
+    participant: &'a AIParticipant,
+) -> Option<(&'a str, &'a str)> {
+    if participant.api_base.is_none() && participant.api_key.is_none() {
+        return None;
+    }
+
+    Some((
+        participant.api_base.as_deref().unwrap_or(&config.api_base),
+        participant.api_key.as_deref().unwrap_or(&config.api_key),
+    ))
+}
+
+/// Join context documents into one reference message, truncated to `budget` characters.
+fn format_context_docs(docs: &[String], budget: usize, header: &str) -> Option<String> {
+    if docs.is_empty() || budget == 0 {
+        return None;
+    }
+
+    let joined = docs
+        .iter()
+        .enumerate()
+        .map(|(i, doc)| format!("[Document {}]\n{}", i + 1, doc.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let documents = match joined.char_indices().nth(budget) {
+        Some((cut, _)) => format!("{}\n[...truncated]", &joined[..cut]),
+        None => joined,
+    };
+
+    Some(format!("{}\n\n{}", header, documents))
+}
+
+/// Remove paired `*`/`**` emphasis markers, keeping the emphasized text.
+///
+/// Asterisks that are not wrapped around text, or that sit between word
+/// characters (as in "3*4*5"), are left alone.
+fn strip_emphasis(text: &str) -> String {
+    let mut result = text.to_string();
+    for pattern in [
+        r"(^|[^\w*])\*\*([^*\s](?:[^*\n]*?[^*\s])?)\*\*($|[^\w*])",
+        r"(^|[^\w*])\*([^*\s](?:[^*\n]*?[^*\s])?)\*($|[^\w*])",
+    ] {
+        if let Ok(re) = regex::Regex::new(pattern) {
+            // Each match consumes the character on either side of it, so a
+            // span right after another ("*a* *b*") needs another pass
+            loop {
+                let replaced = re.replace_all(&result, "$1$2$3");
+                if replaced == result {
+                    break;
+                }
+                result = replaced.into_owned();
+            }
+        }
+    }
+    result
+}
+
+/// Similarity between two responses as the Jaccard overlap of their word
+/// 3-shingles (or single words for very short texts), from 0.0 to 1.0.
+fn response_similarity(a: &str, b: &str) -> f32 {
+    fn shingles(text: &str) -> HashSet<Vec<String>> {
+        let words: Vec<String> = text
+            .split_whitespace()
+            .map(|w| {
+                w.chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .collect::<String>()
+                    .to_lowercase()
+            })
+            .filter(|w| !w.is_empty())
+            .collect();
+        let size = if words.len() < 3 { 1 } else { 3 };
+        words.windows(size).map(|w| w.to_vec()).collect()
+    }
+
+    let (a, b) = (shingles(a), shingles(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let shared = a.intersection(&b).count();
+    shared as f32 / (a.len() + b.len() - shared) as f32
+}
+
+/// Drop the oldest messages outside the `pinned` ranges so at most `window`
+/// of them remain, moving the ranges to match.
+fn trim_history(
+    history: &mut Vec<ChatCompletionRequestMessage>,
+    pinned: &mut [Range<usize>],
+    window: usize,
+) {
+    let mut unpinned = 0;
+    let mut keep: Vec<bool> = (0..history.len())
+        .rev()
+        .map(|i| {
+            if pinned.iter().any(|range| range.contains(&i)) {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= window
+        })
+        .collect();
+    keep.reverse();
+
+    for range in pinned.iter_mut() {
+        let removed = keep[..range.start.min(keep.len())]
+            .iter()
+            .filter(|&&kept| !kept)
+            .count();
+        range.start -= removed;
+        range.end = range.end.saturating_sub(removed);
+    }
+    let mut keep = keep.into_iter();
+    history.retain(|_| keep.next().unwrap_or(true));
+}
+
+/// Check whether a response declines to argue its side.
+///
+/// Only the first sentence is checked: a refusal leads with it, while the
+/// same words later in a turn ("I can't take sides with polluters") are
+/// part of an argument.
+fn is_refusal(response: &str) -> bool {
+    let response = response
+        .trim_start()
+        .to_lowercase()
+        .replace('\u{2019}', "'");
+    let first_sentence = response
+        .split(['.', '!', '?', '\n'])
+        .next()
+        .unwrap_or_default();
+    REFUSAL_PHRASES
+        .iter()
+        .any(|phrase| first_sentence.contains(phrase))
+}
+
+/// Drop Markdown code fence lines (backtick or tilde fences, with or without
+/// a language tag), keeping the lines between them.
+fn unwrap_code_fences(text: &str) -> String {
+    text.lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            !(line.starts_with("```") || line.starts_with("~~~"))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Sanitize AI response by stripping reasoning tokens and XML-like tags.
 ///
 /// Removes patterns like <thinking>...</thinking>, <reflection>...</reflection>, etc.
-fn sanitize_response(response: &str) -> String {
-    // List of known reasoning/internal tags to strip with their content
-    let tags_to_strip = [
-        "thinking",
-        "think",
-        "reflection",
-        "reflect",
-        "internal",
-        "reasoning",
-        "thought",
-        "scratch",
-        "scratchpad",
-        "plan",
-        "analysis",
-        "analyze",
-        "consider",
-        "pondering",
-        "deliberation",
-    ];
-
-    let mut result = response.to_string();
+fn sanitize_response(response: &str, config: &SanitizeConfig) -> String {
+    let mut result = if config.unwrap_code_fences {
+        unwrap_code_fences(response)
+    } else {
+        response.to_string()
+    };
 
     // Strip each known tag and its content
-    for tag in &tags_to_strip {
+    for tag in &config.strip_tags {
         // Match <tag>...</tag> including with attributes and newlines
-        let pattern = format!(r"(?is)<{tag}[^>]*>.*?</{tag}>", tag = tag);
+        let pattern = format!(r"(?is)<{tag}\b[^>]*>.*?</{tag}\s*>", tag = tag);
         if let Ok(re) = regex::Regex::new(&pattern) {
             result = re.replace_all(&result, "").to_string();
         }
     }
 
-    // Also remove any remaining orphaned opening/closing tags
-    if let Ok(orphan_re) = regex::Regex::new(r"</?[\w]+[^>]*>") {
+    // Also remove any remaining orphaned opening/closing tags. Only strip
+    // things shaped like real markup (a tag name directly after `<`, and any
+    // attributes as name="value") so comparisons like "x<y and y>z" survive.
+    if config.strip_markup
+        && let Ok(orphan_re) = regex::Regex::new(
+            r#"</?[A-Za-z][\w:-]*(?:\s+[\w:-]+\s*=\s*(?:"[^"]*"|'[^']*'))*\s*/?>"#,
+        )
+    {
         result = orphan_re.replace_all(&result, "").to_string();
     }
 
+    // Drop disclaimer sentences such as "As an AI language model, ..."
+    if config.strip_disclaimers
+        && let Ok(disclaimer_re) = regex::Regex::new(
+            r"(?i)\b(?:as an ai(?: language model| assistant)?|i'?m (?:just )?an ai(?: language model)?)\b[^.!?]*[.!?]\s*",
+        )
+    {
+        result = disclaimer_re.replace_all(&result, "").to_string();
+    }
+
     // Remove markdown emphasis markers (asterisks)
-    result = result.replace("*", "");
+    result = match config.markdown_stripping {
+        MarkdownStripping::Aggressive => result.replace("*", ""),
+        MarkdownStripping::Conservative => strip_emphasis(&result),
+        MarkdownStripping::Keep => result,
+    };
 
     // Clean up extra whitespace (multiple spaces/newlines become single)
-    if let Ok(ws_re) = regex::Regex::new(r"\s+") {
+    if config.collapse_whitespace
+        && let Ok(ws_re) = regex::Regex::new(r"\s+")
+    {
         result = ws_re.replace_all(&result, " ").to_string();
     }
 
@@ -404,48 +1856,1454 @@ fn sanitize_response(response: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::{CompletionResponse, MockBackend};
+    use crate::debate_format::{FilteredFormat, PresidentialDebateFormat};
+    use crate::participant::ParticipantRole;
+    use async_openai::types::chat::ChatCompletionRequestUserMessageContent;
+
+    fn mock_participants() -> Vec<AIParticipant> {
+        vec![
+            AIParticipant::new("Alice", "model-a", ParticipantRole::For),
+            AIParticipant::new("Bob", "model-b", ParticipantRole::Against),
+        ]
+    }
+
+    /// Two-person format with explicitly given sections.
+    struct TestFormat {
+        sections: Vec<DebateSection>,
+    }
+
+    impl DebateFormat for TestFormat {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn display_name(&self) -> &str {
+            "Test Format"
+        }
+
+        fn sections(&self) -> Vec<DebateSection> {
+            self.sections.clone()
+        }
+
+        fn max_participants(&self) -> usize {
+            2
+        }
+
+        fn min_participants(&self) -> usize {
+            2
+        }
+
+        fn system_prompt(&self, topic: &str, role_name: &str, _opponent_name: &str) -> String {
+            format!("{} debating {}", role_name, topic)
+        }
+    }
+
+    fn test_section(name: &str, speaker_order: Vec<usize>) -> DebateSection {
+        DebateSection {
+            name: name.to_string(),
+            description: format!("{} description", name),
+            speaker_order,
+            max_tokens: 100,
+            per_speaker_prompts: Default::default(),
+            summarize_opponent: false,
+            model_override: None,
+            per_speaker_models: Default::default(),
+            pinned: false,
+        }
+    }
+
+    /// Text of the system message at the start of a history.
+    fn system_message(history: &[ChatCompletionRequestMessage]) -> String {
+        match &history[0] {
+            ChatCompletionRequestMessage::System(m) => match &m.content {
+                ChatCompletionRequestSystemMessageContent::Text(text) => text.clone(),
+                _ => String::new(),
+            },
+            _ => panic!("history does not start with a system message"),
+        }
+    }
+
+    /// Text of every user message in a history.
+    fn user_messages(history: &[ChatCompletionRequestMessage]) -> Vec<String> {
+        history
+            .iter()
+            .filter_map(|m| match m {
+                ChatCompletionRequestMessage::User(u) => match &u.content {
+                    ChatCompletionRequestUserMessageContent::Text(text) => Some(text.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn mock_orchestrator(backend: Arc<MockBackend>) -> DebateOrchestrator {
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(backend)
+    }
+
+    #[tokio::test]
+    async fn test_run_orchestrators_concurrently() {
+        let backend = Arc::new(MockBackend::new());
+        let orchestrators = (0..5).map(|_| mock_orchestrator(backend.clone())).collect();
+
+        let results = run_orchestrators(orchestrators, 2).await;
+
+        assert_eq!(results.len(), 5);
+        for result in results {
+            let transcript = result.unwrap();
+            // 4 sections with 2 speakers each
+            assert_eq!(transcript.len(), 8);
+            assert_eq!(transcript.last().unwrap().section, "Closing Statements");
+        }
+        assert_eq!(backend.calls(), 40);
+    }
+
+    #[tokio::test]
+    async fn test_run_debates_reports_errors_in_order() {
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        let debates: Vec<(DebateConfig, Vec<AIParticipant>, Box<dyn DebateFormat>)> = vec![(
+            config,
+            mock_participants()[..1].to_vec(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )];
+
+        let results = run_debates(debates, 4).await;
+
+        assert_eq!(results.len(), 1);
+        assert!(matches!(
+            results[0],
+            Err(DebateError::InvalidParticipantCount { actual: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_context_docs_injected_and_truncated() {
+        let doc = "Solar capacity doubled in 2023. ".repeat(20);
+        let participants = vec![
+            AIParticipant::new("Alice", "model-a", ParticipantRole::For)
+                .with_context_docs(vec![doc.clone()]),
+            AIParticipant::new("Bob", "model-b", ParticipantRole::Against),
+        ];
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_context_char_budget(100);
+        let orchestrator = DebateOrchestrator::new(
+            config,
+            participants,
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap();
+
+        let alice = user_messages(&orchestrator.histories[0]);
+        assert_eq!(alice.len(), 1);
+        assert!(alice[0].contains("Solar capacity doubled in 2023."));
+        assert!(alice[0].ends_with("[...truncated]"));
+        assert!(alice[0].len() < doc.len());
+
+        assert!(user_messages(&orchestrator.histories[1]).is_empty());
+    }
+
+    #[test]
+    fn test_shared_context_given_to_all_participants() {
+        let participants = vec![
+            AIParticipant::new("Alice", "model-a", ParticipantRole::For)
+                .with_context_docs(vec!["Alice's notes".to_string()]),
+            AIParticipant::new("Bob", "model-b", ParticipantRole::Against),
+        ];
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "")
+            .with_shared_context(vec!["The 2023 census report.".to_string()]);
+        let orchestrator = DebateOrchestrator::new(
+            config,
+            participants,
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap();
+
+        for history in &orchestrator.histories {
+            // Shared evidence comes directly after the system prompt
+            let messages = user_messages(history);
+            assert!(messages[0].starts_with(SHARED_CONTEXT_HEADER));
+            assert!(messages[0].contains("The 2023 census report."));
+        }
+        assert!(user_messages(&orchestrator.histories[0])[1].contains("Alice's notes"));
+        assert_eq!(user_messages(&orchestrator.histories[1]).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_speaker_is_an_error_in_strict_mode() {
+        let format = || TestFormat {
+            sections: vec![test_section("Opening", vec![0, 1, 5])],
+        };
+        let run = |strict: bool| {
+            let config =
+                DebateConfig::new("Test topic", "http://localhost:1", "").with_strict(strict);
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format()))
+                .unwrap()
+                .with_backend(Arc::new(MockBackend::new()))
+        };
+
+        assert_eq!(run(false).run().await.unwrap().len(), 2);
+        assert!(matches!(
+            run(true).run().await,
+            Err(DebateError::ConfigError(msg)) if msg.contains("speaker 5")
+        ));
+    }
+
+    #[test]
+    fn test_participant_endpoint_override() {
+        let config = DebateConfig::new("Test topic", "https://api.example.com/v1", "default-key");
+        let local = AIParticipant::new("Alice", "llama3", ParticipantRole::For)
+            .with_endpoint("http://localhost:11434/v1");
+        let keyed =
+            AIParticipant::new("Bob", "gpt-4o", ParticipantRole::Against).with_api_key("bob-key");
+        let plain = AIParticipant::new("Carol", "gpt-4o", ParticipantRole::Against);
+
+        assert_eq!(
+            endpoint_override(&config, &local),
+            Some(("http://localhost:11434/v1", "default-key"))
+        );
+        assert_eq!(
+            endpoint_override(&config, &keyed),
+            Some(("https://api.example.com/v1", "bob-key"))
+        );
+        assert_eq!(endpoint_override(&config, &plain), None);
+    }
+
+    #[tokio::test]
+    async fn test_prompt_dump_precedes_each_speaker_message() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_debug_prompts(true);
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(Arc::new(MockBackend::new()))
+        .with_callback(Box::new(move |event| recorded.lock().unwrap().push(event)));
+
+        let transcript = orchestrator.run().await.unwrap();
+        let events = events.lock().unwrap();
+
+        let mut dumps = 0;
+        for (i, event) in events.iter().enumerate() {
+            if let DebateEvent::SpeakerMessage { .. } = event {
+                // The most recent speaker-specific event is this turn's dump
+                let dump = events[..i].iter().rev().find_map(|e| match e {
+                    DebateEvent::PromptDump {
+                        speaker_index,
+                        messages_json,
+                    } => Some((*speaker_index, messages_json)),
+                    _ => None,
+                });
+                let (speaker_index, messages_json) = dump.expect("prompt dump before message");
+                assert_eq!(Some(speaker_index), transcript[dumps].speaker_index);
+                assert!(messages_json.contains("Test topic"));
+                dumps += 1;
+            }
+        }
+        assert_eq!(dumps, transcript.len());
+
+        let total_dumps = events
+            .iter()
+            .filter(|e| matches!(e, DebateEvent::PromptDump { .. }))
+            .count();
+        assert_eq!(total_dumps, transcript.len());
+    }
+
+    #[tokio::test]
+    async fn test_judge_scores_the_debate() {
+        let reply = r#"{"Alice": {"persuasiveness": 6, "evidence": 6, "rebuttal": 6, "clarity": 6},
+            "Bob": {"persuasiveness": 9, "evidence": 7, "rebuttal": 8, "clarity": 8}}"#;
+        let backend = Arc::new(MockBackend::new().with_response(reply));
+        let mut orchestrator = mock_orchestrator(backend.clone());
+        orchestrator.run().await.unwrap();
+        let calls = backend.calls();
+
+        let verdict = orchestrator.judge(&JudgeConfig::default()).await.unwrap();
+        assert_eq!(backend.calls(), calls + 1);
+        assert_eq!(verdict.winner().map(|s| s.total), Some(8.0));
+        assert_eq!(verdict.winner().map(|s| s.name.as_str()), Some("Bob"));
+        assert_eq!(verdict.winner().map(|s| s.index), Some(1));
+
+        let names = ["Alice".to_string(), "Bob".to_string()];
+        let judge = JudgeConfig {
+            model: Some("judge-model".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            orchestrator.judge_request(&judge, &names).model,
+            "judge-model"
+        );
+        assert_eq!(
+            orchestrator
+                .judge_request(&JudgeConfig::default(), &names)
+                .model,
+            "model-a"
+        );
+    }
+
+    #[test]
+    fn test_json_mode_reaches_judge_request() {
+        let names = ["Alice".to_string(), "Bob".to_string()];
+        let judge = JudgeConfig::default();
+        let orchestrator = mock_orchestrator(Arc::new(MockBackend::new()));
+        assert!(!orchestrator.judge_request(&judge, &names).json_mode);
+
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "").with_json_mode(true);
+        let orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap();
+        let request = orchestrator.judge_request(&judge, &names);
+        assert!(request.json_mode);
+        // JSON mode requires the prompt to mention JSON
+        assert!(
+            request
+                .messages
+                .iter()
+                .any(|m| serde_json::to_string(m).unwrap().contains("JSON"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancel_flag_stops_after_first_turn() {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = cancel.clone();
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let mut orchestrator = mock_orchestrator(Arc::new(MockBackend::new()))
+            .with_cancel_flag(cancel)
+            .with_callback(Box::new(move |event| {
+                if let DebateEvent::SpeakerMessage { .. } = event {
+                    flag.store(true, Ordering::SeqCst);
+                }
+                recorded.lock().unwrap().push(event);
+            }));
+
+        let transcript = orchestrator.run().await.unwrap();
+        assert_eq!(transcript.len(), 1);
+        assert_eq!(transcript[0].speaker_index, Some(0));
+
+        let events = events.lock().unwrap();
+        let starts = events
+            .iter()
+            .filter(|e| matches!(e, DebateEvent::SpeakerStart { .. }))
+            .count();
+        assert_eq!(starts, 1);
+        assert!(matches!(events.last(), Some(DebateEvent::DebateEnd)));
+    }
+
+    #[tokio::test]
+    async fn test_no_prompt_dump_by_default() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let mut orchestrator = mock_orchestrator(Arc::new(MockBackend::new()))
+            .with_callback(Box::new(move |event| recorded.lock().unwrap().push(event)));
+
+        orchestrator.run().await.unwrap();
+        assert!(
+            !events
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|e| matches!(e, DebateEvent::PromptDump { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_response_hook_transforms_transcript() {
+        let mut orchestrator = mock_orchestrator(Arc::new(MockBackend::new()))
+            .with_response_hook(Box::new(|text| text.to_uppercase()));
+
+        let transcript = orchestrator.run().await.unwrap();
+        assert!(!transcript.is_empty());
+        for message in &transcript {
+            assert_eq!(message.content, message.content.to_uppercase());
+            assert!(message.content.contains("MOCK RESPONSE"));
+        }
+    }
+
+    #[test]
+    fn test_duplicate_participant_names_rejected() {
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        let participants = vec![
+            AIParticipant::new("Candidate A", "model-a", ParticipantRole::For),
+            AIParticipant::new("Candidate A", "model-b", ParticipantRole::Against),
+        ];
+
+        let result = DebateOrchestrator::new(
+            config,
+            participants,
+            Box::new(PresidentialDebateFormat::new(4)),
+        );
+        assert!(matches!(
+            result,
+            Err(DebateError::DuplicateParticipantName(name)) if name == "Candidate A"
+        ));
+    }
+
+    #[test]
+    fn test_response_similarity() {
+        let a = "We must invest in renewable energy to secure our future.";
+        assert_eq!(response_similarity(a, a), 1.0);
+        assert!(
+            response_similarity(
+                a,
+                "We must invest in renewable energy to secure our future!"
+            ) > 0.9
+        );
+        assert!(response_similarity(a, "Taxes on small businesses are far too high today.") < 0.1);
+    }
+
+    #[test]
+    fn test_replay_transcript_events() {
+        let message = |section: &str, speaker_index: usize, name: &str| DebateMessage {
+            section: section.to_string(),
+            speaker_index: Some(speaker_index),
+            speaker_name: name.to_string(),
+            content: format!("{} speaks.", name),
+            raw_content: None,
+        };
+        let messages = vec![
+            message("Opening", 0, "Alice"),
+            message("Opening", 1, "Bob"),
+            message("Closing", 1, "Bob"),
+        ];
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let callback: DebateCallback = Box::new(move |event| {
+            sink.lock()
+                .unwrap()
+                .push(serde_json::to_string(&event).unwrap());
+        });
+
+        replay_transcript(&messages, &mock_participants(), &callback);
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            *events,
+            [
+                r#"{"type":"section_start","name":"Opening","description":""}"#,
+                r#"{"type":"speaker_start","name":"Alice","role":"FOR"}"#,
+                r#"{"type":"speaker_message","name":"Alice","content":"Alice speaks."}"#,
+                r#"{"type":"speaker_start","name":"Bob","role":"AGAINST"}"#,
+                r#"{"type":"speaker_message","name":"Bob","content":"Bob speaks."}"#,
+                r#"{"type":"section_start","name":"Closing","description":""}"#,
+                r#"{"type":"speaker_start","name":"Bob","role":"AGAINST"}"#,
+                r#"{"type":"speaker_message","name":"Bob","content":"Bob speaks."}"#,
+                r#"{"type":"debate_end"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_section() {
+        let sections = PresidentialDebateFormat::new(4).sections();
+        let message = |section: &DebateSection, speaker_index: usize| DebateMessage {
+            section: section.name.clone(),
+            speaker_index: Some(speaker_index),
+            speaker_name: format!("Speaker {}", speaker_index),
+            content: "Text".to_string(),
+            raw_content: None,
+        };
+        let messages = vec![
+            message(&sections[0], 0),
+            message(&sections[0], 1),
+            message(&sections[1], 1),
+            message(&sections[3], 1),
+            message(&sections[3], 0),
+        ];
+
+        let groups = group_by_section(&messages, &sections);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            groups.iter().map(|g| g.order).collect::<Vec<_>>(),
+            [0, 1, 3]
+        );
+        assert_eq!(groups[0].name, sections[0].name);
+        assert_eq!(groups[2].description, sections[3].description);
+        assert_eq!(
+            groups[2]
+                .messages
+                .iter()
+                .map(|m| m.speaker_index)
+                .collect::<Vec<_>>(),
+            [Some(1), Some(0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_grouped_nests_every_message() {
+        let mut orchestrator = mock_orchestrator(Arc::new(MockBackend::new()));
+
+        let groups = orchestrator.run_grouped().await.unwrap();
+
+        assert_eq!(groups.len(), 4);
+        assert!(groups.iter().all(|g| g.messages.len() == 2));
+    }
+
+    #[test]
+    fn test_normalize_topic() {
+        assert!(normalize_topic("").is_err());
+        assert!(normalize_topic(" \t\n ").is_err());
+        assert_eq!(
+            normalize_topic("  Should   cities\nban cars?  ").unwrap(),
+            "Should cities ban cars?"
+        );
+
+        let long = "word ".repeat(100);
+        let topic = normalize_topic(&long).unwrap();
+        assert!(topic.ends_with("word..."));
+        assert!(topic.chars().count() <= MAX_TOPIC_CHARS + 3);
+    }
+
+    #[tokio::test]
+    async fn test_empty_retries_are_configurable() {
+        for retries in [0, 1] {
+            let backend = Arc::new(MockBackend::new().with_response(""));
+            let config = DebateConfig::new("Test topic", "http://localhost:1", "")
+                .with_empty_retries(retries);
+            let mut orchestrator = DebateOrchestrator::new(
+                config,
+                mock_participants(),
+                Box::new(PresidentialDebateFormat::new(4)),
+            )
+            .unwrap()
+            .with_backend(backend.clone());
+
+            assert!(matches!(
+                orchestrator.run().await,
+                Err(DebateError::EmptyResponse(_))
+            ));
+            assert_eq!(backend.calls(), retries as usize + 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_participant_empty_retry_override() {
+        let backend = Arc::new(MockBackend::new().with_response(""));
+        let participants = vec![
+            AIParticipant::new("Alice", "model-a", ParticipantRole::For).with_empty_retries(1),
+            AIParticipant::new("Bob", "model-b", ParticipantRole::Against),
+        ];
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_empty_retries(0);
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            participants,
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(backend.clone());
+
+        // Alice opens, and gets one retry where the debate default allows none
+        assert!(orchestrator.run().await.is_err());
+        assert_eq!(backend.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_is_not_recorded() {
+        let backend = Arc::new(MockBackend::new());
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "").with_warmup(true);
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(backend.clone());
+
+        let transcript = orchestrator.run().await.unwrap();
+
+        // One warm-up per participant plus 8 debate turns
+        assert_eq!(backend.calls(), 10);
+        assert_eq!(transcript.len(), 8);
+        for history in &orchestrator.histories {
+            assert!(!user_messages(history).iter().any(|m| m == WARMUP_PROMPT));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repetitive_response_triggers_reminder() {
+        let backend =
+            Arc::new(MockBackend::new().with_response("I will say the same thing every time."));
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "")
+            .with_repetition_threshold(0.8);
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(backend.clone());
+
+        let transcript = orchestrator.run().await.unwrap();
+
+        // Each speaker's first turn is fresh; the other three are re-prompted once
+        assert_eq!(transcript.len(), 8);
+        assert_eq!(backend.calls(), 8 + 6);
+
+        let reminders = user_messages(&orchestrator.histories[0])
+            .iter()
+            .filter(|m| m.contains("repeated an argument"))
+            .count();
+        assert_eq!(reminders, 3);
+    }
+
+    #[test]
+    fn test_is_refusal() {
+        assert!(is_refusal(
+            "As an AI, I don't have personal opinions, so I can't take a side."
+        ));
+        assert!(is_refusal(
+            "I\u{2019}m sorry, but I cannot take sides on this."
+        ));
+        assert!(is_refusal(
+            "  I can't argue either side. Here are both views."
+        ));
+
+        // Concessions and the same words later in a turn are arguments
+        assert!(!is_refusal(
+            "I can't argue with that statistic, but it ignores the cost."
+        ));
+        assert!(!is_refusal(
+            "I don't have personal opinions about my opponent's tie, only their plan."
+        ));
+        assert!(!is_refusal(
+            "Cars pollute. I can't take sides with polluters, and neither should you."
+        ));
+        assert!(!is_refusal(
+            "My opponent's plan fails.\nAs an AI, I don't have personal opinions, they said."
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_refusal_reprompts_or_fails_fast() {
+        let refusal = "As an AI, I don't have personal opinions, so I can't take a side.";
+
+        let backend = Arc::new(MockBackend::new().with_response(refusal));
+        let mut orchestrator = mock_orchestrator(backend.clone());
+        let transcript = orchestrator.run().await.unwrap();
+        // Every turn refuses and is re-prompted once
+        assert_eq!(transcript.len(), 8);
+        assert_eq!(backend.calls(), 16);
+        assert!(
+            user_messages(&orchestrator.histories[0])
+                .iter()
+                .any(|m| m == REFUSAL_REMINDER)
+        );
+
+        let backend = Arc::new(MockBackend::new().with_response(refusal));
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "")
+            .with_fail_fast_on_refusal(true);
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(backend.clone());
+        let result = orchestrator.run().await;
+        assert!(matches!(
+            result,
+            Err(DebateError::Refusal { participant }) if participant == "Alice"
+        ));
+        assert_eq!(backend.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_history_window_keeps_opening_statements() {
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_history_window(3);
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(8)),
+        )
+        .unwrap()
+        .with_backend(Arc::new(MockBackend::new()));
+        let transcript = orchestrator.run().await.unwrap();
+        assert_eq!(transcript.len(), 16);
+
+        for (idx, history) in orchestrator.histories.iter().enumerate() {
+            let text = serde_json::to_string(history).unwrap();
+            // Both opening statements survive; the speaker's own turn in a
+            // middle round (not quoted in the closing digest) does not
+            assert!(text.contains(&transcript[0].content));
+            assert!(text.contains(&transcript[1].content));
+            let middle = transcript
+                .iter()
+                .find(|m| m.section.ends_with("Round 3") && m.speaker_index == Some(idx))
+                .unwrap();
+            assert!(!text.contains(&middle.content));
+            // Pinned start (system prompt + opening section) plus the window,
+            // plus what arrived after the speaker's last turn
+            assert!(history.len() <= 4 + 3 + 2);
+        }
+
+        // Without the opening statements, only the system prompt is pinned
+        let format = FilteredFormat::new(
+            Box::new(PresidentialDebateFormat::new(8)),
+            &[],
+            &["Opening Statements".to_string()],
+        )
+        .unwrap();
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_history_window(3);
+        let mut orchestrator =
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format))
+                .unwrap()
+                .with_backend(Arc::new(MockBackend::new()));
+        orchestrator.run().await.unwrap();
+        for pinned in &orchestrator.pinned {
+            assert_eq!(pinned.len(), 1);
+            assert_eq!(pinned[0], 0..1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_window_keeps_only_pinned_sections() {
+        let mut key = test_section("Key Question", vec![0, 1]);
+        key.pinned = true;
+        let format = TestFormat {
+            sections: vec![
+                test_section("Warm Up", vec![0, 1]),
+                key,
+                test_section("Round 1", vec![0, 1]),
+                test_section("Round 2", vec![0, 1]),
+                test_section("Round 3", vec![0, 1]),
+            ],
+        };
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_history_window(2);
+        let mut orchestrator =
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format))
+                .unwrap()
+                .with_backend(Arc::new(MockBackend::new()));
+        let transcript = orchestrator.run().await.unwrap();
+
+        let text = serde_json::to_string(&orchestrator.histories[0]).unwrap();
+        // The unpinned section before the pinned one is trimmed away
+        assert!(!text.contains(&transcript[0].content));
+        assert!(!text.contains(&transcript[1].content));
+        // The pinned section survives
+        assert!(text.contains(&transcript[2].content));
+        assert!(text.contains(&transcript[3].content));
+        assert_eq!(orchestrator.pinned[0].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_opponent_message_template() {
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "")
+            .with_opponent_message_template("<{name}> {content}");
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(Arc::new(MockBackend::new()));
+        let transcript = orchestrator.run().await.unwrap();
+
+        let injected = user_messages(&orchestrator.histories[1]);
+        assert!(injected.contains(&format!("<Alice> {}", transcript[0].content)));
+        assert!(!injected.iter().any(|m| m.starts_with("[Opponent")));
+    }
+
+    #[tokio::test]
+    async fn test_repetition_check_disabled_by_default() {
+        let backend =
+            Arc::new(MockBackend::new().with_response("I will say the same thing every time."));
+        let mut orchestrator = mock_orchestrator(backend.clone());
+
+        orchestrator.run().await.unwrap();
+
+        assert_eq!(backend.calls(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_per_speaker_prompts_reach_the_right_speaker() {
+        let mut section = test_section("Plan", vec![0, 1]);
+        section
+            .per_speaker_prompts
+            .insert(0, "Present your plan.".to_string());
+        section
+            .per_speaker_prompts
+            .insert(1, "Attack the plan.".to_string());
+        let format = TestFormat {
+            sections: vec![section],
+        };
+
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        let mut orchestrator =
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format))
+                .unwrap()
+                .with_backend(Arc::new(MockBackend::new()));
+        orchestrator.run().await.unwrap();
+
+        let first = user_messages(&orchestrator.histories[0]);
+        let second = user_messages(&orchestrator.histories[1]);
+        assert!(first[0].ends_with("Present your plan."));
+        assert!(!first.iter().any(|m| m.contains("Attack the plan.")));
+        // Speaker 1 sees speaker 0's statement first, then their own prompt
+        assert!(second[1].ends_with("Attack the plan."));
+        assert!(!second.iter().any(|m| m.contains("Present your plan.")));
+    }
+
+    #[tokio::test]
+    async fn test_consecutive_turns_by_same_speaker() {
+        let format = TestFormat {
+            sections: vec![test_section("Opening", vec![0, 0, 1])],
+        };
+
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        let mut orchestrator =
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format))
+                .unwrap()
+                .with_backend(Arc::new(MockBackend::new()));
+        orchestrator.run().await.unwrap();
+
+        let kinds = |history: &[ChatCompletionRequestMessage]| -> Vec<&str> {
+            history
+                .iter()
+                .map(|m| match m {
+                    ChatCompletionRequestMessage::System(_) => "system",
+                    ChatCompletionRequestMessage::User(u) if u.name.is_some() => "opponent",
+                    ChatCompletionRequestMessage::User(_) => "prompt",
+                    ChatCompletionRequestMessage::Assistant(_) => "assistant",
+                    _ => "other",
+                })
+                .collect()
+        };
+
+        // Alice's first turn is her own context for the second, not an opponent's
+        assert_eq!(
+            kinds(&orchestrator.histories[0]),
+            [
+                "system",
+                "prompt",
+                "assistant",
+                "prompt",
+                "assistant",
+                "opponent"
+            ]
+        );
+        let alice = user_messages(&orchestrator.histories[0]);
+        assert!(!alice.iter().any(|m| m.contains("[Opponent Alice said]")));
+        assert!(alice[2].contains("[Opponent Bob said]: This is mock response number 3"));
+
+        // Bob sees both of Alice's turns, in order, before his own prompt
+        assert_eq!(
+            kinds(&orchestrator.histories[1]),
+            ["system", "opponent", "opponent", "prompt", "assistant"]
+        );
+        let bob = user_messages(&orchestrator.histories[1]);
+        assert!(bob[0].contains("[Opponent Alice said]: This is mock response number 1"));
+        assert!(bob[1].contains("[Opponent Alice said]: This is mock response number 2"));
+        assert!(!bob.iter().any(|m| m.contains("[Opponent Bob said]")));
+    }
+
+    #[tokio::test]
+    async fn test_closing_prompt_summarizes_opponent() {
+        let mut closing = test_section("Closing", vec![0, 1]);
+        closing.summarize_opponent = true;
+        let format = TestFormat {
+            sections: vec![test_section("Opening", vec![0, 1]), closing],
+        };
+
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        let mut orchestrator =
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format))
+                .unwrap()
+                .with_backend(Arc::new(MockBackend::new()));
+        orchestrator.run().await.unwrap();
+
+        let alice = user_messages(&orchestrator.histories[0]);
+        let closing_prompt = alice.iter().find(|m| m.starts_with("[Closing")).unwrap();
+        assert!(
+            closing_prompt
+                .contains("- Bob (Opening): This is mock response number 2 from model-b.")
+        );
+        assert!(!closing_prompt.contains("from model-a"));
+
+        // Bob's closing also covers Alice's closing, which came just before
+        let bob = user_messages(&orchestrator.histories[1]);
+        let closing_prompt = bob.iter().find(|m| m.starts_with("[Closing")).unwrap();
+        assert!(closing_prompt.contains("- Alice (Opening): This is mock response number 1"));
+        assert!(closing_prompt.contains("- Alice (Closing): This is mock response number 3"));
+
+        // Sections without the flag get no digest
+        assert!(!alice[0].contains("arguments so far"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_turn_delay_applied_only_when_set() {
+        let delay = Duration::from_millis(500);
+        for turn_delay in [None, Some(delay)] {
+            let mut config = DebateConfig::new("Test topic", "http://localhost:1", "");
+            if let Some(delay) = turn_delay {
+                config = config.with_turn_delay(delay);
+            }
+            let mut orchestrator = DebateOrchestrator::new(
+                config,
+                mock_participants(),
+                Box::new(PresidentialDebateFormat::new(4)),
+            )
+            .unwrap()
+            .with_backend(Arc::new(MockBackend::new()));
+
+            // The clock is paused, so only the delays advance it
+            let started = tokio::time::Instant::now();
+            orchestrator.run().await.unwrap();
+            let expected = turn_delay.map_or(Duration::ZERO, |delay| delay * 8);
+            assert_eq!(started.elapsed(), expected);
+        }
+    }
+
+    #[test]
+    fn test_plan_resolves_speaker_names() {
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        let orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(6)),
+        )
+        .unwrap();
+        let plan = orchestrator.plan();
+
+        assert_eq!(plan.topic, "Test topic");
+        assert_eq!(plan.participants[1].role, "AGAINST");
+        assert_eq!(plan.participants[1].model, "model-b");
+
+        let sections = PresidentialDebateFormat::new(6).sections();
+        assert_eq!(plan.sections.len(), sections.len());
+        for (planned, section) in plan.sections.iter().zip(&sections) {
+            assert_eq!(planned.name, section.name);
+            let expected: Vec<&str> = section
+                .speaker_order
+                .iter()
+                .map(|&i| orchestrator.participants()[i].name.as_str())
+                .collect();
+            assert_eq!(planned.speakers, expected);
+        }
+        assert_eq!(plan.sections.last().unwrap().speakers, ["Alice", "Bob"]);
+
+        let json = serde_json::to_value(&plan).unwrap();
+        assert_eq!(json["sections"][0]["speakers"][0], "Alice");
+    }
+
+    #[tokio::test]
+    async fn test_zero_token_budget_errors_before_calling_api() {
+        let mut section = test_section("Opening", vec![0, 1]);
+        section.max_tokens = 0;
+        let format = TestFormat {
+            sections: vec![section],
+        };
+
+        let backend = Arc::new(MockBackend::new());
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        let mut orchestrator =
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format))
+                .unwrap()
+                .with_backend(backend.clone());
+
+        let result = orchestrator.run().await;
+        assert!(matches!(result, Err(DebateError::ConfigError(m)) if m.contains("Opening")));
+        assert_eq!(backend.calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_section_model_override() {
+        let mut closing = test_section("Closing", vec![0, 1]);
+        closing.model_override = Some("strong-model".to_string());
+        closing.per_speaker_models.insert(1, "strong-b".to_string());
+        let format = TestFormat {
+            sections: vec![test_section("Opening", vec![0, 1]), closing],
+        };
+
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        let mut orchestrator =
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format))
+                .unwrap()
+                .with_backend(Arc::new(MockBackend::new()));
+        let transcript = orchestrator.run().await.unwrap();
+
+        // The mock names the requested model in each response
+        assert!(transcript[0].content.contains(" from model-a."));
+        assert!(transcript[1].content.contains(" from model-b."));
+        assert!(transcript[2].content.contains(" from strong-model."));
+        assert!(transcript[3].content.contains(" from strong-b."));
+    }
+
+    #[tokio::test]
+    async fn test_truncated_response_requests_continuation() {
+        let backend = Arc::new(
+            MockBackend::new()
+                .with_response("We must act now")
+                .with_finish_reason(async_openai::types::chat::FinishReason::Length),
+        );
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_finish_truncated(true);
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(backend.clone());
+
+        let transcript = orchestrator.run().await.unwrap();
+
+        // One continuation per turn, appended to the cut-off response
+        assert_eq!(backend.calls(), 16);
+        assert_eq!(transcript[0].content, "We must act now We must act now");
+    }
+
+    #[tokio::test]
+    async fn test_keep_raw_retains_stripped_tags() {
+        let raw = "<think>Plan the attack first.</think>We must act on this now.";
+        for keep_raw in [false, true] {
+            let config =
+                DebateConfig::new("Test topic", "http://localhost:1", "").with_keep_raw(keep_raw);
+            let mut orchestrator = DebateOrchestrator::new(
+                config,
+                mock_participants(),
+                Box::new(PresidentialDebateFormat::new(4)),
+            )
+            .unwrap()
+            .with_backend(Arc::new(MockBackend::new().with_response(raw)));
+
+            let transcript = orchestrator.run().await.unwrap();
+            assert_eq!(transcript[0].content, "We must act on this now.");
+
+            let json = crate::export::render_json(&transcript).unwrap();
+            if keep_raw {
+                assert_eq!(transcript[0].raw_content.as_deref(), Some(raw));
+                assert!(json.contains("\"raw_content\": \"<think>Plan the attack first.</think>"));
+            } else {
+                assert!(transcript.iter().all(|m| m.raw_content.is_none()));
+                assert!(!json.contains("raw_content"));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_halftime_recap_at_midpoint() {
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_halftime_recap(true);
+        let format = PresidentialDebateFormat::new(4);
+        let sections = format.sections();
+        let mut orchestrator =
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format))
+                .unwrap()
+                .with_backend(Arc::new(MockBackend::new()));
+
+        let transcript = orchestrator.run().await.unwrap();
+
+        // Two of the four sections (four turns) come before the recap
+        assert_eq!(transcript.len(), 9);
+        let recaps: Vec<usize> = (0..transcript.len())
+            .filter(|&i| transcript[i].is_announcer())
+            .collect();
+        assert_eq!(recaps, [4]);
+        let recap = &transcript[4];
+        assert_eq!(recap.speaker_name, ANNOUNCER_NAME);
+        assert_eq!(recap.section, sections[1].name);
+        assert_eq!(
+            recap.content,
+            "This is mock response number 5 from model-a."
+        );
+
+        // Participants never see the recap
+        for history in &orchestrator.histories {
+            assert!(
+                !user_messages(history)
+                    .iter()
+                    .any(|m| m.contains("number 5 "))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_moderator_opens_each_section() {
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "")
+            .with_moderator_prompt("Moderate {topic}: {section_name}");
+        let format = PresidentialDebateFormat::new(4);
+        let sections = format.sections();
+        let mut orchestrator =
+            DebateOrchestrator::new(config, mock_participants(), Box::new(format))
+                .unwrap()
+                .with_backend(Arc::new(MockBackend::new()));
+
+        let transcript = orchestrator.run().await.unwrap();
+
+        // One question before the two turns of each of the four sections
+        assert_eq!(transcript.len(), 12);
+        for (i, section) in sections.iter().enumerate() {
+            let question = &transcript[i * 3];
+            assert!(question.is_announcer());
+            assert_eq!(question.speaker_name, MODERATOR_NAME);
+            assert_eq!(question.section, section.name);
+        }
+
+        // Both participants are asked the first question
+        for history in &orchestrator.histories {
+            assert!(
+                user_messages(history)
+                    .iter()
+                    .any(|m| m == "[Moderator asks]: This is mock response number 1 from model-a.")
+            );
+        }
+    }
+
+    /// Backend whose first reply per turn is cut off by the token limit.
+    struct CutoffBackend {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl CompletionBackend for CutoffBackend {
+        async fn complete(
+            &self,
+            request: CompletionRequest,
+        ) -> Result<CompletionResponse, DebateError> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let continued = user_messages(&request.messages)
+                .last()
+                .is_some_and(|m| m == CONTINUE_PROMPT);
+            Ok(if continued {
+                CompletionResponse {
+                    content: "now, before it is too late.".to_string(),
+                    finish_reason: Some(async_openai::types::chat::FinishReason::Stop),
+                }
+            } else {
+                CompletionResponse {
+                    content: format!("Turn {}: we must act", call),
+                    finish_reason: Some(async_openai::types::chat::FinishReason::Length),
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cut_off_response_is_continued() {
+        let backend = Arc::new(CutoffBackend {
+            calls: Default::default(),
+        });
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_max_continuations(3);
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(backend.clone());
+
+        let transcript = orchestrator.run().await.unwrap();
+
+        assert_eq!(transcript.len(), 8);
+        assert_eq!(
+            transcript[0].content,
+            "Turn 0: we must act now, before it is too late."
+        );
+        // The continuation prompt is not kept in the speaker's history
+        assert!(
+            !user_messages(&orchestrator.histories[0])
+                .iter()
+                .any(|m| m == CONTINUE_PROMPT)
+        );
+
+        // Continuations stop at the configured maximum
+        let backend = Arc::new(
+            MockBackend::new()
+                .with_response("and more")
+                .with_finish_reason(async_openai::types::chat::FinishReason::Length),
+        );
+        let config =
+            DebateConfig::new("Test topic", "http://localhost:1", "").with_max_continuations(2);
+        let mut orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap()
+        .with_backend(backend.clone());
+        let transcript = orchestrator.run().await.unwrap();
+        assert_eq!(backend.calls(), 8 * 3);
+        assert_eq!(transcript[0].content, "and more and more and more");
+    }
+
+    #[tokio::test]
+    async fn test_truncated_response_kept_when_disabled() {
+        let backend = Arc::new(
+            MockBackend::new()
+                .with_response("We must act now")
+                .with_finish_reason(async_openai::types::chat::FinishReason::Length),
+        );
+        let mut orchestrator = mock_orchestrator(backend.clone());
+
+        let transcript = orchestrator.run().await.unwrap();
+
+        assert_eq!(backend.calls(), 8);
+        assert_eq!(transcript[0].content, "We must act now");
+    }
+
+    #[test]
+    fn test_persona_only_in_intended_system_prompt() {
+        let participants = vec![
+            AIParticipant::new("Alice", "model-a", ParticipantRole::For)
+                .with_persona("a fiscal conservative economist"),
+            AIParticipant::new("Bob", "model-b", ParticipantRole::Against),
+        ];
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "");
+        let orchestrator = DebateOrchestrator::new(
+            config,
+            participants,
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap();
+
+        assert!(
+            system_message(&orchestrator.histories[0]).contains("a fiscal conservative economist")
+        );
+        assert!(!system_message(&orchestrator.histories[1]).contains("PERSONA"));
+    }
+
+    #[test]
+    fn test_rules_in_every_system_prompt_once() {
+        let rules = "No ad hominem. Cite sources.";
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "").with_rules(rules);
+        let orchestrator = DebateOrchestrator::new(
+            config,
+            mock_participants(),
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap();
+
+        for prompt in orchestrator.system_prompts() {
+            assert_eq!(prompt.matches(rules).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_debate_date_substituted_into_system_prompt() {
+        let participants = vec![
+            AIParticipant::new("Alice", "model-a", ParticipantRole::For)
+                .with_system_prompt("Argue as of {date}."),
+            AIParticipant::new("Bob", "model-b", ParticipantRole::Against),
+        ];
+        let config = DebateConfig::new("Test topic", "http://localhost:1", "")
+            .with_debate_date("2024-06-01");
+        let orchestrator = DebateOrchestrator::new(
+            config,
+            participants,
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap();
+
+        let prompts = orchestrator.system_prompts();
+        assert!(prompts[0].starts_with("Argue as of 2024-06-01.\n\nTODAY'S DATE: 2024-06-01."));
+        assert!(prompts[1].contains("TODAY'S DATE: 2024-06-01."));
+        assert!(!prompts.iter().any(|p| p.contains("{date}")));
+    }
+
+    #[test]
+    fn test_system_prompts_one_per_participant() {
+        let participants = vec![
+            AIParticipant::new("Alice", "model-a", ParticipantRole::For),
+            AIParticipant::new("Bob", "model-b", ParticipantRole::Against),
+        ];
+        let config = DebateConfig::new("Ban cars downtown", "http://localhost:1", "");
+        let orchestrator = DebateOrchestrator::new(
+            config,
+            participants,
+            Box::new(PresidentialDebateFormat::new(4)),
+        )
+        .unwrap();
+
+        let prompts = orchestrator.system_prompts();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts.iter().all(|p| p.contains("Ban cars downtown")));
+        assert_eq!(prompts[0], system_message(&orchestrator.histories[0]));
+    }
+
+    #[test]
+    fn test_message_author_name() {
+        assert_eq!(message_author_name("Alice"), "Alice");
+        assert_eq!(message_author_name("Candidate A"), "Candidate_A");
+        assert_eq!(message_author_name("Dr. O'Neil-Smith"), "Dr__O_Neil-Smith");
+        assert_eq!(message_author_name(&"x".repeat(100)).len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_opponent_messages_carry_speaker_name() {
+        let mut orchestrator = mock_orchestrator(Arc::new(MockBackend::new()));
+        orchestrator.run().await.unwrap();
+
+        for (idx, opponent) in [(0, "Bob"), (1, "Alice")] {
+            let injected: Vec<_> = orchestrator.histories[idx]
+                .iter()
+                .filter_map(|m| match m {
+                    ChatCompletionRequestMessage::User(u) if u.name.is_some() => u.name.clone(),
+                    _ => None,
+                })
+                .collect();
+            // One injected statement per opponent turn, all attributed to the opponent
+            assert_eq!(injected.len(), 4);
+            assert!(injected.iter().all(|name| name == opponent));
+        }
+    }
 
     #[test]
     fn test_sanitize_response_thinking_tags() {
         let input = "<thinking>Let me think about this...</thinking>The answer is 42.";
-        let output = sanitize_response(input);
+        let output = sanitize_response(input, &SanitizeConfig::default());
         assert_eq!(output, "The answer is 42.");
     }
 
     #[test]
     fn test_sanitize_response_reflection_tags() {
         let input = "Hello <reflection>internal thought</reflection> world!";
-        let output = sanitize_response(input);
+        let output = sanitize_response(input, &SanitizeConfig::default());
         assert_eq!(output, "Hello world!");
     }
 
     #[test]
     fn test_sanitize_response_no_tags() {
         let input = "No tags here, just text.";
-        let output = sanitize_response(input);
+        let output = sanitize_response(input, &SanitizeConfig::default());
         assert_eq!(output, "No tags here, just text.");
     }
 
     #[test]
     fn test_sanitize_response_multiline_tags() {
         let input = "<thinking>\nMultiple\nlines\nof\nthought\n</thinking>Final answer here.";
-        let output = sanitize_response(input);
+        let output = sanitize_response(input, &SanitizeConfig::default());
         assert_eq!(output, "Final answer here.");
     }
 
     #[test]
     fn test_sanitize_response_nested_content() {
         let input = "Start <think>nested <inner>tags</inner> content</think> end";
-        let output = sanitize_response(input);
+        let output = sanitize_response(input, &SanitizeConfig::default());
         // After stripping <think> and orphan tags, should get clean result
         assert!(!output.contains("<"));
         assert!(!output.contains(">"));
     }
 
+    #[test]
+    fn test_sanitize_response_keeps_comparisons() {
+        assert_eq!(
+            sanitize_response("5 < 10 and 10 > 5", &SanitizeConfig::default()),
+            "5 < 10 and 10 > 5"
+        );
+        assert_eq!(
+            sanitize_response("If x<y and y>z then x<z.", &SanitizeConfig::default()),
+            "If x<y and y>z then x<z."
+        );
+    }
+
+    #[test]
+    fn test_sanitize_response_removes_stray_markup() {
+        assert_eq!(
+            sanitize_response("Hello <div>world</div>", &SanitizeConfig::default()),
+            "Hello world"
+        );
+        assert_eq!(
+            sanitize_response(
+                r#"A <span class="x">styled</span> word<br/>"#,
+                &SanitizeConfig::default()
+            ),
+            "A styled word"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_response_markdown_stripping() {
+        let conservative = &SanitizeConfig::default();
+        assert_eq!(sanitize_response("3 * 4 = 12", conservative), "3 * 4 = 12");
+        assert_eq!(sanitize_response("*emphasis*", conservative), "emphasis");
+        assert_eq!(
+            sanitize_response("This is **vital**, and *so* is this.", conservative),
+            "This is vital, and so is this."
+        );
+        assert_eq!(sanitize_response("Footnote*", conservative), "Footnote*");
+        assert_eq!(sanitize_response("*a* *b* *c*", conservative), "a b c");
+        assert_eq!(
+            sanitize_response("**one** **two**, *three*", conservative),
+            "one two, three"
+        );
+
+        assert_eq!(
+            sanitize_response(
+                "3 * 4 = 12",
+                &SanitizeConfig {
+                    markdown_stripping: MarkdownStripping::Aggressive,
+                    ..SanitizeConfig::default()
+                }
+            ),
+            "3 4 = 12"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_presets() {
+        let input = "<thinking>plan it</thinking><note>draft</note> As an AI, I have no views. \
+                     We **must** act: 3 * 4 = 12.\n\n<b>Now</b>.";
+
+        assert_eq!(
+            sanitize_response(input, &SanitizeConfig::strict()),
+            "We must act: 3 4 = 12. Now."
+        );
+        assert_eq!(
+            sanitize_response(input, &SanitizeConfig::lenient()),
+            "draft As an AI, I have no views. We must act: 3 * 4 = 12. Now."
+        );
+        assert_eq!(sanitize_response(input, &SanitizeConfig::none()), input);
+
+        assert_eq!(SanitizeConfig::default(), SanitizeConfig::lenient());
+        for name in SanitizeConfig::PRESETS {
+            assert!(SanitizeConfig::preset(name).is_some());
+        }
+        assert!(SanitizeConfig::preset("raw").is_none());
+    }
+
+    #[test]
+    fn test_sanitize_response_unwraps_code_fences() {
+        let input = "```text\nWe must act now.\n```\nThank you.";
+        assert_eq!(
+            sanitize_response(input, &SanitizeConfig::default()),
+            "We must act now. Thank you."
+        );
+
+        let keep = SanitizeConfig {
+            unwrap_code_fences: false,
+            ..SanitizeConfig::default()
+        };
+        assert_eq!(
+            sanitize_response(input, &keep),
+            "```text We must act now. ``` Thank you."
+        );
+    }
+
     #[test]
     fn test_sanitize_response_multiple_tag_types() {
         let input = "<plan>First plan</plan>Then <reasoning>reason</reasoning> finally the answer.";
-        let output = sanitize_response(input);
+        let output = sanitize_response(input, &SanitizeConfig::default());
         assert_eq!(output, "Then finally the answer.");
     }
 }
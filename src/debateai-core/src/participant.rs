@@ -2,10 +2,11 @@
 //!
 //! Represents individual AI debaters with their configuration.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Role of a participant in the debate.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub enum ParticipantRole {
     /// Arguing in favor of the topic.
     For,
@@ -13,6 +14,8 @@ pub enum ParticipantRole {
     Against,
     /// Neutral or moderating role.
     Neutral,
+    /// A format-specific role such as "Prime Minister" or "Devil's Advocate".
+    Custom(String),
 }
 
 impl ParticipantRole {
@@ -21,12 +24,13 @@ impl ParticipantRole {
             ParticipantRole::For => "FOR",
             ParticipantRole::Against => "AGAINST",
             ParticipantRole::Neutral => "NEUTRAL",
+            ParticipantRole::Custom(label) => label,
         }
     }
 }
 
 /// An AI participant in the debate.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AIParticipant {
     /// Display name for this participant.
     pub name: String,
@@ -38,6 +42,22 @@ pub struct AIParticipant {
     pub custom_system_prompt: Option<String>,
     /// Voice ID for TTS (Phase 2).
     pub voice_id: Option<String>,
+    /// Optional persona appended to the system prompt
+    /// (e.g., "a fiscal conservative economist").
+    pub persona: Option<String>,
+    /// Source documents injected at the start of this participant's history.
+    #[serde(default)]
+    pub context_docs: Vec<String>,
+    /// API base URL for this participant, overriding the debate default.
+    pub api_base: Option<String>,
+    /// API key for this participant, overriding the debate default.
+    /// Never serialized, so keys stay out of saved manifests.
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,
+    /// Retries after an empty response, overriding the debate default.
+    pub max_empty_retries: Option<u32>,
+    /// Retries after a failed API request, overriding the debate default.
+    pub max_api_retries: Option<u32>,
 }
 
 impl AIParticipant {
@@ -49,6 +69,12 @@ impl AIParticipant {
             role,
             custom_system_prompt: None,
             voice_id: None,
+            persona: None,
+            context_docs: Vec::new(),
+            api_base: None,
+            api_key: None,
+            max_empty_retries: None,
+            max_api_retries: None,
         }
     }
 
@@ -64,8 +90,61 @@ impl AIParticipant {
         self
     }
 
+    /// Set the persona this participant argues as.
+    pub fn with_persona(mut self, persona: impl Into<String>) -> Self {
+        self.persona = Some(persona.into());
+        self
+    }
+
+    /// Set source documents to ground this participant's arguments in.
+    pub fn with_context_docs(mut self, docs: Vec<String>) -> Self {
+        self.context_docs = docs;
+        self
+    }
+
+    /// Send this participant's requests to a different API endpoint.
+    pub fn with_endpoint(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = Some(api_base.into());
+        self
+    }
+
+    /// Use a different API key for this participant's requests.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Retry empty responses from this participant a different number of times.
+    pub fn with_empty_retries(mut self, retries: u32) -> Self {
+        self.max_empty_retries = Some(retries);
+        self
+    }
+
+    /// Retry this participant's failed API requests a different number of times.
+    pub fn with_api_retries(mut self, retries: u32) -> Self {
+        self.max_api_retries = Some(retries);
+        self
+    }
+
     /// Get the full display name with role.
     pub fn display_name_with_role(&self) -> String {
         format!("{} ({})", self.name, self.role.display_name())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_role_display_name() {
+        let role = ParticipantRole::Custom("Devil's Advocate".to_string());
+        assert_eq!(role.display_name(), "Devil's Advocate");
+
+        let participant = AIParticipant::new("Alice", "model-a", role);
+        assert_eq!(
+            participant.display_name_with_role(),
+            "Alice (Devil's Advocate)"
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! Minimal HTTP server that streams debates as Server-Sent Events.
+//!
+//! `GET /debate?topic=...&model=...&model=...` runs a debate and streams each
+//! [`DebateEvent`] as an SSE message named after the event type (e.g.
+//! `section_start`), with the event as JSON data. The stream ends, and the
+//! connection closes, when the debate finishes. A failed debate ends with an
+//! `error` event.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+use crate::backend::CompletionBackend;
+use crate::builder::DebateBuilder;
+use crate::debate_format::DEFAULT_ROUNDS;
+use crate::error::DebateError;
+use crate::orchestrator::{DebateEvent, normalize_topic};
+use crate::participant::ParticipantRole;
+
+/// Settings shared by every debate the server runs.
+#[derive(Clone)]
+pub struct ServerConfig {
+    /// OpenAI-compatible API base URL.
+    pub api_base: String,
+    /// API key for authentication.
+    pub api_key: String,
+    /// Number of rounds for each debate.
+    pub rounds: u32,
+    backend: Option<Arc<dyn CompletionBackend>>,
+}
+
+impl ServerConfig {
+    /// Create a server configuration for the given API.
+    pub fn new(api_base: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            api_base: api_base.into(),
+            api_key: api_key.into(),
+            rounds: DEFAULT_ROUNDS,
+            backend: None,
+        }
+    }
+
+    /// Set the number of rounds for each debate.
+    pub fn with_rounds(mut self, rounds: u32) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Use a different completion backend for every debate
+    /// (e.g. a mock for offline runs).
+    pub fn with_backend(mut self, backend: Arc<dyn CompletionBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+}
+
+/// Most bytes read for the request line and headers together.
+const MAX_HEADER_BYTES: u64 = 8192;
+
+/// Accept connections forever, running one debate per request.
+///
+/// A failed accept (e.g. the process is out of file descriptors) is logged
+/// and retried after a short pause rather than stopping the server.
+pub async fn serve(listener: TcpListener, config: ServerConfig) -> Result<(), DebateError> {
+    let config = Arc::new(config);
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                eprintln!("  [Server] Failed to accept a connection: {}", e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let config = config.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &config).await {
+                eprintln!("  [Server] {}", e);
+            }
+        });
+    }
+}
+
+/// A parsed `/debate` request.
+#[derive(Debug, PartialEq)]
+struct DebateRequest {
+    topic: String,
+    models: Vec<String>,
+}
+
+async fn handle_connection(stream: TcpStream, config: &ServerConfig) -> Result<(), DebateError> {
+    let (reader, mut writer) = stream.into_split();
+    let Some(request_line) = read_request_line(reader).await? else {
+        return write_plain(
+            &mut writer,
+            "431 Request Header Fields Too Large",
+            "Request headers are too large",
+        )
+        .await;
+    };
+
+    let request = match parse_request(&request_line) {
+        Ok(request) => request,
+        Err((status, message)) => return write_plain(&mut writer, status, &message).await,
+    };
+
+    let mut builder = DebateBuilder::new(request.topic)
+        .rounds(config.rounds)
+        .api(&config.api_base, &config.api_key);
+    for (i, model) in request.models.iter().enumerate() {
+        let role = if i % 2 == 0 {
+            ParticipantRole::For
+        } else {
+            ParticipantRole::Against
+        };
+        let name = format!("Candidate {}", char::from(b'A' + (i % 26) as u8));
+        builder = builder.participant(name, model, role);
+    }
+    let mut orchestrator = match builder.build() {
+        Ok(orchestrator) => orchestrator,
+        Err(e) => return write_plain(&mut writer, "400 Bad Request", &e.to_string()).await,
+    };
+    if let Some(backend) = &config.backend {
+        orchestrator = orchestrator.with_backend(backend.clone());
+    }
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut orchestrator = orchestrator.with_callback(Box::new(move |event| {
+        let _ = tx.send(event);
+    }));
+    // The sender lives in the orchestrator, so the channel closes when it finishes
+    let debate = tokio::spawn(async move { orchestrator.run().await.map(|_| ()) });
+
+    writer
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+              Cache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+    while let Some(event) = rx.recv().await {
+        if let Err(e) = writer.write_all(sse_message(&event).as_bytes()).await {
+            // The client went away; stop spending tokens on the debate
+            debate.abort();
+            return Err(e.into());
+        }
+    }
+
+    let error = match debate.await {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(e) => Some(e.to_string()),
+    };
+    if let Some(message) = error {
+        let data = serde_json::json!({ "message": message });
+        writer
+            .write_all(format!("event: error\ndata: {}\n\n", data).as_bytes())
+            .await?;
+    }
+    writer.shutdown().await?;
+    Ok(())
+}
+
+/// Read the request line, skipping the headers after it (they are not used).
+///
+/// At most [`MAX_HEADER_BYTES`] are read. `None` if the headers don't end
+/// within that limit.
+async fn read_request_line(reader: impl AsyncRead + Unpin) -> Result<Option<String>, DebateError> {
+    let mut reader = BufReader::new(reader.take(MAX_HEADER_BYTES));
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 {
+            // The client stopped sending, or the limit was reached
+            let truncated = reader.get_ref().limit() == 0;
+            return Ok((!truncated).then_some(request_line));
+        }
+        if header.trim().is_empty() {
+            return Ok(Some(request_line));
+        }
+    }
+}
+
+/// Parse the request line of a `GET /debate` request.
+fn parse_request(request_line: &str) -> Result<DebateRequest, (&'static str, String)> {
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    if path != "/debate" {
+        return Err(("404 Not Found", format!("Unknown path '{}'", path)));
+    }
+    if method != "GET" {
+        return Err(("405 Method Not Allowed", "Use GET".to_string()));
+    }
+
+    let mut topic = String::new();
+    let mut models = Vec::new();
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "topic" => topic = percent_decode(value),
+            "model" => models.push(percent_decode(value)),
+            _ => {}
+        }
+    }
+
+    let topic = normalize_topic(&topic).map_err(|e| ("400 Bad Request", e.to_string()))?;
+    Ok(DebateRequest { topic, models })
+}
+
+/// Decode a URL query component (`+` is a space, `%XX` an encoded byte).
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = |b: u8| char::from(b).to_digit(16);
+                match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                    (Some(high), Some(low)) => {
+                        decoded.push((high * 16 + low) as u8);
+                        i += 2;
+                    }
+                    _ => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Format an event as an SSE message named after its type.
+fn sse_message(event: &DebateEvent) -> String {
+    let data = serde_json::to_value(event).unwrap_or_default();
+    let name = data["type"].as_str().unwrap_or("message");
+    format!("event: {}\ndata: {}\n\n", name, data)
+}
+
+/// Send a plain-text response and close the connection.
+async fn write_plain(
+    writer: &mut (impl AsyncWrite + Unpin),
+    status: &str,
+    message: &str,
+) -> Result<(), DebateError> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        message.len(),
+        message
+    );
+    writer.write_all(response.as_bytes()).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MockBackend;
+
+    #[test]
+    fn test_parse_request() {
+        let request =
+            parse_request("GET /debate?topic=Ban+cars%3F&model=a&model=b%2Fc HTTP/1.1\r\n")
+                .unwrap();
+        assert_eq!(
+            request,
+            DebateRequest {
+                topic: "Ban cars?".to_string(),
+                models: vec!["a".to_string(), "b/c".to_string()],
+            }
+        );
+
+        assert_eq!(
+            parse_request("GET / HTTP/1.1").unwrap_err().0,
+            "404 Not Found"
+        );
+        assert_eq!(
+            parse_request("GET /debate?model=a HTTP/1.1").unwrap_err().0,
+            "400 Bad Request"
+        );
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[tokio::test]
+    async fn test_request_headers_are_capped() {
+        let request = b"GET /debate?topic=x HTTP/1.1\r\nHost: x\r\n\r\n";
+        assert_eq!(
+            read_request_line(&request[..]).await.unwrap().as_deref(),
+            Some("GET /debate?topic=x HTTP/1.1\r\n")
+        );
+
+        let mut oversized = b"GET /debate?topic=x HTTP/1.1\r\nX-Padding: ".to_vec();
+        oversized.extend(vec![b'a'; MAX_HEADER_BYTES as usize]);
+        oversized.extend(b"\r\n\r\n");
+        assert_eq!(read_request_line(&oversized[..]).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_debate_endpoint_streams_events() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let config = ServerConfig::new("http://localhost:1", "")
+            .with_rounds(4)
+            .with_backend(Arc::new(MockBackend::new()));
+        tokio::spawn(serve(listener, config));
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(b"GET /debate?topic=Test+topic&model=a&model=b HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("text/event-stream"));
+        assert_eq!(response.matches("event: section_start\n").count(), 4);
+        assert_eq!(response.matches("event: speaker_message\n").count(), 8);
+        assert!(response.contains("event: speaker_start\ndata: {"));
+        assert!(
+            response
+                .trim_end()
+                .ends_with(r#"data: {"type":"debate_end"}"#)
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Minimal handlebars-style templates for prompts.
+//!
+//! Supports `{{variable}}` substitution and `{{#if variable}} ... {{else}} ... {{/if}}`
+//! blocks, which may be nested. A variable is truthy when it is set, non-empty
+//! and not `"false"`. Unknown variables render as an empty string.
+
+use std::collections::HashMap;
+
+use crate::error::DebateError;
+
+/// A parsed template fragment.
+#[derive(Debug)]
+enum Node<'a> {
+    Text(&'a str),
+    Var(&'a str),
+    If {
+        name: &'a str,
+        then: Vec<Node<'a>>,
+        otherwise: Vec<Node<'a>>,
+    },
+}
+
+/// A `{{ ... }}` tag or the text between tags.
+#[derive(Debug)]
+enum Token<'a> {
+    Text(&'a str),
+    Var(&'a str),
+    If(&'a str),
+    Else,
+    EndIf,
+}
+
+/// Render `template` with the given variables.
+pub fn render(template: &str, vars: &HashMap<&str, String>) -> Result<String, DebateError> {
+    let tokens = tokenize(template)?;
+    let mut tokens = tokens.into_iter();
+    let (nodes, end) = parse(&mut tokens)?;
+
+    match end {
+        None => {
+            let mut output = String::with_capacity(template.len());
+            write_nodes(&nodes, vars, &mut output);
+            Ok(output)
+        }
+        Some(Token::Else) => Err(template_error("{{else}} outside of an {{#if}} block")),
+        Some(_) => Err(template_error("{{/if}} without a matching {{#if}}")),
+    }
+}
+
+/// Check that `template` parses, without rendering it.
+pub fn validate(template: &str) -> Result<(), DebateError> {
+    render(template, &HashMap::new()).map(|_| ())
+}
+
+fn template_error(message: &str) -> DebateError {
+    DebateError::ConfigError(format!("Invalid prompt template: {}", message))
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token<'_>>, DebateError> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| template_error("unclosed '{{'"))?
+            + start;
+        let tag = rest[start + 2..end].trim();
+
+        let token = if let Some(name) = tag.strip_prefix("#if") {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(template_error("{{#if}} requires a variable name"));
+            }
+            Token::If(name)
+        } else if tag == "else" {
+            Token::Else
+        } else if tag == "/if" {
+            Token::EndIf
+        } else if tag.is_empty() || tag.starts_with(['#', '/']) {
+            return Err(template_error(&format!(
+                "unsupported tag '{{{{{}}}}}'",
+                tag
+            )));
+        } else {
+            Token::Var(tag)
+        };
+        tokens.push(token);
+        rest = &rest[end + 2..];
+    }
+
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest));
+    }
+
+    Ok(tokens)
+}
+
+/// Parse nodes until the end of input or an `{{else}}`/`{{/if}}`, which is returned.
+fn parse<'a>(
+    tokens: &mut impl Iterator<Item = Token<'a>>,
+) -> Result<(Vec<Node<'a>>, Option<Token<'a>>), DebateError> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Var(name) => nodes.push(Node::Var(name)),
+            Token::If(name) => {
+                let (then, end) = parse(tokens)?;
+                let otherwise = match end {
+                    Some(Token::EndIf) => Vec::new(),
+                    Some(Token::Else) => match parse(tokens)? {
+                        (otherwise, Some(Token::EndIf)) => otherwise,
+                        _ => return Err(template_error("{{#if}} block is missing {{/if}}")),
+                    },
+                    _ => return Err(template_error("{{#if}} block is missing {{/if}}")),
+                };
+                nodes.push(Node::If {
+                    name,
+                    then,
+                    otherwise,
+                });
+            }
+            end @ (Token::Else | Token::EndIf) => return Ok((nodes, Some(end))),
+        }
+    }
+
+    Ok((nodes, None))
+}
+
+fn write_nodes(nodes: &[Node<'_>], vars: &HashMap<&str, String>, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Var(name) => output.push_str(vars.get(name).map_or("", String::as_str)),
+            Node::If {
+                name,
+                then,
+                otherwise,
+            } => {
+                let truthy = vars
+                    .get(name)
+                    .is_some_and(|value| !value.is_empty() && value != "false");
+                write_nodes(if truthy { then } else { otherwise }, vars, output);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(is_for: bool) -> HashMap<&'static str, String> {
+        HashMap::from([
+            ("name", "Alice".to_string()),
+            ("is_for", is_for.to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_render_conditional_for_and_against() {
+        let template =
+            "You are {{name}}. {{#if is_for}}Argue IN FAVOR.{{else}}Argue AGAINST.{{/if}}";
+
+        assert_eq!(
+            render(template, &vars(true)).unwrap(),
+            "You are Alice. Argue IN FAVOR."
+        );
+        assert_eq!(
+            render(template, &vars(false)).unwrap(),
+            "You are Alice. Argue AGAINST."
+        );
+    }
+
+    #[test]
+    fn test_render_nested_blocks_and_unknown_vars() {
+        let template = "{{#if is_for}}{{#if missing}}x{{else}}{{ name }}{{/if}}{{/if}}{{missing}}";
+        assert_eq!(render(template, &vars(true)).unwrap(), "Alice");
+        assert_eq!(render(template, &vars(false)).unwrap(), "");
+    }
+
+    #[test]
+    fn test_invalid_templates_are_errors() {
+        assert!(validate("{{#if is_for}}unclosed").is_err());
+        assert!(validate("stray {{/if}}").is_err());
+        assert!(validate("stray {{else}}").is_err());
+        assert!(validate("{{name").is_err());
+        assert!(validate("{{#each items}}{{/each}}").is_err());
+        assert!(validate("plain {topic} text").is_ok());
+    }
+}
@@ -1,9 +1,11 @@
 //! TTS module for text-to-speech synthesis using kokoro-tiny.
 
 use kokoro_tiny::TtsEngine;
+use regex::Regex;
 use std::path::Path;
+use std::sync::LazyLock;
 
-use crate::config::VoicesConfig;
+use crate::config::{Pronunciations, VoicesConfig};
 use crate::error::DebateError;
 use crate::orchestrator::DebateMessage;
 use crate::participant::ParticipantRole;
@@ -23,8 +25,96 @@ pub struct DebateTts {
     engine: TtsEngine,
     voices: VoicesConfig,
     available_voices: Vec<String>,
+    /// Strip emoji and uncommon symbols before synthesis.
+    clean_text: bool,
+    /// Expand abbreviations and numbers before synthesis.
+    normalize_text: bool,
+    /// Phonetic respellings applied before any other text pass.
+    respelling: Option<Respelling>,
+    /// Matches words to replace with a censor tone.
+    censor_pattern: Option<Regex>,
+    /// Silence inserted between chunks and after each segment.
+    timing: TtsTiming,
+    /// Switch to a matching voice when a message is not in English.
+    auto_language_voice: bool,
 }
 
+/// Silence added around synthesized speech.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TtsTiming {
+    /// Pause after each synthesized chunk, in seconds.
+    pub chunk_pause_secs: f32,
+    /// Silence appended to every segment, in seconds.
+    pub trailing_pad_secs: f32,
+}
+
+impl Default for TtsTiming {
+    /// Padding that prevents kokoro cutting off the end of chunks.
+    fn default() -> Self {
+        Self {
+            chunk_pause_secs: 0.3,
+            trailing_pad_secs: 0.5,
+        }
+    }
+}
+
+impl TtsTiming {
+    /// Near-zero padding for tighter audio, at the risk of clipped endings.
+    pub fn tight() -> Self {
+        Self {
+            chunk_pause_secs: 0.05,
+            trailing_pad_secs: 0.0,
+        }
+    }
+
+    /// Convert a duration in seconds to a sample count.
+    fn samples(secs: f32) -> usize {
+        (secs.max(0.0) * TTS_SAMPLE_RATE as f32).round() as usize
+    }
+}
+
+/// Silence between consecutive speech segments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentGaps {
+    /// Gap between two segments by the same speaker, in seconds.
+    pub same_speaker_secs: f32,
+    /// Gap where the speaker changes, in seconds.
+    pub speaker_change_secs: f32,
+}
+
+impl Default for SegmentGaps {
+    fn default() -> Self {
+        Self {
+            same_speaker_secs: 0.5,
+            speaker_change_secs: 1.0,
+        }
+    }
+}
+
+impl SegmentGaps {
+    /// The gap, in seconds, to leave before the participant at index `next`
+    /// when the one at `previous` spoke last (`None` after the announcer).
+    pub fn between(&self, previous: Option<usize>, next: usize) -> f32 {
+        if previous == Some(next) {
+            self.same_speaker_secs
+        } else {
+            self.speaker_change_secs
+        }
+    }
+}
+
+/// Sample rate of the audio produced by kokoro-tiny.
+const TTS_SAMPLE_RATE: u32 = 24000;
+
+/// Frequency of the censor tone in Hz.
+const CENSOR_TONE_HZ: f32 = 1000.0;
+
+/// Length of the censor tone in seconds.
+const CENSOR_TONE_SECONDS: f32 = 0.4;
+
+/// Typical speaking rate used to estimate how long text takes to say.
+pub const SPEECH_WORDS_PER_MINUTE: f32 = 150.0;
+
 impl DebateTts {
     /// Initialize the TTS engine (downloads model on first run).
     pub async fn new(voices: VoicesConfig) -> Result<Self, DebateError> {
@@ -38,74 +128,139 @@ impl DebateTts {
             engine,
             voices,
             available_voices,
+            clean_text: false,
+            normalize_text: false,
+            respelling: None,
+            censor_pattern: None,
+            timing: TtsTiming::default(),
+            auto_language_voice: false,
         })
     }
 
+    /// Set the silence inserted between chunks and after each segment.
+    pub fn with_timing(mut self, timing: TtsTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Enable or disable emoji/symbol cleaning before synthesis.
+    pub fn with_text_cleaning(mut self, enabled: bool) -> Self {
+        self.clean_text = enabled;
+        self
+    }
+
+    /// Enable or disable abbreviation/number expansion before synthesis.
+    pub fn with_text_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_text = enabled;
+        self
+    }
+
+    /// Respell phrases the engine mispronounces before synthesis.
+    ///
+    /// Phrases match case-insensitively and only as whole words, so a
+    /// respelling for "LLM" leaves "LLMs" unchanged.
+    pub fn with_pronunciations(mut self, pronunciations: &Pronunciations) -> Self {
+        self.respelling = Respelling::new(pronunciations);
+        self
+    }
+
+    /// Replace these words with a tone instead of speaking them.
+    ///
+    /// Words match case-insensitively and only as whole words.
+    pub fn with_censored_words(mut self, words: &[String]) -> Self {
+        self.censor_pattern = censor_pattern(words);
+        self
+    }
+
+    /// Enable or disable switching voices for messages not in English.
+    ///
+    /// When a message's language is detected (see [`detect_language`]) and
+    /// a Kokoro voice for it exists, that voice speaks the message instead.
+    pub fn with_auto_language_voice(mut self, enabled: bool) -> Self {
+        self.auto_language_voice = enabled;
+        self
+    }
+
     /// Get list of available voice IDs.
     pub fn available_voices(&self) -> &[String] {
         &self.available_voices
     }
 
+    /// Re-query the engine for its voices, in case they changed since
+    /// the engine was initialized.
+    pub fn refresh_voices(&mut self) {
+        self.available_voices = self.engine.voices();
+    }
+
     /// Validate that a voice ID exists.
     pub fn validate_voice(&self, voice_id: &str) -> Result<(), DebateError> {
-        if voice_id.is_empty() {
-            return Err(DebateError::TtsError(format!(
-                "Voice ID cannot be empty. Available voices:\n{}",
-                self.format_available_voices()
-            )));
-        }
-
-        if !self.available_voices.contains(&voice_id.to_string()) {
-            return Err(DebateError::TtsError(format!(
-                "Unknown voice '{}'. Available voices:\n{}",
-                voice_id,
-                self.format_available_voices()
-            )));
-        }
-
-        Ok(())
+        check_voice(voice_id, &self.available_voices)
     }
 
-    /// Format available voices for display.
-    fn format_available_voices(&self) -> String {
-        let mut english_voices: Vec<&String> = self
-            .available_voices
-            .iter()
-            .filter(|v| {
-                v.starts_with("af_")
-                    || v.starts_with("am_")
-                    || v.starts_with("bf_")
-                    || v.starts_with("bm_")
-            })
-            .collect();
-        english_voices.sort();
+    /// Validate a voice ID, refreshing the voice list once if it is unknown.
+    fn ensure_voice(&mut self, voice_id: &str) -> Result<(), DebateError> {
+        let engine = &self.engine;
+        validate_or_refresh(&mut self.available_voices, voice_id, || engine.voices())
+    }
 
-        english_voices
-            .iter()
-            .map(|v| format!("  - {}", v))
-            .collect::<Vec<_>>()
-            .join("\n")
+    /// Suggest the available voice closest to a mistyped voice ID.
+    pub fn suggest_voice(&self, input: &str) -> Option<String> {
+        closest_voice(input, &self.available_voices)
     }
 
     /// Validate all configured voices.
     pub fn validate_all_voices(&self) -> Result<(), DebateError> {
-        self.validate_voice(&self.voices.for_voice)?;
-        self.validate_voice(&self.voices.against_voice)?;
-        self.validate_voice(&self.voices.announcer_voice)?;
-        Ok(())
+        check_voices(&self.voices, &self.available_voices)
     }
 
     /// Synthesize text in chunks to handle long text.
     /// Kokoro-tiny has a strict limit on text length, so we split into small chunks.
+    ///
+    /// Text that produces no audio (e.g. only emoji removed by cleaning) is
+    /// an error, so the caller can decide what to put in its place.
     pub fn synthesize(&mut self, text: &str, voice_id: &str) -> Result<Vec<f32>, DebateError> {
         // Validate voice first
-        self.validate_voice(voice_id)?;
+        self.ensure_voice(voice_id)?;
 
-        // Split text into small chunks (kokoro has ~200 char safe limit)
-        let chunks = split_into_chunks(text, 200);
+        let text = self.prepare_text(text);
 
         let mut all_samples = Vec::new();
 
+        // Speak the text between censored words, with a tone in their place
+        let censored: Vec<(usize, usize)> = self
+            .censor_pattern
+            .as_ref()
+            .map(|re| re.find_iter(&text).map(|m| (m.start(), m.end())).collect())
+            .unwrap_or_default();
+        let mut last = 0;
+        for (start, end) in censored {
+            self.synthesize_chunks(&text[last..start], voice_id, &mut all_samples)?;
+            all_samples.extend(generate_tone(
+                CENSOR_TONE_HZ,
+                CENSOR_TONE_SECONDS,
+                TTS_SAMPLE_RATE,
+            ));
+            last = end;
+        }
+        self.synthesize_chunks(&text[last..], voice_id, &mut all_samples)?;
+
+        pad_segment(
+            all_samples,
+            TtsTiming::samples(self.timing.trailing_pad_secs),
+            &text,
+        )
+    }
+
+    /// Synthesize text chunk by chunk, appending the audio to `all_samples`.
+    fn synthesize_chunks(
+        &mut self,
+        text: &str,
+        voice_id: &str,
+        all_samples: &mut Vec<f32>,
+    ) -> Result<(), DebateError> {
+        // Split text into small chunks (kokoro has ~200 char safe limit)
+        let chunks = split_into_chunks(text, 200);
+
         for chunk in chunks {
             if chunk.trim().is_empty() {
                 continue;
@@ -118,14 +273,27 @@ impl DebateTts {
 
             all_samples.extend(samples);
 
-            // Add pause between chunks (0.3 seconds at 24kHz) to prevent cutoff
-            all_samples.extend(vec![0.0; 7200]);
+            // Add pause between chunks to prevent cutoff
+            let pause = TtsTiming::samples(self.timing.chunk_pause_secs);
+            all_samples.extend(vec![0.0; pause]);
         }
 
-        // Add trailing padding (0.5 seconds) at end of entire message to prevent final cutoff
-        all_samples.extend(vec![0.0; 12000]);
+        Ok(())
+    }
 
-        Ok(all_samples)
+    /// Apply the enabled pre-synthesis text passes.
+    fn prepare_text(&self, text: &str) -> String {
+        let mut text = match &self.respelling {
+            Some(respelling) => respelling.apply(text),
+            None => text.to_string(),
+        };
+        if self.normalize_text {
+            text = expand_for_tts(&text);
+        }
+        if self.clean_text {
+            text = clean_for_tts(&text);
+        }
+        text
     }
 
     /// Synthesize an announcer segment.
@@ -140,11 +308,17 @@ impl DebateTts {
         message: &DebateMessage,
         role: &ParticipantRole,
     ) -> Result<Vec<f32>, DebateError> {
-        let voice_id = match role {
-            ParticipantRole::For => self.voices.for_voice.clone(),
-            ParticipantRole::Against => self.voices.against_voice.clone(),
-            ParticipantRole::Neutral => self.voices.announcer_voice.clone(),
-        };
+        let mut voice_id = self.voices.voice_for_role(role).to_string();
+        if self.auto_language_voice {
+            let language = detect_language(&message.content);
+            if let Some(voice) = voice_for_language(language, &voice_id, &self.available_voices) {
+                eprintln!(
+                    "  [TTS] Detected language '{}', using voice {}",
+                    language, voice
+                );
+                voice_id = voice;
+            }
+        }
 
         self.synthesize(&message.content, &voice_id)
     }
@@ -158,14 +332,576 @@ impl DebateTts {
 
     /// Get voice ID for a role.
     pub fn voice_for_role(&self, role: &ParticipantRole) -> &str {
-        match role {
-            ParticipantRole::For => &self.voices.for_voice,
-            ParticipantRole::Against => &self.voices.against_voice,
-            ParticipantRole::Neutral => &self.voices.announcer_voice,
+        self.voices.voice_for_role(role)
+    }
+}
+
+/// Remove emoji and uncommon symbols that the TTS engine mispronounces.
+///
+/// Letters, digits and ordinary punctuation are kept; typographic quotes,
+/// dashes and ellipses are transliterated to their ASCII equivalents.
+pub fn clean_for_tts(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\u{2018}' | '\u{2019}' | '\u{201B}' | '\u{2032}' => result.push('\''),
+            '\u{201C}' | '\u{201D}' | '\u{201F}' | '\u{2033}' => result.push('"'),
+            '\u{2013}' | '\u{2014}' | '\u{2212}' => result.push_str(" - "),
+            '\u{2026}' => result.push_str("..."),
+            c if c.is_alphanumeric() || c.is_whitespace() => result.push(c),
+            '.' | ',' | '!' | '?' | ';' | ':' | '\'' | '"' | '-' | '(' | ')' | '%' | '$' | '&'
+            | '/' => result.push(c),
+            // Emoji, pictographs and other symbols are dropped
+            _ => result.push(' '),
+        }
+    }
+
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Common words used to tell Latin-script languages apart.
+const LANGUAGE_STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "of", "to", "in", "that", "it", "we", "not", "this", "are",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "que", "y", "es", "en", "por", "una", "del", "para",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "des", "que", "une", "pas", "nous", "dans", "du",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "la", "che", "di", "e", "è", "non", "per", "una", "sono", "gli", "del",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "que", "e", "é", "não", "uma", "para", "com", "são", "da", "do",
+        ],
+    ),
+];
+
+/// Guess the language of `text` as an ISO 639-1 code, defaulting to "en".
+///
+/// A lightweight heuristic rather than a real classifier: texts written
+/// mostly in Japanese, Chinese or Devanagari script are identified by
+/// script, and Latin-script texts by counting common words.
+pub fn detect_language(text: &str) -> &'static str {
+    let (mut kana, mut han, mut devanagari, mut letters) = (0, 0, 0, 0);
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        match c {
+            '\u{3040}'..='\u{30FF}' => kana += 1,
+            '\u{4E00}'..='\u{9FFF}' => han += 1,
+            '\u{0900}'..='\u{097F}' => devanagari += 1,
+            _ => {}
+        }
+    }
+    if letters == 0 {
+        return "en";
+    }
+    if (kana + han) * 2 > letters {
+        return if kana > 0 { "ja" } else { "zh" };
+    }
+    if devanagari * 2 > letters {
+        return "hi";
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let score = |stopwords: &[&str]| {
+        words
+            .iter()
+            .filter(|w| stopwords.contains(&w.as_str()))
+            .count()
+    };
+    let english = score(LANGUAGE_STOPWORDS[0].1);
+    LANGUAGE_STOPWORDS[1..]
+        .iter()
+        .map(|(code, stopwords)| (*code, score(stopwords)))
+        .filter(|(_, hits)| *hits >= 2 && *hits > english)
+        .max_by_key(|(_, hits)| *hits)
+        .map_or("en", |(code, _)| code)
+}
+
+/// Pick a voice for `language`, preferring the gender of `current`.
+///
+/// Kokoro voice IDs start with a language letter and a gender letter
+/// (e.g. `ef_dora` is a Spanish female voice). Returns `None` for English
+/// or when no voice exists for the language.
+fn voice_for_language(language: &str, current: &str, voices: &[String]) -> Option<String> {
+    let prefix = match language {
+        "es" => 'e',
+        "fr" => 'f',
+        "hi" => 'h',
+        "it" => 'i',
+        "ja" => 'j',
+        "pt" => 'p',
+        "zh" => 'z',
+        _ => return None,
+    };
+    let gender = current.chars().nth(1);
+    let candidates: Vec<&String> = voices.iter().filter(|v| v.starts_with(prefix)).collect();
+
+    candidates
+        .iter()
+        .find(|v| v.chars().nth(1) == gender)
+        .or_else(|| candidates.first())
+        .map(|v| v.to_string())
+}
+
+/// Check that `voice_id` is one of the `available` voices.
+fn check_voice(voice_id: &str, available: &[String]) -> Result<(), DebateError> {
+    if voice_id.is_empty() {
+        return Err(DebateError::TtsError(format!(
+            "Voice ID cannot be empty. Available voices:\n{}",
+            format_available_voices(available)
+        )));
+    }
+
+    if !available.iter().any(|v| v == voice_id) {
+        let suggestion = closest_voice(voice_id, available)
+            .map(|v| format!(" Did you mean '{}'?", v))
+            .unwrap_or_default();
+        return Err(DebateError::TtsError(format!(
+            "Unknown voice '{}'.{} Available voices:\n{}",
+            voice_id,
+            suggestion,
+            format_available_voices(available)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Check that every voice in `voices` is one of the `available` voices.
+pub fn check_voices(voices: &VoicesConfig, available: &[String]) -> Result<(), DebateError> {
+    check_voice(&voices.for_voice, available)?;
+    check_voice(&voices.against_voice, available)?;
+    check_voice(&voices.announcer_voice, available)?;
+    for voice in voices.roles.values() {
+        check_voice(voice, available)?;
+    }
+    Ok(())
+}
+
+/// Check a voice, and if it is unknown, replace `available` with a fresh
+/// list from `query` and check once more.
+fn validate_or_refresh(
+    available: &mut Vec<String>,
+    voice_id: &str,
+    query: impl FnOnce() -> Vec<String>,
+) -> Result<(), DebateError> {
+    match check_voice(voice_id, available) {
+        Err(_) if !voice_id.is_empty() => {
+            *available = query();
+            check_voice(voice_id, available)
+        }
+        result => result,
+    }
+}
+
+/// Format the English voices for display.
+fn format_available_voices(available: &[String]) -> String {
+    let mut english_voices: Vec<&String> = available
+        .iter()
+        .filter(|v| {
+            v.starts_with("af_")
+                || v.starts_with("am_")
+                || v.starts_with("bf_")
+                || v.starts_with("bm_")
+        })
+        .collect();
+    english_voices.sort();
+
+    english_voices
+        .iter()
+        .map(|v| format!("  - {}", v))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Find the voice closest to `input` by edit distance.
+///
+/// Only returns a match within a third of the input's length (minimum 2
+/// edits), so unrelated input gets no suggestion.
+fn closest_voice(input: &str, voices: &[String]) -> Option<String> {
+    let input = input.to_lowercase();
+    let max_distance = (input.chars().count() / 3).max(2);
+
+    voices
+        .iter()
+        .map(|voice| (edit_distance(&input, &voice.to_lowercase()), voice))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, voice)| voice.clone())
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Abbreviations expanded by [`expand_for_tts`], as (pattern, replacement).
+const TTS_ABBREVIATIONS: &[(&str, &str)] = &[
+    (r"\bDr\.", "Doctor"),
+    (r"\bMr\.", "Mister"),
+    (r"\bMrs\.", "Missus"),
+    (r"\bProf\.", "Professor"),
+    (r"\bSen\.", "Senator"),
+    (r"\bGov\.", "Governor"),
+    (r"\bvs\.?(\s)", "versus$1"),
+    (r"\be\.g\.", "for example"),
+    (r"\bi\.e\.", "that is"),
+    (r"\betc\b", "et cetera"),
+    (r"\bapprox\.", "approximately"),
+];
+
+/// [`TTS_ABBREVIATIONS`], compiled.
+static ABBREVIATION_RES: LazyLock<Vec<(Regex, &str)>> = LazyLock::new(|| {
+    TTS_ABBREVIATIONS
+        .iter()
+        .map(|(pattern, replacement)| {
+            let re = Regex::new(pattern).expect("valid abbreviation pattern");
+            (re, *replacement)
+        })
+        .collect()
+});
+
+/// A number: digits with optional thousands separators in groups of three
+/// ("1,250"), then optional decimals. "1,2,3" is three numbers, not one.
+const NUMBER_PATTERN: &str = r"(?:\d{1,3}(?:,\d{3})+|\d+)(?:\.\d+)?";
+
+/// Dollar amounts with an optional scale word or suffix ("$5M").
+static CURRENCY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"\$({})(?:\s?(trillion|billion|million|thousand)\b|(tn|bn|[TBMKk])\b)?",
+        NUMBER_PATTERN
+    ))
+    .expect("valid currency pattern")
+});
+
+/// Percentages ("12%").
+static PERCENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"\b({})%", NUMBER_PATTERN)).expect("valid percent pattern")
+});
+
+/// Standalone numbers.
+static NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"\b{}\b", NUMBER_PATTERN)).expect("valid number pattern")
+});
+
+/// Expand common abbreviations, currency, percentages and numbers to words.
+///
+/// The rules are deliberately conservative:
+/// - Titles and Latin abbreviations ("Dr.", "e.g.") are spelled out.
+/// - Dollar amounts with an optional scale ("$5M", "$2.5 billion") are read
+///   as "five million dollars".
+/// - Percentages are read as "five percent".
+/// - Standalone four-digit numbers from 1100 to 2099 are read as years:
+///   "2025" becomes "twenty twenty-five", "2005" becomes "two thousand five"
+///   and "1900" becomes "nineteen hundred".
+/// - Any other standalone number is read as a cardinal, with decimals read
+///   digit by digit after "point".
+pub fn expand_for_tts(text: &str) -> String {
+    let mut result = text.to_string();
+
+    for (re, replacement) in ABBREVIATION_RES.iter() {
+        result = re.replace_all(&result, *replacement).to_string();
+    }
+
+    result = CURRENCY_RE
+        .replace_all(&result, |caps: &regex::Captures| {
+            let amount = number_to_words(&caps[1]);
+            let scale = match (caps.get(2), caps.get(3).map(|m| m.as_str())) {
+                (Some(word), _) => Some(word.as_str()),
+                (None, Some("tn" | "T")) => Some("trillion"),
+                (None, Some("bn" | "B")) => Some("billion"),
+                (None, Some("M")) => Some("million"),
+                (None, Some("K" | "k")) => Some("thousand"),
+                _ => None,
+            };
+            match scale {
+                Some(scale) => format!("{} {} dollars", amount, scale),
+                None if &caps[1] == "1" => "one dollar".to_string(),
+                None => format!("{} dollars", amount),
+            }
+        })
+        .to_string();
+
+    result = PERCENT_RE
+        .replace_all(&result, |caps: &regex::Captures| {
+            format!("{} percent", number_to_words(&caps[1]))
+        })
+        .to_string();
+
+    result = NUMBER_RE
+        .replace_all(&result, |caps: &regex::Captures| {
+            let digits = &caps[0];
+            match digits.parse::<u64>() {
+                Ok(year) if digits.len() == 4 && (1100..=2099).contains(&year) => {
+                    year_to_words(year)
+                }
+                _ => number_to_words(digits),
+            }
+        })
+        .to_string();
+
+    result
+}
+
+/// Read a (possibly comma-grouped, possibly decimal) number as words.
+fn number_to_words(number: &str) -> String {
+    let number = number.replace(',', "");
+    let (integer, fraction) = match number.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (number.as_str(), None),
+    };
+
+    let mut words = match integer.parse::<u64>() {
+        Ok(n) => cardinal_to_words(n),
+        // Too large to read sensibly; leave it as digits
+        Err(_) => return number.to_string(),
+    };
+
+    if let Some(fraction) = fraction {
+        words.push_str(" point");
+        for digit in fraction.chars().filter_map(|c| c.to_digit(10)) {
+            words.push(' ');
+            words.push_str(ONES[digit as usize]);
+        }
+    }
+
+    words
+}
+
+/// Read a year in the conventional spoken style.
+fn year_to_words(year: u64) -> String {
+    let (high, low) = (year / 100, year % 100);
+    if (2000..2010).contains(&year) {
+        cardinal_to_words(year)
+    } else if low == 0 {
+        format!("{} hundred", cardinal_to_words(high))
+    } else if low < 10 {
+        format!("{} oh {}", cardinal_to_words(high), ONES[low as usize])
+    } else {
+        format!("{} {}", cardinal_to_words(high), cardinal_to_words(low))
+    }
+}
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Convert an integer to English words (e.g. 1205 -> "one thousand two hundred five").
+fn cardinal_to_words(n: u64) -> String {
+    const SCALES: [(u64, &str); 5] = [
+        (1_000_000_000_000_000, "quadrillion"),
+        (1_000_000_000_000, "trillion"),
+        (1_000_000_000, "billion"),
+        (1_000_000, "million"),
+        (1_000, "thousand"),
+    ];
+
+    if n < 20 {
+        return ONES[n as usize].to_string();
+    }
+    if n < 100 {
+        let tens = TENS[(n / 10) as usize];
+        return match n % 10 {
+            0 => tens.to_string(),
+            ones => format!("{}-{}", tens, ONES[ones as usize]),
+        };
+    }
+    if n < 1000 {
+        let hundreds = format!("{} hundred", ONES[(n / 100) as usize]);
+        return match n % 100 {
+            0 => hundreds,
+            rest => format!("{} {}", hundreds, cardinal_to_words(rest)),
+        };
+    }
+
+    let mut parts = Vec::new();
+    let mut rest = n;
+    for (value, name) in SCALES {
+        if rest >= value {
+            parts.push(format!("{} {}", cardinal_to_words(rest / value), name));
+            rest %= value;
+        }
+    }
+    if rest > 0 {
+        parts.push(cardinal_to_words(rest));
+    }
+    parts.join(" ")
+}
+
+/// Append trailing padding to the audio synthesized for `text`.
+///
+/// Fails if the text produced no audio at all.
+fn pad_segment(
+    mut samples: Vec<f32>,
+    trailing_samples: usize,
+    text: &str,
+) -> Result<Vec<f32>, DebateError> {
+    if samples.is_empty() {
+        return Err(DebateError::TtsError(format!(
+            "No audio produced for {:?}",
+            text.chars().take(40).collect::<String>()
+        )));
+    }
+    samples.extend(vec![0.0; trailing_samples]);
+    Ok(samples)
+}
+
+/// Compiled whole-word, case-insensitive phrase replacements.
+struct Respelling {
+    /// One capture group per phrase, in the order of `replacements`.
+    pattern: Regex,
+    replacements: Vec<String>,
+}
+
+impl Respelling {
+    /// Compile the respellings. `None` if there are none.
+    fn new(pronunciations: &Pronunciations) -> Option<Self> {
+        let mut phrases: Vec<(&str, &str)> = pronunciations
+            .iter()
+            .map(|(phrase, respelling)| (phrase.trim(), respelling))
+            .filter(|(phrase, _)| !phrase.is_empty())
+            .collect();
+        if phrases.is_empty() {
+            return None;
         }
+
+        // Longest first, so "New York City" wins over "New York"
+        phrases.sort_by_key(|(p, _)| std::cmp::Reverse(p.len()));
+        let alternatives: Vec<String> = phrases
+            .iter()
+            .map(|(p, _)| format!("({})", regex::escape(p)))
+            .collect();
+
+        // The matched group, not the matched text, picks the respelling:
+        // case folding can match forms (e.g. Greek final sigma) that
+        // lowercasing would not map back to the phrase
+        let pattern = Regex::new(&format!(r"(?i)\b(?:{})\b", alternatives.join("|"))).ok()?;
+        Some(Self {
+            pattern,
+            replacements: phrases.iter().map(|(_, r)| r.to_string()).collect(),
+        })
+    }
+
+    fn apply(&self, text: &str) -> String {
+        self.pattern
+            .replace_all(text, |caps: &regex::Captures| {
+                (1..caps.len())
+                    .find(|&i| caps.get(i).is_some())
+                    .and_then(|i| self.replacements.get(i - 1))
+                    .cloned()
+                    .unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned()
     }
 }
 
+/// Build a case-insensitive whole-word pattern for the censored words.
+fn censor_pattern(words: &[String]) -> Option<Regex> {
+    let alternatives: Vec<String> = words
+        .iter()
+        .map(|w| w.trim())
+        .filter(|w| !w.is_empty())
+        .map(regex::escape)
+        .collect();
+    if alternatives.is_empty() {
+        return None;
+    }
+
+    Regex::new(&format!(r"(?i)\b(?:{})\b", alternatives.join("|"))).ok()
+}
+
+/// Generate a sine tone, e.g. to bleep out a censored word.
+///
+/// The tone fades in and out over a few milliseconds to avoid clicks.
+pub fn generate_tone(freq: f32, duration_secs: f32, sample_rate: u32) -> Vec<f32> {
+    let len = (duration_secs.max(0.0) * sample_rate as f32) as usize;
+    let fade = (sample_rate as usize / 200).min(len / 2).max(1);
+    let amplitude = 0.3;
+
+    (0..len)
+        .map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            let envelope = (i.min(len - 1 - i) as f32 / fade as f32).min(1.0);
+            amplitude * envelope * (2.0 * std::f32::consts::PI * freq * t).sin()
+        })
+        .collect()
+}
+
+/// Estimate how many seconds it takes to speak `text` at `wpm` words per minute.
+pub fn estimate_speech_seconds(text: &str, wpm: f32) -> f32 {
+    if wpm <= 0.0 {
+        return 0.0;
+    }
+    text.split_whitespace().count() as f32 * 60.0 / wpm
+}
+
+/// Estimate how many samples it takes to speak `text` at `wpm` words per
+/// minute (see [`estimate_speech_seconds`]).
+///
+/// Used to keep the audio timeline roughly aligned when a segment fails to
+/// synthesize and is replaced by silence.
+pub fn estimate_speech_samples(text: &str, wpm: f32, sample_rate: u32) -> usize {
+    (estimate_speech_seconds(text, wpm) * sample_rate as f32).round() as usize
+}
+
 /// Split text into chunks that are safe for TTS synthesis.
 fn split_into_chunks(text: &str, max_chars: usize) -> Vec<String> {
     let mut chunks = Vec::new();
@@ -220,12 +956,29 @@ pub fn adjust_audio_speed(samples: Vec<f32>, rate: f32) -> Vec<f32> {
         return samples; // No change needed
     }
 
-    // Calculate new length (slower = longer)
-    let new_len = (samples.len() as f32 / rate) as usize;
+    interpolate(&samples, rate)
+}
+
+/// Resample audio from one sample rate to another.
+///
+/// Uses the same linear interpolation as [`adjust_audio_speed`], so pitch and
+/// duration are preserved while the number of samples changes.
+pub fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || from_rate == 0 || to_rate == 0 {
+        return samples.to_vec();
+    }
+
+    interpolate(samples, from_rate as f32 / to_rate as f32)
+}
+
+/// Linearly interpolate `samples`, advancing `step` source samples per output sample.
+fn interpolate(samples: &[f32], step: f32) -> Vec<f32> {
+    // Calculate new length (smaller step = longer)
+    let new_len = (samples.len() as f32 / step) as usize;
     let mut result = Vec::with_capacity(new_len);
 
     for i in 0..new_len {
-        let src_pos = i as f32 * rate;
+        let src_pos = i as f32 * step;
         let src_idx = src_pos as usize;
         let frac = src_pos - src_idx as f32;
 
@@ -262,6 +1015,173 @@ pub fn combine_audio_segments(
     combined
 }
 
+/// Mix `background`, attenuated by `bg_gain`, under `foreground`, starting
+/// from sample `offset` of the background.
+///
+/// The result has the foreground's length: the background is looped if it is
+/// shorter and cut off if it is longer. An empty background leaves the
+/// foreground unchanged.
+pub fn mix_background(
+    foreground: &[f32],
+    background: &[f32],
+    offset: usize,
+    bg_gain: f32,
+) -> Vec<f32> {
+    if background.is_empty() {
+        return foreground.to_vec();
+    }
+
+    foreground
+        .iter()
+        .zip(background.iter().cycle().skip(offset % background.len()))
+        .map(|(fg, bg)| fg + bg * bg_gain)
+        .collect()
+}
+
+/// Read a WAV file as mono float samples, averaging the channels.
+///
+/// Returns the samples and the file's sample rate.
+pub fn read_wav_mono<P: AsRef<Path>>(path: P) -> Result<(Vec<f32>, u32), DebateError> {
+    let wav_error = |e: hound::Error| {
+        DebateError::TtsError(format!(
+            "Failed to read WAV {}: {}",
+            path.as_ref().display(),
+            e
+        ))
+    };
+    let mut reader = hound::WavReader::open(path.as_ref()).map_err(wav_error)?;
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(wav_error)?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(wav_error)?
+        }
+    };
+
+    let channels = usize::from(spec.channels.max(1));
+    let mono = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    Ok((mono, spec.sample_rate))
+}
+
+/// A running cap on audio length, spent segment by segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioBudget {
+    remaining: usize,
+}
+
+impl AudioBudget {
+    /// A budget of `max_seconds` of audio at `sample_rate`.
+    pub fn new(max_seconds: f32, sample_rate: u32) -> Self {
+        Self {
+            remaining: (max_seconds.max(0.0) as f64 * sample_rate as f64) as usize,
+        }
+    }
+
+    /// Spend `samples` from the budget.
+    ///
+    /// Returns false, leaving the budget unchanged, if the segment would
+    /// cross the limit.
+    pub fn take(&mut self, samples: usize) -> bool {
+        match self.remaining.checked_sub(samples) {
+            Some(remaining) => {
+                self.remaining = remaining;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A 16-bit mono PCM WAV file written one segment at a time, so the
+/// combined audio is never held in memory.
+pub struct WavStreamWriter {
+    writer: hound::WavWriter<std::io::BufWriter<std::fs::File>>,
+    written: usize,
+}
+
+impl WavStreamWriter {
+    /// Create (or truncate) the WAV file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P, sample_rate: u32) -> Result<Self, DebateError> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer = hound::WavWriter::create(path.as_ref(), spec).map_err(wav_write_error)?;
+        Ok(Self { writer, written: 0 })
+    }
+
+    /// Append a segment of samples.
+    pub fn write(&mut self, samples: &[f32]) -> Result<(), DebateError> {
+        for sample in samples {
+            self.writer
+                .write_sample(f32_to_i16(*sample))
+                .map_err(wav_write_error)?;
+        }
+        self.written += samples.len();
+        Ok(())
+    }
+
+    /// Number of samples written so far.
+    pub fn samples_written(&self) -> usize {
+        self.written
+    }
+
+    /// Finish the file header. Returns the total number of samples written.
+    pub fn finalize(self) -> Result<usize, DebateError> {
+        self.writer.finalize().map_err(wav_write_error)?;
+        Ok(self.written)
+    }
+}
+
+fn wav_write_error(e: hound::Error) -> DebateError {
+    DebateError::TtsError(format!("Failed to write WAV: {}", e))
+}
+
+/// Write audio segments to a WAV file one at a time, separated by silence.
+///
+/// Produces the same audio as [`combine_audio_segments`] followed by a save,
+/// without ever holding the combined buffer in memory. Samples are written
+/// as 16-bit mono PCM. Returns the total number of samples written.
+pub fn write_wav_streaming<P, I>(
+    path: P,
+    segments: I,
+    gap_samples: usize,
+    sample_rate: u32,
+) -> Result<usize, DebateError>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = Vec<f32>>,
+{
+    let mut writer = WavStreamWriter::create(path, sample_rate)?;
+    let gap = vec![0.0; gap_samples];
+    for (i, segment) in segments.into_iter().enumerate() {
+        if i > 0 {
+            writer.write(&gap)?;
+        }
+        writer.write(&segment)?;
+    }
+    writer.finalize()
+}
+
+/// Convert a float sample in [-1.0, 1.0] to 16-bit PCM.
+fn f32_to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
 /// Generate filename for debate output.
 pub fn generate_output_filename(topic: &str) -> String {
     // Sanitize topic for filename
@@ -315,6 +1235,324 @@ mod tests {
         assert_eq!(combined[2], 0.0); // gap sample
     }
 
+    #[test]
+    fn test_gap_depends_on_speaker_change() {
+        let gaps = SegmentGaps {
+            same_speaker_secs: 0.2,
+            speaker_change_secs: 0.5,
+        };
+        assert_eq!(gaps.between(None, 0), 0.5);
+        assert_eq!(gaps.between(Some(0), 0), 0.2);
+        assert_eq!(gaps.between(Some(0), 1), 0.5);
+    }
+
+    #[test]
+    fn test_clean_for_tts_strips_emoji() {
+        assert_eq!(
+            clean_for_tts("We will win 🎉🇺🇸 this debate! 👍"),
+            "We will win this debate!"
+        );
+    }
+
+    #[test]
+    fn test_clean_for_tts_keeps_punctuation() {
+        let input = "Costs rose 5% (roughly $3, or more); why? Because: it's true - really.";
+        assert_eq!(clean_for_tts(input), input);
+        assert_eq!(
+            clean_for_tts("\u{201C}Fair\u{201D}\u{2014}isn\u{2019}t it\u{2026}"),
+            "\"Fair\" - isn't it..."
+        );
+    }
+
+    #[test]
+    fn test_expand_for_tts_abbreviations() {
+        assert_eq!(
+            expand_for_tts("Dr. Smith vs. Mr. Jones"),
+            "Doctor Smith versus Mister Jones"
+        );
+    }
+
+    #[test]
+    fn test_expand_for_tts_years() {
+        assert_eq!(expand_for_tts("by 2025"), "by twenty twenty-five");
+        assert_eq!(expand_for_tts("in 2005"), "in two thousand five");
+        assert_eq!(expand_for_tts("since 1900"), "since nineteen hundred");
+    }
+
+    #[test]
+    fn test_expand_for_tts_currency_and_numbers() {
+        assert_eq!(
+            expand_for_tts("$5M by 2025"),
+            "five million dollars by twenty twenty-five"
+        );
+        assert_eq!(
+            expand_for_tts("$2.5 billion"),
+            "two point five billion dollars"
+        );
+        assert_eq!(expand_for_tts("up 12%"), "up twelve percent");
+        assert_eq!(
+            expand_for_tts("1,250 people"),
+            "one thousand two hundred fifty people"
+        );
+        assert_eq!(expand_for_tts("GPT4 stays"), "GPT4 stays");
+        // Commas only group digits in threes
+        assert_eq!(expand_for_tts("steps 1,2,3"), "steps one,two,three");
+        assert_eq!(expand_for_tts("$1,000,000"), "one million dollars");
+    }
+
+    #[test]
+    fn test_closest_voice_suggests_typo_fix() {
+        let voices: Vec<String> = ["af_sky", "bf_emma", "bm_fable", "bm_george"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+
+        assert_eq!(
+            closest_voice("bf_ema", &voices),
+            Some("bf_emma".to_string())
+        );
+        assert_eq!(
+            closest_voice("BM_GEORGE", &voices),
+            Some("bm_george".to_string())
+        );
+        assert_eq!(closest_voice("zz_nothing_like_it", &voices), None);
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language("We believe that the policy is not in the public interest."),
+            "en"
+        );
+        assert_eq!(
+            detect_language("Creemos que la política no es buena para los ciudadanos."),
+            "es"
+        );
+        assert_eq!(
+            detect_language("Nous pensons que cette loi est une erreur dans les faits."),
+            "fr"
+        );
+        assert_eq!(detect_language("私たちはこの政策に反対します。"), "ja");
+        assert_eq!(detect_language("我们反对这项政策。"), "zh");
+        assert_eq!(detect_language("हम इस नीति का विरोध करते हैं।"), "hi");
+        assert_eq!(detect_language("42!"), "en");
+    }
+
+    #[test]
+    fn test_voice_for_language_prefers_same_gender() {
+        let voices: Vec<String> = ["af_heart", "am_adam", "ef_dora", "em_alex", "ff_siwis"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+
+        assert_eq!(
+            voice_for_language("es", "am_adam", &voices),
+            Some("em_alex".to_string())
+        );
+        assert_eq!(
+            voice_for_language("fr", "am_adam", &voices),
+            Some("ff_siwis".to_string())
+        );
+        assert_eq!(voice_for_language("ja", "am_adam", &voices), None);
+        assert_eq!(voice_for_language("en", "am_adam", &voices), None);
+    }
+
+    #[test]
+    fn test_unknown_voice_found_after_refresh() {
+        let mut available = vec!["af_sky".to_string()];
+        let refreshed = vec!["af_sky".to_string(), "af_new".to_string()];
+
+        assert!(check_voice("af_new", &available).is_err());
+        validate_or_refresh(&mut available, "af_new", || refreshed.clone()).unwrap();
+        assert_eq!(available, refreshed);
+
+        // Known voices do not trigger a refresh
+        validate_or_refresh(&mut available, "af_sky", || panic!("refreshed")).unwrap();
+
+        let error = validate_or_refresh(&mut available, "af_gone", Vec::new).unwrap_err();
+        assert!(error.to_string().contains("Unknown voice 'af_gone'"));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_write_wav_streaming_matches_combine() {
+        let segments = vec![vec![0.5, -0.5, 0.25], vec![1.0], vec![-1.0, 0.0]];
+        let combined = combine_audio_segments(segments.clone(), 0.2, 10); // 2 sample gaps
+
+        let path =
+            std::env::temp_dir().join(format!("debateai-stream-test-{}.wav", std::process::id()));
+        let written = write_wav_streaming(&path, segments, 2, 10).unwrap();
+
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().sample_rate, 10);
+        let samples: Vec<i16> = reader.samples::<i16>().map(|s| s.unwrap()).collect();
+        std::fs::remove_file(&path).unwrap();
+
+        let expected: Vec<i16> = combined.iter().map(|s| f32_to_i16(*s)).collect();
+        assert_eq!(written, combined.len());
+        assert_eq!(samples, expected);
+    }
+
+    #[test]
+    fn test_mix_background_loops_and_attenuates() {
+        let foreground = [0.5, -0.5, 0.25, 0.0, 0.1];
+        let background = [0.2, -0.4];
+
+        let mixed = mix_background(&foreground, &background, 0, 0.5);
+        let expected = [0.6, -0.7, 0.35, -0.2, 0.2];
+        assert_eq!(mixed.len(), foreground.len());
+        for (got, want) in mixed.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-6, "{} != {}", got, want);
+        }
+
+        // A longer background is cut to the foreground's length
+        let long: Vec<f32> = vec![1.0; 100];
+        assert_eq!(mix_background(&foreground[..2], &long, 0, 0.5), [1.0, 0.0]);
+        assert_eq!(mix_background(&foreground, &[], 3, 0.5), foreground);
+
+        // An offset picks up the loop where the previous segment left off
+        let offset = mix_background(&[0.0; 3], &background, 3, 1.0);
+        assert_eq!(offset, [-0.4, 0.2, -0.4]);
+    }
+
+    #[test]
+    fn test_resample_linear() {
+        let samples: Vec<f32> = (0..2400).map(|i| (i as f32 * 0.01).sin()).collect();
+
+        let upsampled = resample_linear(&samples, 24000, 48000);
+        assert!((upsampled.len() as i64 - 4800).abs() <= 2);
+        // Every other output sample lines up with an input sample
+        assert_eq!(upsampled[100], samples[50]);
+
+        let downsampled = resample_linear(&samples, 24000, 16000);
+        assert!((downsampled.len() as i64 - 1600).abs() <= 2);
+
+        assert_eq!(resample_linear(&samples, 24000, 24000), samples);
+    }
+
+    #[test]
+    fn test_empty_synthesis_is_an_error() {
+        let err = pad_segment(Vec::new(), 0, "🎉🎉").unwrap_err();
+        assert!(matches!(err, DebateError::TtsError(msg) if msg.contains("No audio produced")));
+
+        let trailing = TtsTiming::samples(TtsTiming::default().trailing_pad_secs);
+        assert_eq!(trailing, 12000);
+        assert_eq!(
+            pad_segment(vec![0.5; 10], trailing, "a").unwrap().len(),
+            10 + trailing
+        );
+    }
+
+    #[test]
+    fn test_zero_padding_omits_trailing_silence() {
+        let timing = TtsTiming::tight();
+        let segment = pad_segment(
+            vec![0.5; 10],
+            TtsTiming::samples(timing.trailing_pad_secs),
+            "a",
+        )
+        .unwrap();
+        assert_eq!(segment, vec![0.5; 10]);
+        assert_eq!(
+            TtsTiming::samples(TtsTiming::default().chunk_pause_secs),
+            7200
+        );
+    }
+
+    #[test]
+    fn test_audio_budget_stops_at_segment_boundary() {
+        // 4.5s at 10 Hz: 1s, 2s and 1s segments fit, a further 3s does not
+        let mut budget = AudioBudget::new(4.5, 10);
+        assert!(budget.take(10));
+        assert!(budget.take(20));
+        assert!(budget.take(10));
+        assert!(!budget.take(30));
+        // A refused segment leaves the remainder for a shorter one
+        assert!(budget.take(5));
+        assert!(!budget.take(1));
+
+        // A limit landing exactly on a boundary keeps that segment
+        let mut budget = AudioBudget::new(3.0, 10);
+        assert!(budget.take(10));
+        assert!(budget.take(20));
+        assert!(!budget.take(1));
+    }
+
+    #[test]
+    fn test_estimate_speech_seconds() {
+        let text = "word ".repeat(300);
+        assert_eq!(estimate_speech_seconds(&text, 150.0), 120.0);
+        assert_eq!(estimate_speech_seconds("one two three", 180.0), 1.0);
+        assert_eq!(estimate_speech_seconds("one two three", 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_estimate_speech_samples() {
+        assert_eq!(estimate_speech_samples("", 150.0, 24000), 0);
+        // 150 words at 150 wpm is one minute
+        let text = "word ".repeat(150);
+        assert_eq!(estimate_speech_samples(&text, 150.0, 24000), 60 * 24000);
+        assert_eq!(estimate_speech_samples(&text, 300.0, 24000), 30 * 24000);
+        assert_eq!(
+            estimate_speech_samples("five short words right here", 150.0, 1000),
+            2000
+        );
+    }
+
+    #[test]
+    fn test_respelling_matches_whole_words() {
+        let pronunciations: Pronunciations = [
+            ("LLM".to_string(), "el el em".to_string()),
+            ("Qwen".to_string(), "chwen".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let respelling = Respelling::new(&pronunciations).unwrap();
+
+        assert_eq!(
+            respelling.apply("An LLM, like qwen, is an llm."),
+            "An el el em, like chwen, is an el el em."
+        );
+        assert_eq!(respelling.apply("LLMs and MyLLM"), "LLMs and MyLLM");
+        assert!(Respelling::new(&Pronunciations::default()).is_none());
+
+        // Case folding treats σ, ς and Σ alike, though lowercasing does not
+        let greek: Pronunciations = [("ΛΟΓΟΣ".to_string(), "logos".to_string())]
+            .into_iter()
+            .collect();
+        let respelling = Respelling::new(&greek).unwrap();
+        assert_eq!(respelling.apply("ὁ λογοσ, λογος"), "ὁ logos, logos");
+    }
+
+    #[test]
+    fn test_generate_tone() {
+        let tone = generate_tone(1000.0, 0.5, 24000);
+        assert_eq!(tone.len(), 12000);
+        assert!(tone.iter().all(|s| s.abs() <= 1.0));
+        let peak = tone.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+        assert!(peak > 0.1, "tone should be audible, peak was {}", peak);
+
+        assert!(generate_tone(1000.0, 0.0, 24000).is_empty());
+    }
+
+    #[test]
+    fn test_censor_pattern_matches_whole_words() {
+        let re = censor_pattern(&["darn".to_string(), " ".to_string()]).unwrap();
+        let matches: Vec<_> = re
+            .find_iter("Darn it, darnation! DARN.")
+            .map(|m| m.as_str())
+            .collect();
+        assert_eq!(matches, ["Darn", "DARN"]);
+
+        assert!(censor_pattern(&[]).is_none());
+    }
+
     #[test]
     fn test_split_into_chunks() {
         let text = "Hello world. This is a test. Another sentence here.";
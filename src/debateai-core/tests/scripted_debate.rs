@@ -0,0 +1,106 @@
+//! Full orchestration loop against a scripted backend.
+
+use std::sync::Arc;
+
+use debateai_core::{
+    AIParticipant, DebateConfig, DebateOrchestrator, ParticipantRole, PresidentialDebateFormat,
+    ScriptedBackend,
+};
+
+#[tokio::test]
+async fn test_presidential_debate_follows_script() {
+    let backend = Arc::new(
+        ScriptedBackend::new()
+            .with_responses(
+                0,
+                [
+                    "Alice opening statement.",
+                    "Alice main argument one.",
+                    "Alice rebuttal to Bob.",
+                    "Alice closing statement.",
+                ],
+            )
+            .with_responses(
+                1,
+                [
+                    "Bob opening statement.",
+                    "Bob main argument one.",
+                    "Bob rebuttal to Alice.",
+                    "Bob closing statement.",
+                ],
+            ),
+    );
+
+    let participants = vec![
+        AIParticipant::new("Alice", "model-a", ParticipantRole::For),
+        AIParticipant::new("Bob", "model-b", ParticipantRole::Against),
+    ];
+    let config = DebateConfig::new("Scripted topic", "http://localhost:1", "");
+    let mut orchestrator = DebateOrchestrator::new(
+        config,
+        participants,
+        Box::new(PresidentialDebateFormat::new(4)),
+    )
+    .unwrap()
+    .with_backend(backend.clone());
+
+    let transcript = orchestrator.run().await.unwrap();
+
+    let actual: Vec<(&str, usize, &str, &str)> = transcript
+        .iter()
+        .map(|m| {
+            (
+                m.section.as_str(),
+                m.speaker_index.expect("only participants speak"),
+                m.speaker_name.as_str(),
+                m.content.as_str(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        actual,
+        vec![
+            ("Opening Statements", 0, "Alice", "Alice opening statement."),
+            ("Opening Statements", 1, "Bob", "Bob opening statement."),
+            (
+                "Main Arguments - Round 1",
+                0,
+                "Alice",
+                "Alice main argument one."
+            ),
+            (
+                "Main Arguments - Round 1",
+                1,
+                "Bob",
+                "Bob main argument one."
+            ),
+            ("Rebuttals", 1, "Bob", "Bob rebuttal to Alice."),
+            ("Rebuttals", 0, "Alice", "Alice rebuttal to Bob."),
+            ("Closing Statements", 0, "Alice", "Alice closing statement."),
+            ("Closing Statements", 1, "Bob", "Bob closing statement."),
+        ]
+    );
+    assert_eq!(backend.remaining(0), 0);
+    assert_eq!(backend.remaining(1), 0);
+}
+
+#[tokio::test]
+async fn test_exhausted_script_is_an_error() {
+    let backend = Arc::new(ScriptedBackend::new().with_responses(0, ["Only one response here."]));
+
+    let participants = vec![
+        AIParticipant::new("Alice", "model-a", ParticipantRole::For),
+        AIParticipant::new("Bob", "model-b", ParticipantRole::Against),
+    ];
+    let config = DebateConfig::new("Scripted topic", "http://localhost:1", "");
+    let mut orchestrator = DebateOrchestrator::new(
+        config,
+        participants,
+        Box::new(PresidentialDebateFormat::new(4)),
+    )
+    .unwrap()
+    .with_backend(backend);
+
+    assert!(orchestrator.run().await.is_err());
+}